@@ -0,0 +1,236 @@
+use crate::input::{self, line, Records};
+use crate::{Error, Result};
+use numpr::formats::{Format, Record};
+use numpr::generators::SeededGenerator;
+use numpr::rating::{self, Difficulty};
+use numpr::render::{Booklet, PageSize, Renderer, Scene};
+use numpr::solvers::BitParallelSolver;
+use numpr::{canonical, Board, NumprError, Puzzle, Solver, Symmetry};
+use serde_json::json;
+use std::io::Write;
+
+/// Returns whether `board` has no solution, exactly one, or more.
+fn check(board: &Board) -> Result<std::result::Result<Board, NumprError>> {
+    Ok(match BitParallelSolver::new().count(board, 2)? {
+        0 => Err(NumprError::Unsolvable),
+        1 => Ok(BitParallelSolver::new().solve(board, false)?),
+        _ => Err(NumprError::MultipleSolutions),
+    })
+}
+
+/// Returns the status of a failed check in snake case.
+fn status(e: &NumprError) -> &'static str {
+    match e {
+        NumprError::Unsolvable => "unsolvable",
+        NumprError::MultipleSolutions => "multiple_solutions",
+        _ => "error",
+    }
+}
+
+/// Solves puzzles and returns `false` when any of them doesn't have a unique
+/// answer.
+pub fn solve(records: Records, json: bool) -> Result<bool> {
+    let mut ok = true;
+    for (i, r) in records.enumerate() {
+        let r = r?;
+        let result = check(&r.puzzle)?;
+        ok &= result.is_ok();
+        match (&result, json) {
+            (Ok(s), true) => println!(
+                "{}",
+                json!({"index": i, "puzzle": line(&r.puzzle), "status": "solved", "solution": line(s)})
+            ),
+            (Err(e), true) => println!(
+                "{}",
+                json!({"index": i, "puzzle": line(&r.puzzle), "status": status(e), "error": e.to_string()})
+            ),
+            (Ok(s), false) => println!("{}", line(s)),
+            (Err(e), false) => eprintln!("puzzle {}: {}", i, e),
+        }
+    }
+    Ok(ok)
+}
+
+/// Rates puzzles and returns `false` when any of them doesn't have a unique
+/// answer.
+pub fn rate(records: Records, json: bool) -> Result<bool> {
+    let mut ok = true;
+    for (i, r) in records.enumerate() {
+        let r = r?;
+        let result = rating::rate(&r.puzzle);
+        ok &= result.is_ok();
+        match (&result, json) {
+            (Ok(d), true) => println!(
+                "{}",
+                json!({"index": i, "puzzle": line(&r.puzzle), "status": "ok", "difficulty": d.to_string()})
+            ),
+            (Err(e), true) => println!(
+                "{}",
+                json!({"index": i, "puzzle": line(&r.puzzle), "status": status(e), "error": e.to_string()})
+            ),
+            (Ok(d), false) => println!("{}", d),
+            (Err(e), false) => eprintln!("puzzle {}: {}", i, e),
+        }
+    }
+    Ok(ok)
+}
+
+/// Checks if puzzles have unique answers and returns `false` when any of them
+/// doesn't.
+pub fn check_unique(records: Records, json: bool) -> Result<bool> {
+    let mut ok = true;
+    for (i, r) in records.enumerate() {
+        let r = r?;
+        let result = check(&r.puzzle)?;
+        ok &= result.is_ok();
+        let s = match &result {
+            Ok(_) => "unique",
+            Err(e) => status(e),
+        };
+        if json {
+            let unique = result.is_ok();
+            println!(
+                "{}",
+                json!({"index": i, "puzzle": line(&r.puzzle), "status": s, "unique": unique})
+            );
+        } else {
+            println!("{}", s);
+        }
+    }
+    Ok(ok)
+}
+
+pub fn canonicalize(records: Records, json: bool) -> Result<bool> {
+    for (i, r) in records.enumerate() {
+        let r = r?;
+        let (c, t) = canonical::canonicalize(&r.puzzle);
+        if json {
+            println!(
+                "{}",
+                json!({"index": i, "puzzle": line(&r.puzzle), "canonical": line(&c), "transform": t.to_string()})
+            );
+        } else {
+            println!("{}", line(&c));
+        }
+    }
+    Ok(true)
+}
+
+pub fn convert(records: Records, to: Format, output: Option<&str>, json: bool) -> Result<bool> {
+    let records = records.collect::<numpr::NumprResult<Vec<_>>>()?;
+    to.write(input::write(output)?, &records)?.flush()?;
+    if json && output.is_some() {
+        println!("{}", json!({"records": records.len()}));
+    }
+    Ok(true)
+}
+
+/// Options of `generate`.
+pub struct Generate {
+    pub count: usize,
+    pub difficulty: Option<Difficulty>,
+    pub symmetry: Symmetry,
+    pub seed: u64,
+    pub attempts: usize,
+    pub format: Format,
+}
+
+/// Generates puzzles. Each puzzle is generated from a seed counted up from
+/// the given one, so the output is reproducible. Seeds are skipped when the
+/// puzzle ends up easier than the difficulty.
+pub fn generate(opts: &Generate, json: bool) -> Result<bool> {
+    let mut seed = opts.seed;
+    let mut puzzles = Vec::new();
+    for _ in 0..opts.count {
+        let mut attempts = 0;
+        let p = loop {
+            if attempts == opts.attempts {
+                return Err(Error::NotFound(format!(
+                    "no puzzle was found in {} attempts",
+                    opts.attempts
+                )));
+            }
+            attempts += 1;
+            let mut g = SeededGenerator::new(seed).symmetry(opts.symmetry);
+            if let Some(d) = opts.difficulty {
+                g = g.difficulty(d);
+            }
+            let p = g.puzzle();
+            seed = seed.wrapping_add(1);
+            match p {
+                Ok(p) if opts.difficulty.is_none_or(|d| p.difficulty() == Some(d)) => break p,
+                _ => {}
+            }
+        };
+        if json {
+            println!(
+                "{}",
+                json!({
+                    "id": p.id(),
+                    "puzzle": line(p.givens()),
+                    "solution": line(p.solution()),
+                    "difficulty": p.difficulty().map(|d| d.to_string()),
+                    "symmetry": p.symmetry().to_string(),
+                    "seed": p.seed(),
+                    "clues": p.clues(),
+                })
+            );
+        } else {
+            puzzles.push(p);
+        }
+    }
+    if !json {
+        let records: Vec<Record> = puzzles.iter().map(Record::from).collect();
+        opts.format.write(std::io::stdout(), &records)?.flush()?;
+    }
+    Ok(true)
+}
+
+/// Renders puzzles to ASCII or SVG. SVG is only written to stdout for a
+/// single puzzle.
+pub fn render(records: Records, svg: bool, solution: bool, output: Option<&str>) -> Result<bool> {
+    let records = records.collect::<numpr::NumprResult<Vec<_>>>()?;
+    let mut w = input::write(output)?;
+    if svg {
+        if records.len() != 1 {
+            return Err(Error::Usage(format!(
+                "SVG needs exactly one puzzle, found {}",
+                records.len()
+            )));
+        }
+        let r = &records[0];
+        let scene = if solution {
+            Scene::solution(&Puzzle::new(r.puzzle)?)
+        } else {
+            Scene::from(&r.puzzle)
+        };
+        w.write_all(Renderer::new().svg(&scene).as_bytes())?;
+    } else {
+        for (i, r) in records.iter().enumerate() {
+            if i > 0 {
+                writeln!(w)?;
+            }
+            let b = if solution {
+                *Puzzle::new(r.puzzle)?.solution()
+            } else {
+                r.puzzle
+            };
+            w.write_all(input::ascii(&b).as_bytes())?;
+        }
+    }
+    w.flush()?;
+    Ok(true)
+}
+
+pub fn booklet(records: Records, size: PageSize, per_page: usize, output: &str) -> Result<bool> {
+    let puzzles = records
+        .map(|r| Puzzle::new(r?.puzzle))
+        .collect::<numpr::NumprResult<Vec<_>>>()?;
+    let w = input::write(Some(output))?;
+    Booklet::new()
+        .page_size(size)
+        .per_page(per_page)
+        .write(w, &puzzles)?
+        .flush()?;
+    Ok(true)
+}
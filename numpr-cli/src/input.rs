@@ -0,0 +1,93 @@
+use crate::{Error, Result};
+use numpr::formats::{Format, Record};
+use numpr::{Board, NumprResult};
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+
+pub type Records = Box<dyn Iterator<Item = NumprResult<Record>>>;
+
+/// Parses a format name.
+pub fn format(name: &str) -> Result<Format> {
+    match name {
+        "sdk" => Ok(Format::Sdk),
+        "ss" => Ok(Format::Ss),
+        "sdm" => Ok(Format::Sdm),
+        "opensudoku" => Ok(Format::OpenSudoku),
+        "csv" => Ok(Format::Csv),
+        _ => Err(Error::Usage(format!("unknown format: {}", name))),
+    }
+}
+
+/// Opens `path`, or stdin when it's `None` or `-`. The format is guessed from
+/// the extension unless given, and defaults to `.sdm` having a puzzle per
+/// line.
+pub fn read(path: Option<&str>, format: Option<Format>) -> Result<Records> {
+    match path {
+        None | Some("-") => {
+            let f = format.unwrap_or(Format::Sdm);
+            Ok(f.reader(BufReader::new(io::stdin())))
+        }
+        Some(path) => {
+            let f = format
+                .or_else(|| Format::from_path(path))
+                .unwrap_or(Format::Sdm);
+            let file = File::open(path).map_err(|e| Error::Io(format!("{}: {}", path, e)))?;
+            Ok(f.reader(BufReader::new(file)))
+        }
+    }
+}
+
+/// Opens `path` to write, or stdout when it's `None` or `-`.
+pub fn write(path: Option<&str>) -> Result<Box<dyn Write>> {
+    match path {
+        None | Some("-") => Ok(Box::new(io::stdout())),
+        Some(path) => {
+            let file = File::create(path).map_err(|e| Error::Io(format!("{}: {}", path, e)))?;
+            Ok(Box::new(io::BufWriter::new(file)))
+        }
+    }
+}
+
+/// Formats `b` in a line having `0` as empty grids.
+pub fn line(b: &Board) -> String {
+    b.iter()
+        .map(|(_, n)| (b'0' + n.unwrap_or(0)) as char)
+        .collect()
+}
+
+/// Formats `b` in rows with borders of blocks.
+pub fn ascii(b: &Board) -> String {
+    let mut s = String::new();
+    for (pt, n) in b.iter() {
+        let (x, y) = (pt.x(), pt.y());
+        if x == 0 && y != 0 && y % 3 == 0 {
+            s.push_str("------+-------+------\n");
+        }
+        if x != 0 {
+            s.push_str(if x % 3 == 0 { " | " } else { " " });
+        }
+        s.push(n.map_or('.', |n| (b'0' + n) as char));
+        if x == 8 {
+            s.push('\n');
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpr::Pt;
+
+    #[test]
+    fn text() {
+        let mut b = Board::default();
+        b.set(Pt::new(3, 0).unwrap(), 5).unwrap();
+        assert_eq!(line(&b), format!("0005{}", "0".repeat(77)));
+        let a = ascii(&b);
+        assert_eq!(a.lines().count(), 11);
+        assert!(a.starts_with(". . . | 5 . . | . . .\n"));
+        assert_eq!(a.lines().nth(3), Some("------+-------+------"));
+        assert!(format("xml").is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use numpr::game::Game;
+use numpr::render::PageSize;
+use numpr::{NumprError, Puzzle, Symmetry};
+use std::fmt;
+use std::process;
+
+mod commands;
+mod input;
+mod play;
+
+/// Every puzzle was processed successfully.
+const SUCCESS: i32 = 0;
+/// Some puzzles don't have a unique answer.
+const FAILURE: i32 = 1;
+/// The arguments are wrong.
+const USAGE: i32 = 2;
+/// Input or output failed, or input is malformed.
+const ERROR: i32 = 3;
+/// No puzzle matching the options was generated.
+const NOT_FOUND: i32 = 4;
+
+pub enum Error {
+    Usage(String),
+    Io(String),
+    Numpr(NumprError),
+    NotFound(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(msg) | Error::Io(msg) | Error::NotFound(msg) => f.write_str(msg),
+            Error::Numpr(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<NumprError> for Error {
+    fn from(e: NumprError) -> Self {
+        Error::Numpr(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e.to_string())
+    }
+}
+
+fn app() -> App<'static, 'static> {
+    let input = || {
+        Arg::with_name("INPUT")
+            .help("File of puzzles; reads stdin when omitted or '-'")
+            .index(1)
+    };
+    let format = || {
+        Arg::with_name("format")
+            .long("format")
+            .short("f")
+            .takes_value(true)
+            .possible_values(&["sdk", "ss", "sdm", "opensudoku", "csv"])
+            .help("Format of the input; guessed from the extension, or sdm")
+    };
+    let output = || {
+        Arg::with_name("output")
+            .long("output")
+            .short("o")
+            .takes_value(true)
+            .help("File to write; writes stdout when omitted or '-'")
+    };
+
+    App::new("numpr")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Solves, generates, and evaluates Number Place puzzles.")
+        .after_help(
+            "EXIT CODES:\n    0    success\n    1    some puzzles have no unique answer\n    \
+             2    invalid arguments\n    3    I/O errors or malformed input\n    \
+             4    no puzzle matching the options of generate was found",
+        )
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::VersionlessSubcommands)
+        .arg(
+            Arg::with_name("json")
+                .long("json")
+                .global(true)
+                .help("Prints a JSON object per line"),
+        )
+        .subcommand(
+            SubCommand::with_name("solve")
+                .about("Solves puzzles")
+                .arg(input())
+                .arg(format()),
+        )
+        .subcommand(
+            SubCommand::with_name("generate")
+                .about("Generates puzzles having a unique answer")
+                .arg(
+                    Arg::with_name("count")
+                        .long("count")
+                        .short("n")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("Number of puzzles"),
+                )
+                .arg(
+                    Arg::with_name("difficulty")
+                        .long("difficulty")
+                        .takes_value(true)
+                        .possible_values(&["easy", "medium", "hard"]),
+                )
+                .arg(
+                    Arg::with_name("symmetry")
+                        .long("symmetry")
+                        .takes_value(true)
+                        .default_value("none")
+                        .possible_values(&[
+                            "none",
+                            "rotational90",
+                            "rotational180",
+                            "horizontal",
+                            "vertical",
+                            "diagonal",
+                            "anti_diagonal",
+                        ]),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .help("Seed to reproduce puzzles; random when omitted"),
+                )
+                .arg(
+                    Arg::with_name("attempts")
+                        .long("attempts")
+                        .takes_value(true)
+                        .default_value("1000")
+                        .help("Maximum number of tries per puzzle to match the difficulty"),
+                )
+                .arg(format().help("Format of the output").default_value("sdm")),
+        )
+        .subcommand(
+            SubCommand::with_name("rate")
+                .about("Rates difficulty of puzzles")
+                .arg(input())
+                .arg(format()),
+        )
+        .subcommand(
+            SubCommand::with_name("check-unique")
+                .about("Checks if puzzles have a unique answer")
+                .arg(input())
+                .arg(format()),
+        )
+        .subcommand(
+            SubCommand::with_name("canonicalize")
+                .about("Prints canonical forms of puzzles")
+                .arg(input())
+                .arg(format()),
+        )
+        .subcommand(
+            SubCommand::with_name("convert")
+                .about("Converts puzzles between formats")
+                .arg(input())
+                .arg(format())
+                .arg(
+                    Arg::with_name("to")
+                        .long("to")
+                        .short("t")
+                        .takes_value(true)
+                        .required(true)
+                        .possible_values(&["sdk", "ss", "sdm", "opensudoku", "csv"])
+                        .help("Format of the output"),
+                )
+                .arg(output()),
+        )
+        .subcommand(
+            SubCommand::with_name("render")
+                .about("Renders puzzles to ASCII or SVG")
+                .arg(input())
+                .arg(format())
+                .arg(
+                    Arg::with_name("to")
+                        .long("to")
+                        .short("t")
+                        .takes_value(true)
+                        .default_value("ascii")
+                        .possible_values(&["ascii", "svg"]),
+                )
+                .arg(
+                    Arg::with_name("solution")
+                        .long("solution")
+                        .help("Renders the answers"),
+                )
+                .arg(output()),
+        )
+        .subcommand(
+            SubCommand::with_name("play")
+                .about("Plays a puzzle in the terminal")
+                .arg(input())
+                .arg(format())
+                .arg(
+                    Arg::with_name("index")
+                        .long("index")
+                        .short("i")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Index of the puzzle in the input"),
+                )
+                .arg(
+                    Arg::with_name("resume")
+                        .long("resume")
+                        .takes_value(true)
+                        .conflicts_with("INPUT")
+                        .help("Resumes a saved game"),
+                )
+                .arg(
+                    Arg::with_name("save")
+                        .long("save")
+                        .takes_value(true)
+                        .help("File to save the game; the resumed file or numpr.save by default"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("booklet")
+                .about("Makes a printable PDF of puzzles with the solutions")
+                .arg(input())
+                .arg(format())
+                .arg(output().required(true))
+                .arg(
+                    Arg::with_name("page-size")
+                        .long("page-size")
+                        .takes_value(true)
+                        .default_value("a4")
+                        .possible_values(&["a4", "letter"]),
+                )
+                .arg(
+                    Arg::with_name("per-page")
+                        .long("per-page")
+                        .takes_value(true)
+                        .default_value("2")
+                        .help("Number of puzzles per page"),
+                ),
+        )
+}
+
+/// Parses the value of `name` in `m`.
+fn value<T: std::str::FromStr>(m: &ArgMatches, name: &str) -> Result<Option<T>> {
+    m.value_of(name)
+        .map(|v| {
+            v.parse()
+                .map_err(|_| Error::Usage(format!("invalid value of --{}: {}", name, v)))
+        })
+        .transpose()
+}
+
+fn run(m: &ArgMatches) -> Result<bool> {
+    let (name, sub) = m.subcommand();
+    let sub = match sub {
+        Some(sub) => sub,
+        None => return Err(Error::Usage("no subcommand".to_string())),
+    };
+    let json = m.is_present("json") || sub.is_present("json");
+    let format = sub.value_of("format").map(input::format).transpose()?;
+    let records = || input::read(sub.value_of("INPUT"), format);
+
+    match name {
+        "solve" => commands::solve(records()?, json),
+        "rate" => commands::rate(records()?, json),
+        "check-unique" => commands::check_unique(records()?, json),
+        "canonicalize" => commands::canonicalize(records()?, json),
+        "convert" => {
+            let to = input::format(sub.value_of("to").unwrap())?;
+            commands::convert(records()?, to, sub.value_of("output"), json)
+        }
+        "generate" => {
+            let opts = commands::Generate {
+                count: value(sub, "count")?.unwrap(),
+                difficulty: value(sub, "difficulty")?,
+                symmetry: value::<Symmetry>(sub, "symmetry")?.unwrap(),
+                seed: value(sub, "seed")?.unwrap_or_else(rand::random),
+                attempts: value::<usize>(sub, "attempts")?.unwrap().max(1),
+                format: format.unwrap(),
+            };
+            commands::generate(&opts, json)
+        }
+        "render" => commands::render(
+            records()?,
+            sub.value_of("to") == Some("svg"),
+            sub.is_present("solution"),
+            sub.value_of("output"),
+        ),
+        "play" => {
+            let (game, save) = match sub.value_of("resume") {
+                Some(path) => {
+                    let s = std::fs::read_to_string(path)
+                        .map_err(|e| Error::Io(format!("{}: {}", path, e)))?;
+                    (Game::load(&s)?, path)
+                }
+                None => {
+                    let index: usize = value(sub, "index")?.unwrap();
+                    let record = match records()?.nth(index) {
+                        Some(r) => r?,
+                        None => return Err(Error::Usage(format!("no puzzle at {}", index))),
+                    };
+                    (Game::new(Puzzle::new(record.puzzle)?), "numpr.save")
+                }
+            };
+            let save = sub.value_of("save").unwrap_or(save);
+            play::run(play::Player::new(game, save.into()))
+        }
+        "booklet" => {
+            let size = match sub.value_of("page-size") {
+                Some("letter") => PageSize::Letter,
+                _ => PageSize::A4,
+            };
+            commands::booklet(
+                records()?,
+                size,
+                value(sub, "per-page")?.unwrap(),
+                sub.value_of("output").unwrap(),
+            )
+        }
+        _ => Err(Error::Usage(format!("unknown subcommand: {}", name))),
+    }
+}
+
+fn main() {
+    let m = match app().get_matches_safe() {
+        Ok(m) => m,
+        Err(e) if e.use_stderr() => {
+            eprintln!("{}", e.message);
+            process::exit(USAGE);
+        }
+        // --help and --version
+        Err(e) => e.exit(),
+    };
+    let code = match run(&m) {
+        Ok(true) => SUCCESS,
+        Ok(false) => FAILURE,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            match e {
+                Error::Usage(_) => USAGE,
+                Error::NotFound(_) => NOT_FOUND,
+                _ => ERROR,
+            }
+        }
+    };
+    process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args() {
+        let m = app()
+            .get_matches_from_safe(["numpr", "generate", "--seed", "7", "--json"])
+            .unwrap();
+        let (name, sub) = m.subcommand();
+        assert_eq!(name, "generate");
+        assert_eq!(value::<u64>(sub.unwrap(), "seed").ok().unwrap(), Some(7));
+        assert!(sub.unwrap().is_present("json"));
+
+        let m = app()
+            .get_matches_from_safe(["numpr", "generate", "--count", "x"])
+            .unwrap();
+        assert!(value::<usize>(m.subcommand().1.unwrap(), "count").is_err());
+        assert!(app()
+            .get_matches_from_safe(["numpr", "convert", "a.sdm"])
+            .is_err());
+    }
+
+    #[test]
+    fn booklet() {
+        let m = app()
+            .get_matches_from_safe([
+                "numpr",
+                "booklet",
+                "a.sdm",
+                "-o",
+                "a.pdf",
+                "--page-size",
+                "letter",
+                "--per-page",
+                "4",
+            ])
+            .unwrap();
+        let sub = m.subcommand_matches("booklet").unwrap();
+        assert_eq!(sub.value_of("page-size"), Some("letter"));
+        assert_eq!(value::<usize>(sub, "per-page").ok().unwrap(), Some(4));
+
+        let m = app()
+            .get_matches_from_safe(["numpr", "booklet", "a.sdm", "-o", "a.pdf"])
+            .unwrap();
+        let sub = m.subcommand_matches("booklet").unwrap();
+        assert_eq!(sub.value_of("page-size"), Some("a4"));
+        assert_eq!(value::<usize>(sub, "per-page").ok().unwrap(), Some(2));
+
+        assert!(app()
+            .get_matches_from_safe(["numpr", "booklet", "a.sdm"])
+            .is_err());
+        assert!(app()
+            .get_matches_from_safe([
+                "numpr",
+                "booklet",
+                "a.sdm",
+                "-o",
+                "a.pdf",
+                "--page-size",
+                "a5"
+            ])
+            .is_err());
+    }
+}
@@ -0,0 +1,402 @@
+use crate::Result;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use numpr::game::{Game, Hint, Mark};
+use numpr::{Pt, PtIter};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const HELP: [&str; 9] = [
+    "arrows/hjkl  move",
+    "1-9          enter or mark",
+    "0/del        erase",
+    "tab/m        switch mode",
+    "?            hint",
+    "a            fill notes",
+    "u/r          undo/redo",
+    "p            pause",
+    "s/q          save/quit",
+];
+
+/// What digits do.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Value,
+    Center,
+    Corner,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Move(isize, isize),
+    Digit(u8),
+    Erase,
+    Mode,
+    Hint,
+    AutoNotes,
+    Undo,
+    Redo,
+    Pause,
+    Save,
+    Quit,
+}
+
+/// Returns the action of `key`.
+pub fn action(key: KeyEvent) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    Some(match key.code {
+        KeyCode::Char('z') if ctrl => Action::Undo,
+        KeyCode::Char('y') if ctrl => Action::Redo,
+        KeyCode::Char('c') if ctrl => Action::Quit,
+        KeyCode::Left | KeyCode::Char('h') => Action::Move(-1, 0),
+        KeyCode::Right | KeyCode::Char('l') => Action::Move(1, 0),
+        KeyCode::Up | KeyCode::Char('k') => Action::Move(0, -1),
+        KeyCode::Down | KeyCode::Char('j') => Action::Move(0, 1),
+        KeyCode::Char(c @ '1'..='9') => Action::Digit(c as u8 - b'0'),
+        KeyCode::Char('0') | KeyCode::Char('.') | KeyCode::Backspace | KeyCode::Delete => {
+            Action::Erase
+        }
+        KeyCode::Tab | KeyCode::Char('m') => Action::Mode,
+        KeyCode::Char('?') => Action::Hint,
+        KeyCode::Char('a') => Action::AutoNotes,
+        KeyCode::Char('u') => Action::Undo,
+        KeyCode::Char('r') => Action::Redo,
+        KeyCode::Char('p') => Action::Pause,
+        KeyCode::Char('s') => Action::Save,
+        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+        _ => return None,
+    })
+}
+
+/// A game with the state of the screen.
+pub struct Player {
+    game: Game,
+    cursor: Pt,
+    mode: Mode,
+    message: String,
+    save: PathBuf,
+}
+
+impl Player {
+    /// Returns a player of `game` saving it to `save`.
+    pub fn new(game: Game, save: PathBuf) -> Self {
+        Self {
+            game,
+            cursor: Pt::new(0, 0).unwrap(),
+            mode: Mode::Value,
+            message: String::new(),
+            save,
+        }
+    }
+
+    /// Applies `action` and returns `false` to quit.
+    pub fn apply(&mut self, action: Action) -> bool {
+        self.message.clear();
+        if self.game.is_paused() && !self.game.is_solved() {
+            match action {
+                Action::Pause => self.game.resume(),
+                Action::Save => self.save(),
+                Action::Quit => return false,
+                _ => self.message = "Paused. Press p to resume.".to_string(),
+            }
+            return true;
+        }
+
+        let pt = self.cursor;
+        let result = match action {
+            Action::Move(dx, dy) => {
+                let wrap = |v: usize, d: isize| ((v as isize + d + 9) % 9) as usize;
+                self.cursor = Pt::new(wrap(pt.x(), dx), wrap(pt.y(), dy)).unwrap();
+                Ok(())
+            }
+            Action::Digit(n) => match self.mode {
+                Mode::Value => self.game.enter(pt, n).map(|_| ()),
+                Mode::Center => self.game.toggle_mark(pt, Mark::Center, n),
+                Mode::Corner => self.game.toggle_mark(pt, Mark::Corner, n),
+            },
+            Action::Erase => match self.mode {
+                Mode::Value => self.game.erase(pt),
+                Mode::Center => self.game.clear_marks(pt, Mark::Center),
+                Mode::Corner => self.game.clear_marks(pt, Mark::Corner),
+            },
+            Action::Mode => {
+                self.mode = match self.mode {
+                    Mode::Value => Mode::Center,
+                    Mode::Center => Mode::Corner,
+                    Mode::Corner => Mode::Value,
+                };
+                Ok(())
+            }
+            Action::Hint => {
+                self.hint();
+                Ok(())
+            }
+            Action::AutoNotes => {
+                self.game.auto_notes();
+                Ok(())
+            }
+            Action::Undo => {
+                if !self.game.undo() {
+                    self.message = "Nothing to undo.".to_string();
+                }
+                Ok(())
+            }
+            Action::Redo => {
+                if !self.game.redo() {
+                    self.message = "Nothing to redo.".to_string();
+                }
+                Ok(())
+            }
+            Action::Pause => {
+                if !self.game.is_solved() {
+                    self.game.pause();
+                }
+                Ok(())
+            }
+            Action::Save => {
+                self.save();
+                Ok(())
+            }
+            Action::Quit => return false,
+        };
+        if let Err(e) = result {
+            self.message = e.to_string();
+        } else if self.game.is_solved() && self.message.is_empty() {
+            self.message = format!("Solved in {}!", clock(self.game.elapsed()));
+        }
+        true
+    }
+
+    fn hint(&mut self) {
+        let (pt, msg) = match self.game.hint() {
+            Some(Hint::Mistake(p)) => (p, "This number is wrong.".to_string()),
+            Some(Hint::NakedSingle(p, n)) => (p, format!("Only {} can be placed here.", n)),
+            Some(Hint::HiddenSingle(p, n)) => {
+                (p, format!("{} can only be placed here in a unit.", n))
+            }
+            Some(Hint::Answer(p, n)) => (p, format!("The answer here is {}.", n)),
+            None => return,
+        };
+        self.cursor = pt;
+        self.message = msg;
+    }
+
+    fn save(&mut self) {
+        self.message = match fs::write(&self.save, self.game.save()) {
+            Ok(()) => format!("Saved to {}.", self.save.display()),
+            Err(e) => format!("Failed to save: {}", e),
+        };
+    }
+
+    /// Draws the screen.
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let g = &self.game;
+        let paused = g.is_paused() && !g.is_solved();
+        let conflicts = g.conflicts();
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+        let mode = match self.mode {
+            Mode::Value => "value",
+            Mode::Center => "center marks",
+            Mode::Corner => "corner marks",
+        };
+        queue!(
+            out,
+            Print(format!(
+                "{}  mistakes {}  mode: {}",
+                clock(g.elapsed()),
+                g.mistakes(),
+                mode
+            ))
+        )?;
+
+        const BORDER: &str = "+-------+-------+-------+";
+        let mut row = 2;
+        for pt in PtIter::all() {
+            let (x, y) = (pt.x(), pt.y());
+            if x == 0 {
+                if y % 3 == 0 {
+                    queue!(out, MoveTo(0, row), Print(BORDER))?;
+                    row += 1;
+                }
+                queue!(out, MoveTo(0, row), Print("|"))?;
+            }
+            queue!(out, Print(" "))?;
+            if pt == self.cursor {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            let (c, color) = match g.get(pt) {
+                _ if paused => ('?', Color::DarkGrey),
+                Some(n) if conflicts.contains(&pt) => ((b'0' + n) as char, Color::Red),
+                Some(n) if g.is_given(pt) => ((b'0' + n) as char, Color::Reset),
+                Some(n) => ((b'0' + n) as char, Color::Blue),
+                None if g
+                    .marks(pt, Mark::Center)
+                    .chain(g.marks(pt, Mark::Corner))
+                    .next()
+                    .is_some() =>
+                {
+                    ('*', Color::DarkGrey)
+                }
+                None => ('.', Color::DarkGrey),
+            };
+            if g.is_given(pt) && !paused {
+                queue!(out, SetAttribute(Attribute::Bold))?;
+            }
+            queue!(out, SetForegroundColor(color), Print(c), ResetColor)?;
+            queue!(out, SetAttribute(Attribute::Reset))?;
+            if x % 3 == 2 {
+                queue!(out, Print(" |"))?;
+            }
+            if x == 8 {
+                row += 1;
+            }
+        }
+        queue!(out, MoveTo(0, row), Print(BORDER))?;
+
+        let marks = |m| {
+            g.marks(self.cursor, m)
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let info = [
+            format!("({}, {})", self.cursor.x() + 1, self.cursor.y() + 1),
+            format!("center: {}", marks(Mark::Center)),
+            format!("corner: {}", marks(Mark::Corner)),
+            String::new(),
+        ];
+        for (i, line) in info
+            .iter()
+            .map(|s| s.as_str())
+            .chain(HELP.iter().copied())
+            .enumerate()
+        {
+            queue!(out, MoveTo(28, 2 + i as u16), Print(line))?;
+        }
+        queue!(out, MoveTo(0, row + 2), Print(&self.message))?;
+        out.flush()
+    }
+}
+
+/// Formats a duration as `mm:ss`.
+fn clock(d: Duration) -> String {
+    let s = d.as_secs();
+    format!("{:02}:{:02}", s / 60, s % 60)
+}
+
+/// Restores the terminal when dropped, including on errors.
+struct Screen;
+
+impl Screen {
+    fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Plays until the player quits. The screen is redrawn every second to
+/// update the timer.
+pub fn run(mut player: Player) -> Result<bool> {
+    let screen = Screen::new()?;
+    let mut out = io::stdout();
+    loop {
+        player.draw(&mut out)?;
+        if !event::poll(Duration::from_secs(1))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(a) = action(key) {
+                if !player.apply(a) {
+                    break;
+                }
+            }
+        }
+    }
+    drop(screen);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use numpr::{solvers, Board, Puzzle, Solver};
+
+    fn player() -> (Player, Board) {
+        let answer = solvers::HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        let mut givens = answer;
+        givens.set(Pt::new(0, 0).unwrap(), 0).unwrap();
+        givens.set(Pt::new(8, 8).unwrap(), 0).unwrap();
+        let game = Game::new(Puzzle::new(givens).unwrap());
+        let save = std::env::temp_dir().join(format!("numpr-play-{}.txt", std::process::id()));
+        (Player::new(game, save), answer)
+    }
+
+    #[test]
+    fn keys() {
+        let key = |c| action(KeyEvent::new(c, KeyModifiers::NONE));
+        assert_eq!(key(KeyCode::Char('5')), Some(Action::Digit(5)));
+        assert_eq!(key(KeyCode::Left), Some(Action::Move(-1, 0)));
+        assert_eq!(key(KeyCode::Delete), Some(Action::Erase));
+        assert_eq!(key(KeyCode::Char('x')), None);
+        let ctrl_z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert_eq!(action(ctrl_z), Some(Action::Undo));
+    }
+
+    #[test]
+    fn play() {
+        let (mut p, answer) = player();
+        let n = |x, y| answer.get(Pt::new(x, y).unwrap()).unwrap();
+
+        // Moving wraps around.
+        p.apply(Action::Move(-1, -1));
+        assert_eq!(p.cursor, Pt::new(8, 8).unwrap());
+        p.apply(Action::Mode);
+        p.apply(Action::Digit(n(8, 8)));
+        assert!(p.game.marks(p.cursor, Mark::Center).eq(vec![n(8, 8)]));
+        p.apply(Action::Mode);
+        p.apply(Action::Mode);
+        p.apply(Action::Digit(n(8, 8)));
+
+        p.apply(Action::Hint);
+        assert_eq!(p.cursor, Pt::new(0, 0).unwrap());
+        p.apply(Action::Digit(n(0, 0) % 9 + 1));
+        assert!(!p.game.conflicts().is_empty());
+        p.apply(Action::Undo);
+        assert!(p.game.conflicts().is_empty());
+
+        p.apply(Action::Pause);
+        p.apply(Action::Digit(n(0, 0)));
+        assert_eq!(p.game.get(p.cursor), None);
+        let mut screen = Vec::new();
+        p.draw(&mut screen).unwrap();
+        assert!(String::from_utf8(screen).unwrap().contains("Paused"));
+        p.apply(Action::Pause);
+        p.apply(Action::Digit(n(0, 0)));
+        assert!(p.game.is_solved());
+        assert!(p.message.starts_with("Solved"));
+
+        p.apply(Action::Save);
+        let saved = fs::read_to_string(&p.save).unwrap();
+        fs::remove_file(&p.save).unwrap();
+        assert!(Game::load(&saved).unwrap().is_solved());
+        assert!(!p.apply(Action::Quit));
+    }
+}
@@ -1 +1,478 @@
+use http::StatusCode;
+use hyper::body::HttpBody;
+use hyper::{Body, Method, Request, Response};
+use numpr::budget::Budget;
+use numpr::generators::SeededGenerator;
+use numpr::rating::Difficulty;
+use numpr::{Board, NumprError, NumprResult, Puzzle, Rules, Symmetry};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::instrument;
 
+/// Maximum number of generated puzzles to find one having the requested
+/// difficulty.
+const ATTEMPTS: usize = 20;
+
+/// Time to generate a puzzle in all attempts.
+const GENERATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Nodes and time to check a posted puzzle.
+const CHECK_NODES: u64 = 100_000;
+const CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Maximum size of a request body in bytes.
+const MAX_BODY: usize = 4096;
+
+/// Number of puzzles a store keeps by default.
+pub const CAPACITY: usize = 10_000;
+
+/// Puzzles kept in memory by their identifiers. The oldest one is evicted
+/// when more than the capacity are stored.
+pub struct Store {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    puzzles: HashMap<String, Puzzle>,
+    order: VecDeque<String>,
+}
+
+impl Store {
+    /// Returns a store keeping up to `capacity` puzzles.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+
+    /// Stores `p` and returns its identifier.
+    pub fn insert(&self, p: Puzzle) -> String {
+        let id = p.id();
+        let mut inner = self.inner.lock().unwrap();
+        if inner.puzzles.insert(id.clone(), p).is_none() {
+            inner.order.push_back(id.clone());
+            while inner.order.len() > self.capacity {
+                let old = inner.order.pop_front().unwrap();
+                inner.puzzles.remove(&old);
+            }
+        }
+        id
+    }
+
+    pub fn get(&self, id: &str) -> Option<Puzzle> {
+        self.inner.lock().unwrap().puzzles.get(id).cloned()
+    }
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Self::new(CAPACITY)
+    }
+}
+
+/// Handles a request.
+///
+/// - `GET /problems?difficulty=&symmetry=&seed=` generates a puzzle.
+/// - `POST /problems` stores a puzzle in `{"puzzle": "..."}` having a unique
+///   answer.
+/// - `GET /problems/{id}` returns a stored puzzle.
+///
+/// Generating and checking puzzles run on the blocking pool within a budget.
+#[instrument(skip(store, req), fields(method = %req.method(), path = %req.uri().path()))]
+pub async fn handle(store: Arc<Store>, req: Request<Body>) -> http::Result<Response<Body>> {
+    let path = req.uri().path().trim_end_matches('/').to_string();
+    let result = match (req.method(), path.as_str()) {
+        (&Method::GET, "/problems") => generate(&store, req.uri().query().unwrap_or("")).await,
+        (&Method::POST, "/problems") => create(&store, req).await,
+        (&Method::GET, p) if p.starts_with("/problems/") => {
+            let id = &p["/problems/".len()..];
+            match store.get(id) {
+                Some(puzzle) => Ok((StatusCode::OK, problem(&puzzle))),
+                None => Err(failure(
+                    StatusCode::NOT_FOUND,
+                    "not_found",
+                    &format!("no problem has ID {}", id),
+                )),
+            }
+        }
+        (_, "/problems") => Err(failure(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method_not_allowed",
+            "only GET and POST are allowed",
+        )),
+        _ => Err(failure(StatusCode::NOT_FOUND, "not_found", "not found")),
+    };
+    let (status, body) = result.unwrap_or_else(|e| e);
+    Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string().into())
+}
+
+type Reply = Result<(StatusCode, Value), (StatusCode, Value)>;
+
+async fn generate(store: &Store, query: &str) -> Reply {
+    let params = params(query);
+    let parse = || -> NumprResult<_> {
+        let difficulty = params
+            .get("difficulty")
+            .map(|s| s.parse::<Difficulty>())
+            .transpose()?;
+        let symmetry = params
+            .get("symmetry")
+            .map(|s| s.parse::<Symmetry>())
+            .transpose()?
+            .unwrap_or(Symmetry::None);
+        let seed = params
+            .get("seed")
+            .map(|s| {
+                s.parse::<u64>()
+                    .or_else(|_| NumprError::invalid_format(format!("invalid seed: {}", s)))
+            })
+            .transpose()?
+            .unwrap_or_else(rand::random);
+        Ok((difficulty, symmetry, seed))
+    };
+    let (difficulty, symmetry, seed) = parse().map_err(|e| error(&e))?;
+
+    let budget = Budget::new().timeout(GENERATE_TIMEOUT);
+    let p = blocking(move || -> NumprResult<Puzzle> {
+        let mut seed = seed;
+        for _ in 0..ATTEMPTS {
+            let mut g = SeededGenerator::new(seed)
+                .symmetry(symmetry)
+                .with_budget(budget.clone());
+            if let Some(d) = difficulty {
+                g = g.difficulty(d);
+            }
+            let p = g.puzzle()?;
+            if difficulty.is_none_or(|d| p.difficulty() == Some(d)) {
+                return Ok(p);
+            }
+            seed = seed.wrapping_add(1);
+        }
+        NumprError::budget_exceeded()
+    })
+    .await?
+    .map_err(|e| error(&e))?;
+
+    store.insert(p.clone());
+    Ok((StatusCode::OK, problem(&p)))
+}
+
+async fn create(store: &Store, req: Request<Body>) -> Reply {
+    let bytes = read(req.into_body()).await?;
+    let v: Value = serde_json::from_slice(&bytes)
+        .map_err(|e| error(&NumprError::InvalidFormat(format!("invalid JSON: {}", e))))?;
+    let s = v["puzzle"].as_str().ok_or_else(|| {
+        error(&NumprError::InvalidFormat(
+            "\"puzzle\" must be a string".to_string(),
+        ))
+    })?;
+    let b = board(s).map_err(|e| error(&e))?;
+    let budget = Budget::new().max_nodes(CHECK_NODES).timeout(CHECK_TIMEOUT);
+    let p = blocking(move || Puzzle::with_budget(b, Rules::new(), budget))
+        .await?
+        .map_err(|e| match e {
+            // Checking the same puzzle again never fits in the budget.
+            NumprError::BudgetExceeded => failure(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "budget_exceeded",
+                "the puzzle is too hard to check",
+            ),
+            e => error(&e),
+        })?;
+    store.insert(p.clone());
+    Ok((StatusCode::CREATED, problem(&p)))
+}
+
+/// Runs CPU bound `f` out of the event loop.
+async fn blocking<T, F>(f: F) -> Result<NumprResult<T>, (StatusCode, Value)>
+where
+    F: FnOnce() -> NumprResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.map_err(|e| {
+        failure(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal",
+            &e.to_string(),
+        )
+    })
+}
+
+/// Reads `body` failing with 413 when it's larger than `MAX_BODY`.
+async fn read(mut body: Body) -> Result<Vec<u8>, (StatusCode, Value)> {
+    let too_large = || {
+        failure(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "payload_too_large",
+            &format!("body must be at most {} bytes", MAX_BODY),
+        )
+    };
+    if body.size_hint().lower() > MAX_BODY as u64 {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk
+            .map_err(|e| failure(StatusCode::BAD_REQUEST, "invalid_format", &e.to_string()))?;
+        if bytes.len() + chunk.len() > MAX_BODY {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Parses a query string. Values aren't percent-decoded because none of
+/// them needs escaping.
+fn params(query: &str) -> HashMap<&str, &str> {
+    query
+        .split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| {
+            let mut it = kv.splitn(2, '=');
+            (it.next().unwrap(), it.next().unwrap_or(""))
+        })
+        .filter(|(_, v)| !v.is_empty())
+        .collect()
+}
+
+/// Parses a board in a line of 81 characters having `0` or `.` as empty
+/// grids.
+fn board(s: &str) -> NumprResult<Board> {
+    let n = s
+        .chars()
+        .map(|c| match c {
+            '.' => Ok(0),
+            '0'..='9' => Ok(c as u8 - b'0'),
+            _ => NumprError::invalid_format(format!("unexpected character: {:?}", c)),
+        })
+        .collect::<NumprResult<Vec<u8>>>()?;
+    Board::new(&n)
+}
+
+fn line(b: &Board) -> String {
+    b.iter()
+        .map(|(_, n)| (b'0' + n.unwrap_or(0)) as char)
+        .collect()
+}
+
+fn problem(p: &Puzzle) -> Value {
+    json!({
+        "id": p.id(),
+        "puzzle": line(p.givens()),
+        "solution": line(p.solution()),
+        "difficulty": p.difficulty().map(|d| d.to_string()),
+        "symmetry": p.symmetry().to_string(),
+        "seed": p.seed(),
+        "clues": p.clues(),
+    })
+}
+
+/// Returns the status code and the error object of `e`.
+fn error(e: &NumprError) -> (StatusCode, Value) {
+    use NumprError::*;
+    let (status, code) = match e {
+        IndexOutOfBounds(_) => (StatusCode::BAD_REQUEST, "index_out_of_bounds"),
+        InvalidBoardLength(_) => (StatusCode::BAD_REQUEST, "invalid_board_length"),
+        InvalidValue(..) => (StatusCode::BAD_REQUEST, "invalid_value"),
+        InvalidBoardCount(..) => (StatusCode::BAD_REQUEST, "invalid_board_count"),
+        BoardOutOfBounds(..) => (StatusCode::BAD_REQUEST, "board_out_of_bounds"),
+        Conflict(..) => (StatusCode::BAD_REQUEST, "conflict"),
+        InvalidConstraint(_) => (StatusCode::BAD_REQUEST, "invalid_constraint"),
+        InvalidFormat(_) => (StatusCode::BAD_REQUEST, "invalid_format"),
+        InvalidTransform(_) => (StatusCode::BAD_REQUEST, "invalid_transform"),
+        Parse(..) => (StatusCode::BAD_REQUEST, "parse"),
+        WrongAnswer => (StatusCode::UNPROCESSABLE_ENTITY, "wrong_answer"),
+        MultipleSolutions => (StatusCode::UNPROCESSABLE_ENTITY, "multiple_solutions"),
+        Unsolvable => (StatusCode::UNPROCESSABLE_ENTITY, "unsolvable"),
+        GivenModified(_) => (StatusCode::UNPROCESSABLE_ENTITY, "given_modified"),
+        BudgetExceeded => (StatusCode::SERVICE_UNAVAILABLE, "budget_exceeded"),
+        Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "io"),
+    };
+    failure(status, code, &e.to_string())
+}
+
+fn failure(status: StatusCode, code: &str, message: &str) -> (StatusCode, Value) {
+    (status, json!({"error": {"code": code, "message": message}}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+
+    async fn call(
+        store: &Arc<Store>,
+        method: Method,
+        uri: &str,
+        body: &str,
+    ) -> (StatusCode, Value) {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let res = handle(store.clone(), req).await.unwrap();
+        let status = res.status();
+        let bytes = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn problems() {
+        let store = Arc::new(Store::default());
+
+        let (status, v) = call(
+            &store,
+            Method::POST,
+            "/problems",
+            &json!({ "puzzle": PUZZLE }).to_string(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = v["id"].as_str().unwrap().to_string();
+        assert_eq!(v["puzzle"], PUZZLE.replace('.', "0"));
+
+        let (status, v) = call(&store, Method::GET, &format!("/problems/{}", id), "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(v["id"], id.as_str());
+
+        let (status, v) = call(
+            &store,
+            Method::GET,
+            "/problems?symmetry=rotational180&seed=5",
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(v["seed"], 5);
+        assert_eq!(v["symmetry"], "rotational180");
+        let (_, w) = call(
+            &store,
+            Method::GET,
+            "/problems?symmetry=rotational180&seed=5",
+            "",
+        )
+        .await;
+        assert_eq!(v["id"], w["id"]);
+        let (status, _) = call(
+            &store,
+            Method::GET,
+            &format!("/problems/{}", v["id"].as_str().unwrap()),
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let store = Arc::new(Store::default());
+        let cases = [
+            (
+                Method::POST,
+                "/problems",
+                "{}".to_string(),
+                StatusCode::BAD_REQUEST,
+                "invalid_format",
+            ),
+            (
+                Method::POST,
+                "/problems",
+                "[".to_string(),
+                StatusCode::BAD_REQUEST,
+                "invalid_format",
+            ),
+            (
+                Method::POST,
+                "/problems",
+                json!({"puzzle": "123"}).to_string(),
+                StatusCode::BAD_REQUEST,
+                "invalid_board_length",
+            ),
+            (
+                Method::POST,
+                "/problems",
+                json!({"puzzle": ".".repeat(81)}).to_string(),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "multiple_solutions",
+            ),
+            (
+                Method::POST,
+                "/problems",
+                json!({ "puzzle": ".".repeat(MAX_BODY) }).to_string(),
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+            ),
+            (
+                Method::GET,
+                "/problems?difficulty=extreme",
+                String::new(),
+                StatusCode::BAD_REQUEST,
+                "invalid_format",
+            ),
+            (
+                Method::GET,
+                "/problems?seed=x",
+                String::new(),
+                StatusCode::BAD_REQUEST,
+                "invalid_format",
+            ),
+            (
+                Method::GET,
+                "/problems/0123",
+                String::new(),
+                StatusCode::NOT_FOUND,
+                "not_found",
+            ),
+            (
+                Method::DELETE,
+                "/problems",
+                String::new(),
+                StatusCode::METHOD_NOT_ALLOWED,
+                "method_not_allowed",
+            ),
+            (
+                Method::GET,
+                "/",
+                String::new(),
+                StatusCode::NOT_FOUND,
+                "not_found",
+            ),
+        ];
+        for (method, uri, body, status, code) in cases.iter() {
+            let (s, v) = call(&store, method.clone(), uri, body).await;
+            assert_eq!(
+                (s, v["error"]["code"].as_str()),
+                (*status, Some(*code)),
+                "{} {}",
+                method,
+                uri
+            );
+        }
+    }
+
+    #[test]
+    fn eviction() {
+        let store = Store::new(2);
+        let ids: Vec<String> = (0..3)
+            .map(|seed| store.insert(SeededGenerator::new(seed).puzzle().unwrap()))
+            .collect();
+        assert!(store.get(&ids[0]).is_none());
+        assert!(store.get(&ids[1]).is_some());
+        assert!(store.get(&ids[2]).is_some());
+        // Storing the same puzzle again doesn't evict others.
+        store.insert(store.get(&ids[1]).unwrap());
+        assert!(store.get(&ids[2]).is_some());
+    }
+}
@@ -1,26 +1,12 @@
 use hyper::service::{make_service_fn, service_fn};
-use hyper::{Body, Request, Response, Server};
+use hyper::Server;
+use numpr_http::{handle, Store};
 use std::convert::Infallible;
 use std::net::SocketAddr;
-use tracing::{info, instrument};
+use std::sync::Arc;
+use tracing::info;
 use tracing_subscriber::fmt;
 
-#[instrument(skip(req))]
-async fn hello_world(req: Request<Body>) -> http::Result<Response<Body>> {
-    let uri = req.uri();
-    match uri.path() {
-        "/problems" if req.method() == hyper::Method::GET => {
-            Ok(Response::new("Hello, World".into()))
-        }
-        "/problems" if req.method() == hyper::Method::POST => Response::builder()
-            .status(http::StatusCode::INTERNAL_SERVER_ERROR)
-            .body("Not implemented yet".into()),
-        _ => Response::builder()
-            .status(http::StatusCode::NOT_FOUND)
-            .body("Not Found".into()),
-    }
-}
-
 #[tokio::main]
 async fn main() {
     let subscriber = tracing_subscriber::fmt()
@@ -33,7 +19,11 @@ async fn main() {
         .finish();
     tracing::subscriber::set_global_default(subscriber).unwrap();
     let addr = SocketAddr::from(([127, 0, 0, 1], 3080));
-    let make_svc = make_service_fn(|_conn| async { Ok::<_, Infallible>(service_fn(hello_world)) });
+    let store = Arc::new(Store::default());
+    let make_svc = make_service_fn(move |_conn| {
+        let store = store.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(store.clone(), req))) }
+    });
 
     info!(port = 3080, "starting the server");
     let server = Server::bind(&addr).serve(make_svc);
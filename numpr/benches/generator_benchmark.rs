@@ -50,5 +50,55 @@ pub fn generator(c: &mut Criterion) {
     });
 }
 
+/// Removes grids in the same way as `NaiveGenerator`, checking each removal
+/// with `ParallelUniquenessChecker`, which generators can't take because it
+/// isn't a `UniquenessChecker`.
+#[cfg(feature = "parallel")]
+fn generate_with_parallel_unique(board: &Board) -> Board {
+    use rand::prelude::*;
+
+    let mut targets: Vec<Pt> = PtIter::all().collect();
+    targets.shuffle(&mut rand::thread_rng());
+    let mut b = *board;
+    for pt in targets {
+        let n = b.get(pt).unwrap();
+        b.set(pt, 0).unwrap();
+        if uniques::ParallelUniquenessChecker::new()
+            .check(&b, solvers::HeuristicSolver::new)
+            .is_err()
+        {
+            b.set(pt, n).unwrap();
+        }
+    }
+    b
+}
+
+#[cfg(feature = "parallel")]
+pub fn parallel_generator(c: &mut Criterion) {
+    c.bench_function("HeuristicSolver-And-Parallel", |b| {
+        b.iter(|| {
+            let b = place();
+            let g = generators::ParallelGenerator::new(black_box(81))
+                .generate(
+                    &b,
+                    solvers::HeuristicSolver::new,
+                    uniques::NaiveUniquenessChecker::new,
+                )
+                .unwrap();
+            solve(g);
+        });
+    });
+
+    c.bench_function("HeuristicSolver-And-ParallelUnique", |b| {
+        b.iter(|| {
+            let b = place();
+            solve(generate_with_parallel_unique(black_box(&b)));
+        });
+    });
+}
+
+#[cfg(not(feature = "parallel"))]
 criterion_group!(benches, generator);
+#[cfg(feature = "parallel")]
+criterion_group!(benches, generator, parallel_generator);
 criterion_main!(benches);
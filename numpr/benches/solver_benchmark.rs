@@ -0,0 +1,169 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use numpr::*;
+
+fn puzzles(n: usize) -> Vec<Board> {
+    (0..n)
+        .map(|_| {
+            let b = placers::PermutationPlacer::new()
+                .place(solvers::HeuristicSolver::new())
+                .unwrap();
+            generators::NaiveGenerator::new(81)
+                .generate(
+                    &b,
+                    solvers::HeuristicSolver::new,
+                    uniques::NaiveUniquenessChecker::new,
+                )
+                .unwrap()
+        })
+        .collect()
+}
+
+fn solve_all(puzzles: &[Board], solve: impl Fn(&Board) -> NumprResult<Board>) {
+    for p in puzzles {
+        solve(p).unwrap().validate().unwrap();
+    }
+}
+
+pub fn solver(c: &mut Criterion) {
+    let puzzles = puzzles(10);
+
+    let mut g = c.benchmark_group("Puzzles");
+    g.bench_function("NaiveSolver", |b| {
+        b.iter(|| solve_all(&puzzles, |p| solvers::NaiveSolver::new().solve(p, false)))
+    });
+    g.bench_function("HeuristicSolver", |b| {
+        b.iter(|| {
+            solve_all(&puzzles, |p| {
+                solvers::HeuristicSolver::new().solve(p, false)
+            })
+        })
+    });
+    g.bench_function("BitBoardSolver", |b| {
+        b.iter(|| solve_all(&puzzles, |p| solvers::BitBoardSolver::new().solve(p, false)))
+    });
+    g.bench_function("BitParallelSolver", |b| {
+        b.iter(|| {
+            solve_all(&puzzles, |p| {
+                solvers::BitParallelSolver::new().solve(p, false)
+            })
+        })
+    });
+    g.bench_function("BitParallelSolver/count", |b| {
+        let solver = solvers::BitParallelSolver::new();
+        b.iter(|| {
+            for p in &puzzles {
+                assert_eq!(solver.count(p, 2).unwrap(), 1);
+            }
+        })
+    });
+    g.finish();
+
+    let empty = [Board::default()];
+    let mut g = c.benchmark_group("Empty");
+    g.bench_function("NaiveSolver", |b| {
+        b.iter(|| solve_all(&empty, |p| solvers::NaiveSolver::new().solve(p, true)))
+    });
+    g.bench_function("HeuristicSolver", |b| {
+        b.iter(|| solve_all(&empty, |p| solvers::HeuristicSolver::new().solve(p, true)))
+    });
+    g.bench_function("BitBoardSolver", |b| {
+        b.iter(|| solve_all(&empty, |p| solvers::BitBoardSolver::new().solve(p, true)))
+    });
+    g.bench_function("BitParallelSolver", |b| {
+        b.iter(|| solve_all(&empty, |p| solvers::BitParallelSolver::new().solve(p, true)))
+    });
+    g.finish();
+}
+
+pub fn engine(c: &mut Criterion) {
+    let puzzles = puzzles(10);
+
+    // BitBoardSolver copying boards in recursion vs the solvers on the engine
+    // undoing a trail.
+    let mut g = c.benchmark_group("Engine");
+    g.bench_function("HeuristicSolver", |b| {
+        b.iter(|| {
+            solve_all(&puzzles, |p| {
+                solvers::HeuristicSolver::new().solve(p, false)
+            })
+        })
+    });
+    g.bench_function("BitBoardSolver", |b| {
+        b.iter(|| solve_all(&puzzles, |p| solvers::BitBoardSolver::new().solve(p, false)))
+    });
+    g.bench_function("IterativeSolver/Mrv", |b| {
+        b.iter(|| {
+            solve_all(&puzzles, |p| {
+                solvers::IterativeSolver::new().solve(p, false)
+            })
+        })
+    });
+    g.bench_function("IterativeSolver/InOrder", |b| {
+        b.iter(|| {
+            solve_all(&puzzles, |p| {
+                solvers::IterativeSolver::with_chooser(choosers::InOrder::new()).solve(p, false)
+            })
+        })
+    });
+    g.bench_function("IterativeSolver/Random", |b| {
+        b.iter(|| {
+            solve_all(&puzzles, |p| {
+                solvers::IterativeSolver::with_chooser(choosers::Random::new()).solve(p, false)
+            })
+        })
+    });
+    g.finish();
+}
+
+pub fn heuristic(c: &mut Criterion) {
+    use solvers::{Branching, HeuristicSolver, ValueOrder};
+
+    let puzzles = puzzles(10);
+    let configs = [
+        ("Last", Branching::Last, ValueOrder::Ascending, false),
+        ("Mrv", Branching::Mrv, ValueOrder::Ascending, false),
+        (
+            "MrvDegree",
+            Branching::MrvDegree,
+            ValueOrder::Ascending,
+            false,
+        ),
+        (
+            "HiddenSingles",
+            Branching::Last,
+            ValueOrder::Ascending,
+            true,
+        ),
+        (
+            "LeastConstraining",
+            Branching::Last,
+            ValueOrder::LeastConstraining,
+            false,
+        ),
+        (
+            "Mrv+HiddenSingles",
+            Branching::Mrv,
+            ValueOrder::Ascending,
+            true,
+        ),
+    ];
+
+    let mut g = c.benchmark_group("Heuristic");
+    for &(name, branching, order, hidden) in &configs {
+        g.bench_function(name, |b| {
+            b.iter(|| {
+                solve_all(&puzzles, |p| {
+                    HeuristicSolver::new()
+                        .branching(branching)
+                        .value_order(order)
+                        .hidden_singles(hidden)
+                        .solve(p, false)
+                })
+            })
+        });
+    }
+    g.finish();
+}
+
+criterion_group!(benches, solver, engine, heuristic);
+criterion_main!(benches);
@@ -0,0 +1,189 @@
+//! Solving, checking, and rating many puzzles in parallel.
+//!
+//! This module is available with the `parallel` feature. Each function takes
+//! boards from an iterator or a slice and returns an iterator of results in
+//! the same order as the input. Boards are processed in chunks so that an
+//! arbitrarily long input doesn't have to fit in memory.
+//!
+//! # Examples
+//!
+//! ```
+//! use numpr::{batch, solvers, Board};
+//!
+//! let boards = vec![Board::default(); 4];
+//! let answers: Vec<_> = batch::solve(&boards, solvers::HeuristicSolver::new).collect();
+//! assert_eq!(answers.len(), 4);
+//! for a in answers {
+//!     a.unwrap().validate().unwrap();
+//! }
+//! ```
+
+use crate::board::Board;
+use crate::error::NumprResult;
+use crate::rating::{self, Difficulty};
+use crate::solver::Solver;
+use crate::unique::UniquenessChecker;
+use rayon::prelude::*;
+use std::borrow::Borrow;
+
+/// An iterator over results of applying a function to boards in parallel.
+///
+/// This struct is created by [`map`](fn.map.html).
+pub struct Batch<I, F, T> {
+    boards: I,
+    f: F,
+    chunk_size: usize,
+    done: std::vec::IntoIter<T>,
+}
+
+impl<I, F, T> Batch<I, F, T> {
+    /// Sets the number of boards processed at once. It defaults to 256 times
+    /// the number of threads.
+    pub fn chunk_size(mut self, n: usize) -> Self {
+        self.chunk_size = n.max(1);
+        self
+    }
+}
+
+impl<I, B, F, T> Iterator for Batch<I, F, T>
+where
+    I: Iterator<Item = B>,
+    B: Borrow<Board>,
+    F: Fn(&Board) -> T + Sync,
+    T: Send,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if let Some(t) = self.done.next() {
+            return Some(t);
+        }
+        let boards: Vec<Board> = self
+            .boards
+            .by_ref()
+            .take(self.chunk_size)
+            .map(|b| *b.borrow())
+            .collect();
+        if boards.is_empty() {
+            return None;
+        }
+        let results: Vec<T> = boards.par_iter().map(&self.f).collect();
+        self.done = results.into_iter();
+        self.done.next()
+    }
+}
+
+/// Applies `f` to all the boards in parallel.
+pub fn map<I, B, F, T>(boards: I, f: F) -> Batch<I::IntoIter, F, T>
+where
+    I: IntoIterator<Item = B>,
+    B: Borrow<Board>,
+    F: Fn(&Board) -> T + Sync,
+    T: Send,
+{
+    Batch {
+        boards: boards.into_iter(),
+        f,
+        chunk_size: rayon::current_num_threads() * 256,
+        done: Vec::new().into_iter(),
+    }
+}
+
+/// Solves all the boards in parallel with solvers created by `factory`.
+pub fn solve<I, B, S>(
+    boards: I,
+    factory: impl Fn() -> S + Sync,
+) -> impl Iterator<Item = NumprResult<Board>>
+where
+    I: IntoIterator<Item = B>,
+    B: Borrow<Board>,
+    S: Solver,
+{
+    map(boards, move |b| factory().solve(b, false))
+}
+
+/// Checks if each board has a unique answer in parallel.
+pub fn check<I, B, S, U>(
+    boards: I,
+    solver_factory: impl Fn() -> S + Sync,
+    unique_factory: impl Fn() -> U + Sync,
+) -> impl Iterator<Item = NumprResult<()>>
+where
+    I: IntoIterator<Item = B>,
+    B: Borrow<Board>,
+    S: Solver,
+    U: UniquenessChecker,
+{
+    map(boards, move |b| unique_factory().check(b, &solver_factory))
+}
+
+/// Rates all the boards in parallel. See [`rating::rate`](../rating/fn.rate.html).
+pub fn rate<I, B>(boards: I) -> impl Iterator<Item = NumprResult<Difficulty>>
+where
+    I: IntoIterator<Item = B>,
+    B: Borrow<Board>,
+{
+    map(boards, rating::rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pt::Pt;
+    use crate::solvers::HeuristicSolver;
+    use crate::uniques::NaiveUniquenessChecker;
+
+    fn boards() -> Vec<Board> {
+        let answer = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        (0..20)
+            .map(|i| {
+                let mut b = answer;
+                for x in 0..i % 10 {
+                    b.set(Pt::new(x, x).unwrap(), 0).unwrap();
+                }
+                if i >= 10 {
+                    // Moves the answer of (0, 0) to (0, 1), which already
+                    // has the number in its row, so the board is unsolvable.
+                    let n = answer.get(Pt::new(0, 0).unwrap()).unwrap();
+                    b.set(Pt::new(0, 0).unwrap(), 0).unwrap();
+                    b.set(Pt::new(0, 1).unwrap(), n).unwrap();
+                }
+                b
+            })
+            .collect()
+    }
+
+    #[test]
+    fn order() {
+        let boards = boards();
+        let sums: Vec<u32> = map(boards.iter().cloned(), |b| {
+            b.iter().map(|(_, n)| n.unwrap_or(0) as u32).sum()
+        })
+        .chunk_size(3)
+        .collect();
+        let expected: Vec<u32> = boards
+            .iter()
+            .map(|b| b.iter().map(|(_, n)| n.unwrap_or(0) as u32).sum())
+            .collect();
+        assert_eq!(sums, expected);
+    }
+
+    #[test]
+    fn errors() {
+        let boards = boards();
+        let solved: Vec<bool> = solve(&boards, HeuristicSolver::new)
+            .map(|r| r.is_ok())
+            .collect();
+        let checked: Vec<bool> = check(&boards, HeuristicSolver::new, NaiveUniquenessChecker::new)
+            .map(|r| r.is_ok())
+            .collect();
+        let rated: Vec<bool> = rate(&boards).map(|r| r.is_ok()).collect();
+        for (i, ok) in solved.into_iter().enumerate() {
+            assert_eq!(ok, i < 10, "{}", i);
+            assert_eq!(checked[i], i < 10, "{}", i);
+            assert_eq!(rated[i], i < 10, "{}", i);
+        }
+    }
+}
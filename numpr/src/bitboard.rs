@@ -0,0 +1,190 @@
+use crate::board::Board;
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+
+const ALL: u16 = 0b11_1111_1110;
+
+/// A board maintaining numbers used in each row, column, and block as bit
+/// masks.
+///
+/// Unlike [`Board`](struct.Board.html), the masks are updated incrementally
+/// by `set`, so querying candidates of a grid is O(1). It never holds numbers
+/// conflicting with each other.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::{BitBoard, Board, Pt};
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let mut b = BitBoard::new(&Board::default())?;
+/// b.set(Pt::new(0, 0)?, 5)?;
+/// assert!(!b.is_placeable(Pt::new(8, 0)?, 5));
+/// assert!(b.set(Pt::new(1, 1)?, 5).is_err());
+/// assert_eq!(b.candidates(Pt::new(4, 4)?), 0b11_1111_1110);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct BitBoard {
+    numbers: [u8; SIZE],
+    rows: [u16; HEIGHT],
+    cols: [u16; WIDTH],
+    blocks: [u16; BLOCK_SIZE],
+}
+
+fn block_of(x: usize, y: usize) -> usize {
+    y / BLOCK_HEIGHT * (WIDTH / BLOCK_WIDTH) + x / BLOCK_WIDTH
+}
+
+impl BitBoard {
+    /// Returns a `BitBoard` having the same numbers as `board`.
+    ///
+    /// It fails with `NumprError::InvalidValue` when a number conflicts with
+    /// another one in the same row, column, or block.
+    pub fn new(board: &Board) -> NumprResult<Self> {
+        let mut b = Self {
+            numbers: [0; SIZE],
+            rows: [0; HEIGHT],
+            cols: [0; WIDTH],
+            blocks: [0; BLOCK_SIZE],
+        };
+        for (pt, n) in board.iter() {
+            if let Some(n) = n {
+                b.set(pt, n)?;
+            }
+        }
+        Ok(b)
+    }
+
+    /// Returns the number at `pt`.
+    pub fn get(&self, pt: Pt) -> Option<u8> {
+        match self.numbers[pt.index()] {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    /// Returns a bit mask of numbers that can be placed at `pt`, where the
+    /// n-th bit is set when n can be placed. It returns `0` for filled grids.
+    pub fn candidates(&self, pt: Pt) -> u16 {
+        if self.numbers[pt.index()] != 0 {
+            return 0;
+        }
+        self.raw_candidates(pt.index())
+    }
+
+    /// Returns `true` when `n` can be placed at the empty grid `pt`.
+    pub fn is_placeable(&self, pt: Pt, n: u8) -> bool {
+        self.candidates(pt) & (1 << n) != 0
+    }
+
+    /// Puts `n` at `pt`. `0` clears the grid.
+    ///
+    /// It fails with `NumprError::InvalidValue` when `n` isn't within `[0, 9]`
+    /// or conflicts with another number.
+    pub fn set(&mut self, pt: Pt, n: u8) -> NumprResult<()> {
+        if n > 9 {
+            return NumprError::invalid_value(pt, n);
+        }
+        let prev = self.numbers[pt.index()];
+        if prev == n {
+            return Ok(());
+        }
+        let (x, y, k) = (pt.x(), pt.y(), block_of(pt.x(), pt.y()));
+        let bit = (1 << n) & ALL;
+        if (self.rows[y] | self.cols[x] | self.blocks[k]) & !(1 << prev) & bit != 0 {
+            return NumprError::invalid_value(pt, n);
+        }
+
+        let clear = !(1u16 << prev);
+        self.rows[y] = self.rows[y] & clear | bit;
+        self.cols[x] = self.cols[x] & clear | bit;
+        self.blocks[k] = self.blocks[k] & clear | bit;
+        self.numbers[pt.index()] = n;
+        Ok(())
+    }
+
+    /// Puts `n` at the grid at the index `i` without checking conflicts. The
+    /// grid must be empty and `n` must be one of its candidates.
+    pub(crate) fn place(&mut self, i: usize, n: u8) {
+        let bit = 1 << n;
+        let (x, y) = (i % WIDTH, i / WIDTH);
+        self.rows[y] |= bit;
+        self.cols[x] |= bit;
+        self.blocks[block_of(x, y)] |= bit;
+        self.numbers[i] = n;
+    }
+
+    /// Returns candidates of the grid at the index `i`. The grid must be empty.
+    pub(crate) fn raw_candidates(&self, i: usize) -> u16 {
+        let (x, y) = (i % WIDTH, i / WIDTH);
+        ALL & !(self.rows[y] | self.cols[x] | self.blocks[block_of(x, y)])
+    }
+
+    pub(crate) fn raw_get(&self, i: usize) -> u8 {
+        self.numbers[i]
+    }
+
+    /// Returns a `Board` having the same numbers.
+    pub fn to_board(&self) -> Board {
+        Board::new(&self.numbers).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pt::PtIter;
+
+    #[test]
+    fn set() {
+        let mut b = BitBoard::new(&Board::default()).unwrap();
+        let pt = Pt::new(4, 4).unwrap();
+        b.set(pt, 3).unwrap();
+        assert_eq!(b.get(pt), Some(3));
+        assert_eq!(b.candidates(pt), 0);
+        for p in PtIter::row(pt)
+            .chain(PtIter::col(pt))
+            .chain(PtIter::block(pt))
+        {
+            assert!(p == pt || !b.is_placeable(p, 3));
+        }
+        assert!(b.is_placeable(Pt::new(0, 0).unwrap(), 3));
+
+        // Conflicts don't change anything.
+        assert!(b.set(Pt::new(3, 3).unwrap(), 3).is_err());
+        assert_eq!(b.get(Pt::new(3, 3).unwrap()), None);
+
+        b.set(pt, 4).unwrap();
+        assert!(b.is_placeable(Pt::new(4, 0).unwrap(), 3));
+        assert!(!b.is_placeable(Pt::new(4, 0).unwrap(), 4));
+        b.set(pt, 0).unwrap();
+        assert_eq!(b.candidates(pt), ALL);
+        assert!(b.set(pt, 10).is_err());
+    }
+
+    #[test]
+    fn same_as_board() {
+        let mut n = [0; SIZE];
+        n[..9].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        n[9..12].copy_from_slice(&[4, 5, 6]);
+        let board = Board::new(&n).unwrap();
+        let b = BitBoard::new(&board).unwrap();
+        for pt in PtIter::all() {
+            let expected = board
+                .candidates(pt, false)
+                .into_iter()
+                .fold(0, |m, c| m | 1 << c);
+            assert_eq!(b.candidates(pt), expected);
+        }
+        assert!(b.to_board().iter().eq(board.iter()));
+
+        n[80] = 1;
+        assert!(BitBoard::new(&Board::new(&n).unwrap()).is_ok());
+        n[72] = 1;
+        assert!(BitBoard::new(&Board::new(&n).unwrap()).is_err());
+    }
+}
@@ -42,6 +42,11 @@ impl Board {
         self.numbers[pt.index()]
     }
 
+    /// Returns the numbers of all the grids, where empty grids are `0`.
+    pub(crate) fn numbers(&self) -> &[u8; SIZE] {
+        &self.numbers
+    }
+
     pub fn set(&mut self, pt: Pt, n: u8) -> NumprResult<()> {
         if n > 9 {
             return NumprError::invalid_value(pt, n);
@@ -0,0 +1,232 @@
+//! Limits on searches done by solvers and uniqueness checkers.
+
+use crate::error::{NumprError, NumprResult};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A token to cancel searches from another thread.
+///
+/// Clones of a token share the same state.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Returns a new token that isn't cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels all the searches using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` when the token is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A budget of a search: the maximum number of nodes, a deadline, and a
+/// cancellation token.
+///
+/// Searches fail with `NumprError::BudgetExceeded` when any of them runs out.
+/// Clones of a budget share the number of nodes visited, so a budget passed
+/// to a uniqueness checker and to the solvers it creates limits all of them
+/// together.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::budget::Budget;
+/// use numpr::{solvers, Board, NumprError, Solver};
+///
+/// let budget = Budget::new().max_nodes(10);
+/// let res = solvers::NaiveSolver::new()
+///     .with_budget(budget)
+///     .solve(&Board::default(), false);
+/// assert_eq!(res.err(), Some(NumprError::BudgetExceeded));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    max_nodes: Option<u64>,
+    deadline: Option<Instant>,
+    token: CancelToken,
+    nodes: Arc<AtomicU64>,
+}
+
+// The deadline is checked once in this number of nodes because reading the
+// clock is much slower than visiting a node. It must be a power of two.
+const CLOCK_INTERVAL: u64 = 256;
+
+impl Budget {
+    /// Returns an unlimited budget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of nodes visited.
+    pub fn max_nodes(mut self, n: u64) -> Self {
+        self.max_nodes = Some(n);
+        self
+    }
+
+    /// Sets the deadline.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets the deadline to `timeout` from now.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.deadline(Instant::now() + timeout)
+    }
+
+    /// Uses `token` to cancel searches.
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Returns the token cancelling searches using this budget.
+    pub fn token(&self) -> CancelToken {
+        self.token.clone()
+    }
+
+    /// Returns the number of nodes visited so far.
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// Counts a node visited. It fails with `NumprError::BudgetExceeded` when
+    /// the budget runs out.
+    pub fn spend(&self) -> NumprResult<()> {
+        let n = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+        if matches!(self.max_nodes, Some(m) if n > m) || self.token.is_cancelled() {
+            return NumprError::budget_exceeded();
+        }
+        if n & (CLOCK_INTERVAL - 1) == 0 {
+            self.check_deadline()?;
+        }
+        Ok(())
+    }
+
+    /// Checks the budget without counting a node.
+    pub fn check(&self) -> NumprResult<()> {
+        if matches!(self.max_nodes, Some(m) if self.nodes() > m) || self.token.is_cancelled() {
+            return NumprError::budget_exceeded();
+        }
+        self.check_deadline()
+    }
+
+    fn check_deadline(&self) -> NumprResult<()> {
+        match self.deadline {
+            Some(d) if Instant::now() >= d => NumprError::budget_exceeded(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Calls `spend` of the budget if any.
+#[inline]
+pub(crate) fn spend(budget: &Option<Budget>) -> NumprResult<()> {
+    match budget {
+        Some(b) => b.spend(),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_nodes() {
+        let b = Budget::new().max_nodes(3);
+        let c = b.clone();
+        b.spend().unwrap();
+        c.spend().unwrap();
+        b.check().unwrap();
+        c.spend().unwrap();
+        assert_eq!(b.nodes(), 3);
+        assert!(b.spend().is_err());
+        assert!(c.check().is_err());
+    }
+
+    #[test]
+    fn deadline() {
+        let b = Budget::new().timeout(Duration::from_secs(3600));
+        b.check().unwrap();
+        let b = Budget::new().deadline(Instant::now());
+        assert!(b.check().is_err());
+        for _ in 0..CLOCK_INTERVAL - 1 {
+            b.spend().unwrap();
+        }
+        assert!(b.spend().is_err());
+    }
+
+    #[test]
+    fn solvers() {
+        use crate::solvers::*;
+        use crate::{Board, Rules, Solver};
+
+        let b = Board::default();
+        let budget = || Budget::new().max_nodes(5);
+        let results = vec![
+            NaiveSolver::new().with_budget(budget()).solve(&b, true),
+            HeuristicSolver::new().with_budget(budget()).solve(&b, true),
+            BitBoardSolver::new().with_budget(budget()).solve(&b, true),
+            BitParallelSolver::new()
+                .with_budget(budget())
+                .solve(&b, true),
+            VariantSolver::new(Rules::new())
+                .with_budget(budget())
+                .solve(&b, true),
+        ];
+        for res in results {
+            assert_eq!(res.err(), Some(NumprError::BudgetExceeded));
+        }
+        assert!(BitParallelSolver::new()
+            .with_budget(budget())
+            .count(&b, 2)
+            .is_err());
+    }
+
+    #[test]
+    fn uniqueness() {
+        use crate::generators::NaiveGenerator;
+        use crate::solvers::HeuristicSolver;
+        use crate::uniques::NaiveUniquenessChecker;
+        use crate::{Board, Generator, Solver, UniquenessChecker};
+
+        let answer = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        let mut b = answer;
+        b.set(crate::Pt::new(0, 0).unwrap(), 0).unwrap();
+        b.set(crate::Pt::new(1, 1).unwrap(), 0).unwrap();
+
+        let budget = Budget::new().max_nodes(1);
+        let solver = || HeuristicSolver::new().with_budget(budget.clone());
+        let res = NaiveUniquenessChecker::new()
+            .with_budget(budget.clone())
+            .check(&b, solver);
+        assert_eq!(res.err(), Some(NumprError::BudgetExceeded));
+
+        // Generators give up instead of keeping the grid.
+        let res = NaiveGenerator::new(10).generate(&answer, solver, || {
+            NaiveUniquenessChecker::new().with_budget(budget.clone())
+        });
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn cancel() {
+        let b = Budget::new();
+        b.spend().unwrap();
+        b.token().cancel();
+        assert!(b.spend().is_err());
+        assert!(b.check().is_err());
+    }
+}
@@ -0,0 +1,233 @@
+//! Canonical forms of boards.
+//!
+//! Two boards are equivalent when one can be transformed to the other by a
+//! [`Transform`](../struct.Transform.html): relabeling numbers, permuting rows
+//! within bands and columns within stacks, permuting bands and stacks, and
+//! transposing. The canonical form is the lexicographically smallest board of
+//! all the equivalent ones, comparing grids from the top left corner row by
+//! row with empty grids as 0.
+//!
+//! # Examples
+//!
+//! ```
+//! use numpr::{canonical, solvers, Board, Pt, Solver};
+//!
+//! # use numpr::NumprResult;
+//! # fn main() -> NumprResult<()> {
+//! let a = solvers::HeuristicSolver::new().solve(&Board::default(), true)?;
+//! let b = canonical::canonical_form(&a);
+//! let t = canonical::are_equivalent(&a, &b).unwrap();
+//! assert!(t.apply(&a).iter().eq(b.iter()));
+//!
+//! let mut c = b;
+//! c.set(Pt::new(0, 0)?, 0)?;
+//! assert!(canonical::are_equivalent(&a, &c).is_none());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::board::Board;
+use crate::consts::*;
+use crate::pt::Pt;
+use crate::transform::Transform;
+
+/// Returns the canonical form of `board`.
+pub fn canonical_form(board: &Board) -> Board {
+    canonicalize(board).0
+}
+
+/// Returns the canonical form of `board` and the transformation from `board`
+/// to it.
+pub fn canonicalize(board: &Board) -> (Board, Transform) {
+    let mut s = Search {
+        grid: [[0; 9]; 9],
+        out: [0; SIZE],
+        rows: [0; 9],
+        best: [0; SIZE],
+        found: None,
+        transpose: false,
+        cols: [0; 9],
+    };
+    for &transpose in &[false, true] {
+        s.transpose = transpose;
+        for cols in permutations() {
+            s.cols = cols;
+            for (r, row) in s.grid.iter_mut().enumerate() {
+                for (c, n) in row.iter_mut().enumerate() {
+                    let (y, x) = if transpose {
+                        (cols[c] as usize, r)
+                    } else {
+                        (r, cols[c] as usize)
+                    };
+                    *n = board.raw_get(Pt::new(x, y).unwrap());
+                }
+            }
+            s.search(0, 0, [0; 10], 1);
+        }
+    }
+
+    let t = s.found.unwrap();
+    let mut b = Board::default();
+    for (i, &n) in s.best.iter().enumerate() {
+        b.set(Pt::new(i % WIDTH, i / WIDTH).unwrap(), n).unwrap();
+    }
+    (b, t)
+}
+
+/// Returns the transformation from `a` to `b` if they are equivalent.
+pub fn are_equivalent(a: &Board, b: &Board) -> Option<Transform> {
+    let (ca, ta) = canonicalize(a);
+    let (cb, tb) = canonicalize(b);
+    if ca.iter().eq(cb.iter()) {
+        Some(ta.then(&tb.inverse()))
+    } else {
+        None
+    }
+}
+
+/// A branch and bound search of row orders for a fixed column order.
+struct Search {
+    /// The board with transposition and the column order applied.
+    grid: [[u8; 9]; 9],
+    out: [u8; SIZE],
+    rows: [u8; 9],
+    best: [u8; SIZE],
+    found: Option<Transform>,
+    transpose: bool,
+    cols: [u8; 9],
+}
+
+impl Search {
+    /// Chooses the `k`-th row from rows not in `used`, relabeling numbers
+    /// in order of appearance.
+    fn search(&mut self, k: usize, used: u16, digits: [u8; 10], next: u8) {
+        if k == HEIGHT {
+            let better = match self.found {
+                Some(_) => self.out < self.best,
+                None => true,
+            };
+            if better {
+                self.best = self.out;
+                self.found = Some(self.transform(digits, next));
+            }
+            return;
+        }
+
+        for r in 0..HEIGHT {
+            let allowed = if k.is_multiple_of(BLOCK_HEIGHT) {
+                // Any row in an unused band.
+                used & (0b111 << (r / BLOCK_HEIGHT * BLOCK_HEIGHT)) == 0
+            } else {
+                r / BLOCK_HEIGHT == self.rows[k - 1] as usize / BLOCK_HEIGHT && used & 1 << r == 0
+            };
+            if !allowed {
+                continue;
+            }
+
+            let (mut d, mut n) = (digits, next);
+            for c in 0..WIDTH {
+                let v = self.grid[r][c] as usize;
+                if v != 0 && d[v] == 0 {
+                    d[v] = n;
+                    n += 1;
+                }
+                self.out[k * WIDTH + c] = d[v];
+            }
+            // Rows chosen so far are never larger than the best one.
+            if self.found.is_some() {
+                let end = (k + 1) * WIDTH;
+                if self.out[..end] > self.best[..end] {
+                    continue;
+                }
+            }
+            self.rows[k] = r as u8;
+            self.search(k + 1, used | 1 << r, d, n);
+        }
+    }
+
+    fn transform(&self, mut digits: [u8; 10], mut next: u8) -> Transform {
+        // Numbers not on the board take the remaining labels.
+        for d in digits.iter_mut().skip(1) {
+            if *d == 0 {
+                *d = next;
+                next += 1;
+            }
+        }
+        Transform::from_parts(self.transpose, self.rows, self.cols, digits)
+    }
+}
+
+/// Iterates over permutations of 9 rows or columns keeping bands or stacks.
+fn permutations() -> impl Iterator<Item = [u8; 9]> {
+    const P: [[u8; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    (0..6usize.pow(4)).map(|mut i| {
+        let mut p = [0; 9];
+        let bands = P[i % 6];
+        for (b, &band) in bands.iter().enumerate() {
+            i /= 6;
+            for (j, &r) in P[i % 6].iter().enumerate() {
+                p[b * 3 + j] = band * 3 + r;
+            }
+        }
+        p
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use crate::solvers::HeuristicSolver;
+
+    fn answer() -> Board {
+        HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap()
+    }
+
+    #[test]
+    fn permutations() {
+        let p: Vec<_> = super::permutations().collect();
+        assert_eq!(p.len(), 1296);
+        let mut sorted = p.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 1296);
+    }
+
+    #[test]
+    fn canonical() {
+        let b = answer();
+        let (c, t) = canonicalize(&b);
+        assert!(t.apply(&b).iter().eq(c.iter()));
+        assert!(c.validate().is_ok());
+        // The first row is always relabeled to 1 to 9.
+        assert!((0..9).all(|x| c.get(Pt::new(x, 0).unwrap()) == Some(x as u8 + 1)));
+
+        let u = Transform::from_parts(
+            true,
+            [3, 5, 4, 0, 1, 2, 8, 7, 6],
+            [6, 8, 7, 0, 1, 2, 5, 3, 4],
+            [0, 9, 8, 7, 6, 5, 4, 3, 2, 1],
+        );
+        let mut p = u.apply(&b);
+        assert!(canonical_form(&p).iter().eq(c.iter()));
+        p.set(Pt::new(4, 4).unwrap(), 0).unwrap();
+        p.set(Pt::new(0, 8).unwrap(), 0).unwrap();
+        let t = are_equivalent(&b, &u.apply(&b)).unwrap();
+        assert!(t.apply(&b).iter().eq(u.apply(&b).iter()));
+
+        let mut q = b;
+        q.set(Pt::new(8, 0).unwrap(), 0).unwrap();
+        assert!(are_equivalent(&q, &p).is_none());
+        let t = are_equivalent(&p, &u.inverse().apply(&p)).unwrap();
+        assert!(t.apply(&p).iter().eq(u.inverse().apply(&p).iter()));
+    }
+}
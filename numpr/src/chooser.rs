@@ -0,0 +1,18 @@
+use crate::consts::SIZE;
+use crate::pt::Pt;
+
+/// A trait to choose a grid to branch on in the search
+/// [`Engine`](engine/struct.Engine.html).
+pub trait Chooser {
+    /// Returns an empty grid to branch on.
+    ///
+    /// `candidates[i]` is a bit mask of candidates of the grid at the index
+    /// `i`, where the n-th bit is set when n can be placed. It's `0` for
+    /// filled grids. All the empty grids have at least two candidates.
+    fn choose(&mut self, candidates: &[u16; SIZE]) -> Pt;
+
+    /// Sorts `values`, the candidates of `pt`, in the order to be tried.
+    /// `candidates` is the same as in `choose`. It keeps the given order,
+    /// which is ascending unless the engine shuffles it, by default.
+    fn order(&mut self, _candidates: &[u16; SIZE], _pt: Pt, _values: &mut [u8]) {}
+}
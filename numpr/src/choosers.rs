@@ -0,0 +1,95 @@
+//! Implementations of [`Chooser`](../trait.Chooser.html).
+
+use crate::chooser::Chooser;
+use crate::consts::*;
+use crate::pt::Pt;
+use rand::prelude::*;
+
+fn pt(i: usize) -> Pt {
+    Pt::new(i % WIDTH, i / WIDTH).unwrap()
+}
+
+/// Chooses the grid having the fewest candidates, which is known as the
+/// minimum remaining values heuristic. Ties are broken by the order of grids.
+#[derive(Default)]
+pub struct Mrv {}
+
+impl Mrv {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Chooser for Mrv {
+    fn choose(&mut self, candidates: &[u16; SIZE]) -> Pt {
+        let mut best = 0;
+        let mut fewest = u32::MAX;
+        for (i, m) in candidates.iter().enumerate() {
+            let cnt = m.count_ones();
+            if cnt != 0 && cnt < fewest {
+                best = i;
+                fewest = cnt;
+                if cnt == 2 {
+                    break;
+                }
+            }
+        }
+        pt(best)
+    }
+}
+
+/// Chooses the first empty grid from the top left corner.
+#[derive(Default)]
+pub struct InOrder {}
+
+impl InOrder {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Chooser for InOrder {
+    fn choose(&mut self, candidates: &[u16; SIZE]) -> Pt {
+        pt(candidates.iter().position(|&m| m != 0).unwrap())
+    }
+}
+
+/// Chooses a random empty grid and tries its candidates in random order.
+#[derive(Default)]
+pub struct Random {}
+
+impl Random {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Chooser for Random {
+    fn choose(&mut self, candidates: &[u16; SIZE]) -> Pt {
+        let empty: Vec<usize> = (0..SIZE).filter(|&i| candidates[i] != 0).collect();
+        pt(*empty.choose(&mut rand::thread_rng()).unwrap())
+    }
+
+    fn order(&mut self, _candidates: &[u16; SIZE], _pt: Pt, values: &mut [u8]) {
+        values.shuffle(&mut rand::thread_rng());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choose() {
+        let mut c = [0u16; SIZE];
+        c[3] = 0b1110;
+        c[5] = 0b0110;
+        c[7] = 0b1110;
+        assert_eq!(Mrv::new().choose(&c), pt(5));
+        assert_eq!(InOrder::new().choose(&c), pt(3));
+        for _ in 0..10 {
+            let p = Random::new().choose(&c);
+            assert!(c[p.index()] != 0);
+        }
+    }
+}
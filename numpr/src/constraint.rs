@@ -0,0 +1,34 @@
+use crate::board::Board;
+use crate::consts::SIZE;
+use crate::error::NumprResult;
+use std::any::Any;
+
+/// A trait for a variant rule that a solution has to satisfy in addition to
+/// the classic row, column, and block rules.
+///
+/// Constraints are combined into [`Rules`](struct.Rules.html) and enforced by
+/// [`VariantSolver`](solvers/struct.VariantSolver.html).
+pub trait Constraint: Send + Sync {
+    /// Checks if a completed board satisfies the constraint.
+    ///
+    /// It returns `NumprError::WrongAnswer` when the constraint is violated.
+    fn validate(&self, board: &Board) -> NumprResult<()>;
+
+    /// Removes candidates that cannot satisfy the constraint.
+    ///
+    /// `masks` holds a bit mask of candidates for each grid, where the n-th
+    /// bit is set when n can be placed in the grid. A filled grid only has the
+    /// bit of its number. Propagation doesn't have to be complete, but it must
+    /// never remove a candidate that can be a part of a valid answer.
+    ///
+    /// It returns `true` when any of the masks changed, and
+    /// `NumprError::Unsolvable` when a grid runs out of candidates.
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool>;
+
+    /// Returns the constraint as `Any` so that it can be downcast to its
+    /// concrete type, for example to render it. Constraints that don't need
+    /// to be inspected can keep the default, which returns `None`.
+    fn as_any(&self) -> Option<&dyn Any> {
+        None
+    }
+}
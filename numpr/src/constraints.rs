@@ -0,0 +1,27 @@
+mod arrow;
+pub(crate) mod common;
+mod edges;
+mod inequality;
+mod kropki;
+mod little_killer;
+mod outside;
+mod palindrome;
+mod renban;
+mod sandwich;
+mod skyscraper;
+mod thermometer;
+mod whisper;
+mod xv;
+
+pub use arrow::Arrow;
+pub use inequality::Inequality;
+pub use kropki::Kropki;
+pub use little_killer::{Diagonal, LittleKiller};
+pub use outside::{Outside, Side};
+pub use palindrome::Palindrome;
+pub use renban::Renban;
+pub use sandwich::Sandwich;
+pub use skyscraper::Skyscraper;
+pub use thermometer::Thermometer;
+pub use whisper::GermanWhispers;
+pub use xv::XV;
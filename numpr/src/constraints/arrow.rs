@@ -0,0 +1,123 @@
+use super::common::*;
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use std::any::Any;
+
+/// Numbers on an arrow sum to the number in its circle. Numbers may repeat on
+/// an arrow unless the classic rules forbid it.
+pub struct Arrow {
+    circle: Pt,
+    arrow: Vec<Pt>,
+}
+
+impl Arrow {
+    /// Returns an arrow drawn from `circle` through `arrow`.
+    ///
+    /// It fails when the circle and the arrow don't form a connected line or
+    /// the arrow has more than 8 grids.
+    pub fn new(circle: Pt, arrow: Vec<Pt>) -> NumprResult<Self> {
+        let mut cells = vec![circle];
+        cells.extend(arrow);
+        let mut cells = line(cells, 2, 9)?;
+        let arrow = cells.split_off(1);
+        Ok(Self { circle, arrow })
+    }
+
+    /// Returns the grid with the circle.
+    pub fn circle(&self) -> Pt {
+        self.circle
+    }
+
+    /// Returns grids on the arrow, starting next to the circle.
+    pub fn arrow(&self) -> &[Pt] {
+        &self.arrow
+    }
+}
+
+impl Constraint for Arrow {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        let sum: u32 = values(board, &self.arrow)?.iter().map(|&n| n as u32).sum();
+        match board.get(self.circle) {
+            Some(n) if n as u32 == sum => Ok(()),
+            _ => NumprError::wrong_answer(),
+        }
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let lo: i32 = self
+            .arrow
+            .iter()
+            .map(|p| min(masks[p.index()]) as i32)
+            .sum();
+        let hi: i32 = self
+            .arrow
+            .iter()
+            .map(|p| max(masks[p.index()]) as i32)
+            .sum();
+        let c = self.circle.index();
+        let mut changed = restrict(masks, c, range(lo, hi))?;
+
+        let (cmin, cmax) = (min(masks[c]) as i32, max(masks[c]) as i32);
+        for p in &self.arrow {
+            let m = masks[p.index()];
+            let rest_lo = lo - min(m) as i32;
+            let rest_hi = hi - max(m) as i32;
+            changed |= restrict(masks, p.index(), range(cmin - rest_hi, cmax - rest_lo))?;
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arrow() -> Arrow {
+        let p = |x, y| Pt::new(x, y).unwrap();
+        Arrow::new(p(0, 0), vec![p(1, 1), p(2, 2), p(3, 3)]).unwrap()
+    }
+
+    #[test]
+    fn propagate() {
+        let a = arrow();
+        let mut masks = [ALL; SIZE];
+        assert!(a.propagate(&mut masks).unwrap());
+        // The circle takes at least 1 + 1 + 1.
+        assert_eq!(masks[0], range(3, 9));
+        // Each grid on the arrow takes at most 9 - 1 - 1.
+        assert_eq!(masks[10], range(1, 7));
+
+        masks[0] = 1 << 4;
+        masks[10] = 1 << 2;
+        a.propagate(&mut masks).unwrap();
+        assert_eq!(masks[20], range(1, 1));
+        assert_eq!(masks[30], range(1, 1));
+    }
+
+    #[test]
+    fn validate() {
+        let a = arrow();
+        let mut b = Board::default();
+        for (p, n) in [(0, 6), (10, 1), (20, 2), (30, 3)].iter() {
+            b.set(Pt::new(p % 9, p / 9).unwrap(), *n).unwrap();
+        }
+        a.validate(&b).unwrap();
+        b.set(Pt::new(0, 0).unwrap(), 7).unwrap();
+        assert!(a.validate(&b).is_err());
+    }
+
+    #[test]
+    fn too_long() {
+        let cells = (1..9).map(|x| Pt::new(x, 0).unwrap()).collect();
+        assert!(Arrow::new(Pt::new(0, 0).unwrap(), cells).is_ok());
+        let cells = (0..9).map(|x| Pt::new(x, 1).unwrap()).collect();
+        assert!(Arrow::new(Pt::new(0, 0).unwrap(), cells).is_err());
+    }
+}
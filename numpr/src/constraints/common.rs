@@ -0,0 +1,159 @@
+//! Helpers shared by constraints to manipulate candidate masks.
+
+use crate::board::Board;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+
+/// A mask having all numbers from 1 to 9.
+pub(crate) const ALL: u16 = 0b11_1111_1110;
+
+/// Returns the smallest number in a non-empty mask.
+pub(crate) fn min(m: u16) -> u8 {
+    m.trailing_zeros() as u8
+}
+
+/// Returns the largest number in a non-empty mask.
+pub(crate) fn max(m: u16) -> u8 {
+    15 - m.leading_zeros() as u8
+}
+
+/// Returns a mask of numbers greater than `n`.
+pub(crate) fn above(n: u8) -> u16 {
+    ALL & !((2u16 << n) - 1)
+}
+
+/// Returns a mask of numbers less than `n`.
+pub(crate) fn below(n: u8) -> u16 {
+    ALL & ((1u16 << n.min(10)) - 1)
+}
+
+/// Returns a mask of numbers within `[lo, hi]`.
+pub(crate) fn range(lo: i32, hi: i32) -> u16 {
+    let lo = lo.max(1);
+    let hi = hi.min(9);
+    if lo > hi {
+        return 0;
+    }
+    below(hi as u8 + 1) & above(lo as u8 - 1)
+}
+
+/// Iterates over numbers in a mask in ascending order.
+pub(crate) fn digits(m: u16) -> impl Iterator<Item = u8> {
+    (1..=9).filter(move |n| m & (1 << n) != 0)
+}
+
+/// Narrows the mask at `i` down to `m`.
+pub(crate) fn restrict(masks: &mut [u16], i: usize, m: u16) -> NumprResult<bool> {
+    let next = masks[i] & m;
+    if next == 0 {
+        return NumprError::unsolvable();
+    }
+    let changed = next != masks[i];
+    masks[i] = next;
+    Ok(changed)
+}
+
+/// Makes a pair of grids arc consistent with regard to `rel`. `rel(a, b)`
+/// must return `true` when `a` at `p` and `b` at `q` are allowed.
+pub(crate) fn restrict_pair(
+    masks: &mut [u16],
+    p: usize,
+    q: usize,
+    rel: impl Fn(u8, u8) -> bool,
+) -> NumprResult<bool> {
+    let (mut mp, mut mq) = (0, 0);
+    for a in digits(masks[p]) {
+        for b in digits(masks[q]) {
+            if rel(a, b) {
+                mp |= 1 << a;
+                mq |= 1 << b;
+            }
+        }
+    }
+    Ok(restrict(masks, p, mp)? | restrict(masks, q, mq)?)
+}
+
+/// Returns `true` when two grids are next to each other orthogonally or
+/// diagonally.
+pub(crate) fn touching(a: Pt, b: Pt) -> bool {
+    a != b
+        && (a.x() as isize - b.x() as isize).abs() <= 1
+        && (a.y() as isize - b.y() as isize).abs() <= 1
+}
+
+/// Returns `true` when two grids share an edge.
+pub(crate) fn adjacent(a: Pt, b: Pt) -> bool {
+    (a.x() as isize - b.x() as isize).abs() + (a.y() as isize - b.y() as isize).abs() == 1
+}
+
+/// Validates that `cells` forms a line of connected and distinct grids.
+pub(crate) fn line(cells: Vec<Pt>, min_len: usize, max_len: usize) -> NumprResult<Vec<Pt>> {
+    if cells.len() < min_len || cells.len() > max_len {
+        return NumprError::invalid_constraint(format!(
+            "a line must have {} to {} grids: len = {}",
+            min_len,
+            max_len,
+            cells.len()
+        ));
+    }
+    if cells.windows(2).any(|w| !touching(w[0], w[1])) {
+        return NumprError::invalid_constraint("grids on a line must be connected");
+    }
+    for (i, p) in cells.iter().enumerate() {
+        if cells[i + 1..].contains(p) {
+            return NumprError::invalid_constraint(format!(
+                "a line visits ({}, {}) twice",
+                p.x(),
+                p.y()
+            ));
+        }
+    }
+    Ok(cells)
+}
+
+/// Returns numbers placed on `cells`. It fails with `NumprError::WrongAnswer`
+/// when any of them is empty.
+pub(crate) fn values(board: &Board, cells: &[Pt]) -> NumprResult<Vec<u8>> {
+    cells
+        .iter()
+        .map(|&p| board.get(p).map_or_else(NumprError::wrong_answer, Ok))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        assert_eq!(min(0b1010_0000), 5);
+        assert_eq!(max(0b1010_0000), 7);
+        assert_eq!(above(0), ALL);
+        assert_eq!(above(7), 0b11_0000_0000);
+        assert_eq!(above(9), 0);
+        assert_eq!(below(1), 0);
+        assert_eq!(below(3), 0b110);
+        assert_eq!(below(10), ALL);
+        assert_eq!(range(3, 4), 0b1_1000);
+        assert_eq!(range(-4, 12), ALL);
+        assert_eq!(range(5, 4), 0);
+    }
+
+    #[test]
+    fn pair() {
+        let mut masks = [ALL; 2];
+        assert!(restrict_pair(&mut masks, 0, 1, |a, b| a + b == 3).unwrap());
+        assert_eq!(masks, [0b110, 0b110]);
+        assert!(!restrict_pair(&mut masks, 0, 1, |a, b| a + b == 3).unwrap());
+        assert!(restrict_pair(&mut masks, 0, 1, |a, b| a > b + 5).is_err());
+    }
+
+    #[test]
+    fn invalid_line() {
+        let p = |x, y| Pt::new(x, y).unwrap();
+        assert!(line(vec![p(0, 0)], 2, 9).is_err());
+        assert!(line(vec![p(0, 0), p(2, 0)], 2, 9).is_err());
+        assert!(line(vec![p(0, 0), p(1, 1), p(0, 0)], 2, 9).is_err());
+        assert!(line(vec![p(0, 0), p(1, 1), p(1, 0)], 2, 9).is_ok());
+    }
+}
@@ -0,0 +1,115 @@
+use super::common::*;
+use crate::board::Board;
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::{Pt, PtIter};
+
+/// A relation between numbers on both sides of a marker.
+pub(crate) type Relation = fn(u8, u8) -> bool;
+
+/// Markers placed on edges between orthogonally adjacent grids.
+///
+/// Each marker has a kind that is an index to the relations passed to
+/// `validate` and `propagate`. In the negative mode, none of the relations
+/// holds between grids without a marker.
+#[derive(Default)]
+pub(crate) struct Edges {
+    edges: Vec<(Pt, Pt, usize)>,
+    negative: bool,
+}
+
+impl Edges {
+    pub(crate) fn push(&mut self, a: Pt, b: Pt, kind: usize) -> NumprResult<()> {
+        if !adjacent(a, b) {
+            return NumprError::invalid_constraint(format!(
+                "({}, {}) and ({}, {}) don't share an edge",
+                a.x(),
+                a.y(),
+                b.x(),
+                b.y()
+            ));
+        }
+        if self.find(a, b).is_some() {
+            return NumprError::invalid_constraint(format!(
+                "the edge between ({}, {}) and ({}, {}) already has a marker",
+                a.x(),
+                a.y(),
+                b.x(),
+                b.y()
+            ));
+        }
+        self.edges.push((a, b, kind));
+        Ok(())
+    }
+
+    pub(crate) fn set_negative(&mut self, negative: bool) {
+        self.negative = negative;
+    }
+
+    pub(crate) fn of(&self, kind: usize) -> impl Iterator<Item = (Pt, Pt)> + '_ {
+        self.edges
+            .iter()
+            .filter(move |e| e.2 == kind)
+            .map(|&(a, b, _)| (a, b))
+    }
+
+    fn find(&self, a: Pt, b: Pt) -> Option<usize> {
+        self.edges
+            .iter()
+            .find(|&&(p, q, _)| (p, q) == (a, b) || (p, q) == (b, a))
+            .map(|e| e.2)
+    }
+
+    /// Iterates over edges without markers.
+    fn unmarked(&self) -> impl Iterator<Item = (Pt, Pt)> + '_ {
+        PtIter::all()
+            .flat_map(|p| {
+                let right = Pt::new(p.x() + 1, p.y()).ok();
+                let down = Pt::new(p.x(), p.y() + 1).ok();
+                right.into_iter().chain(down).map(move |q| (p, q))
+            })
+            .filter(move |&(p, q)| self.find(p, q).is_none())
+    }
+
+    pub(crate) fn validate(&self, board: &Board, rels: &[Relation]) -> NumprResult<()> {
+        for &(a, b, kind) in &self.edges {
+            let v = values(board, &[a, b])?;
+            if !rels[kind](v[0], v[1]) {
+                return NumprError::wrong_answer();
+            }
+        }
+        if self.negative {
+            for (a, b) in self.unmarked() {
+                let v = values(board, &[a, b])?;
+                if rels.iter().any(|r| r(v[0], v[1])) {
+                    return NumprError::wrong_answer();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn propagate(
+        &self,
+        masks: &mut [u16; SIZE],
+        rels: &[Relation],
+    ) -> NumprResult<bool> {
+        let mut changed = false;
+        for &(a, b, kind) in &self.edges {
+            changed |= restrict_pair(masks, a.index(), b.index(), rels[kind])?;
+        }
+        if self.negative {
+            for (a, b) in self.unmarked() {
+                let (ma, mb) = (masks[a.index()], masks[b.index()]);
+                // Only grids having few candidates are worth checking.
+                if ma.count_ones() > 2 && mb.count_ones() > 2 {
+                    continue;
+                }
+                changed |= restrict_pair(masks, a.index(), b.index(), |x, y| {
+                    !rels.iter().any(|r| r(x, y))
+                })?;
+            }
+        }
+        Ok(changed)
+    }
+}
@@ -0,0 +1,90 @@
+use super::edges::{Edges, Relation};
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::NumprResult;
+use crate::pt::Pt;
+use std::any::Any;
+
+/// Inequality signs between orthogonally adjacent grids.
+///
+/// It doesn't have the negative constraint unlike other markers: adjacent
+/// grids always have different numbers, so one of them is always greater than
+/// the other.
+#[derive(Default)]
+pub struct Inequality {
+    edges: Edges,
+}
+
+const LESS: usize = 0;
+const RELATIONS: [Relation; 1] = [|a, b| a < b];
+
+impl Inequality {
+    /// Returns inequalities without any sign.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sign telling that the number in `a` is less than the one in `b`.
+    ///
+    /// It fails when `a` and `b` don't share an edge or the edge already has a
+    /// sign.
+    pub fn less(mut self, a: Pt, b: Pt) -> NumprResult<Self> {
+        self.edges.push(a, b, LESS)?;
+        Ok(self)
+    }
+
+    /// Adds a sign telling that the number in `a` is greater than the one in
+    /// `b`.
+    pub fn greater(self, a: Pt, b: Pt) -> NumprResult<Self> {
+        self.less(b, a)
+    }
+
+    /// Iterates over signs as pairs of the lesser grid and the greater grid.
+    pub fn signs(&self) -> impl Iterator<Item = (Pt, Pt)> + '_ {
+        self.edges.of(LESS)
+    }
+}
+
+impl Constraint for Inequality {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        self.edges.validate(board, &RELATIONS)
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        self.edges.propagate(masks, &RELATIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::*;
+    use super::*;
+
+    #[test]
+    fn propagate() {
+        let p = |x, y| Pt::new(x, y).unwrap();
+        let i = Inequality::new()
+            .less(p(0, 0), p(1, 0))
+            .unwrap()
+            .greater(p(0, 0), p(0, 1))
+            .unwrap();
+        let mut masks = [ALL; SIZE];
+        assert!(i.propagate(&mut masks).unwrap());
+        assert_eq!(masks[0], range(2, 8));
+        assert_eq!(masks[1], range(2, 9));
+        assert_eq!(masks[9], range(1, 7));
+
+        let mut b = Board::default();
+        b.set(p(0, 0), 5).unwrap();
+        b.set(p(1, 0), 6).unwrap();
+        b.set(p(0, 1), 4).unwrap();
+        i.validate(&b).unwrap();
+        b.set(p(0, 1), 6).unwrap();
+        assert!(i.validate(&b).is_err());
+    }
+}
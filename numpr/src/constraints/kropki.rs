@@ -0,0 +1,175 @@
+use super::edges::{Edges, Relation};
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::NumprResult;
+use crate::pt::Pt;
+use std::any::Any;
+
+/// Kropki dots between orthogonally adjacent grids. Numbers beside a white
+/// dot are consecutive, and one of numbers beside a black dot is double the
+/// other.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::{constraints::Kropki, Pt};
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let k = Kropki::new()
+///     .white(Pt::new(0, 0)?, Pt::new(1, 0)?)?
+///     .black(Pt::new(0, 0)?, Pt::new(0, 1)?)?
+///     .negative(true);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Kropki {
+    edges: Edges,
+}
+
+const WHITE: usize = 0;
+const BLACK: usize = 1;
+const RELATIONS: [Relation; 2] = [
+    |a, b| a + 1 == b || b + 1 == a,
+    |a, b| a * 2 == b || b * 2 == a,
+];
+
+impl Kropki {
+    /// Returns Kropki dots without any dot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a white dot between `a` and `b`.
+    ///
+    /// It fails when `a` and `b` don't share an edge or the edge already has a
+    /// dot.
+    pub fn white(mut self, a: Pt, b: Pt) -> NumprResult<Self> {
+        self.edges.push(a, b, WHITE)?;
+        Ok(self)
+    }
+
+    /// Adds a black dot between `a` and `b`.
+    ///
+    /// It fails when `a` and `b` don't share an edge or the edge already has a
+    /// dot.
+    pub fn black(mut self, a: Pt, b: Pt) -> NumprResult<Self> {
+        self.edges.push(a, b, BLACK)?;
+        Ok(self)
+    }
+
+    /// Sets the negative constraint. When it's `true`, numbers beside an edge
+    /// without a dot are neither consecutive nor in a ratio of 2.
+    pub fn negative(mut self, negative: bool) -> Self {
+        self.edges.set_negative(negative);
+        self
+    }
+
+    /// Iterates over grids beside white dots.
+    pub fn whites(&self) -> impl Iterator<Item = (Pt, Pt)> + '_ {
+        self.edges.of(WHITE)
+    }
+
+    /// Iterates over grids beside black dots.
+    pub fn blacks(&self) -> impl Iterator<Item = (Pt, Pt)> + '_ {
+        self.edges.of(BLACK)
+    }
+}
+
+impl Constraint for Kropki {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        self.edges.validate(board, &RELATIONS)
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        self.edges.propagate(masks, &RELATIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::*;
+    use super::*;
+    use crate::solvers::HeuristicSolver;
+    use crate::{PtIter, Solver};
+
+    fn p(x: usize, y: usize) -> Pt {
+        Pt::new(x, y).unwrap()
+    }
+
+    #[test]
+    fn propagate() {
+        let k = Kropki::new()
+            .white(p(0, 0), p(1, 0))
+            .unwrap()
+            .black(p(0, 0), p(0, 1))
+            .unwrap();
+        let mut masks = [ALL; SIZE];
+        masks[0] = 1 << 3;
+        assert!(k.propagate(&mut masks).unwrap());
+        assert_eq!(masks[1], 1 << 2 | 1 << 4);
+        assert_eq!(masks[9], 1 << 6);
+    }
+
+    #[test]
+    fn negative() {
+        let k = Kropki::new().negative(true);
+        let mut masks = [ALL; SIZE];
+        masks[0] = 1 << 4;
+        assert!(k.propagate(&mut masks).unwrap());
+        assert_eq!(masks[1], ALL & !(1 << 2 | 1 << 3 | 1 << 5 | 1 << 8));
+        assert_eq!(masks[2], ALL);
+    }
+
+    #[test]
+    fn invalid_edge() {
+        assert!(Kropki::new().white(p(0, 0), p(1, 1)).is_err());
+        assert!(Kropki::new()
+            .white(p(0, 0), p(1, 0))
+            .unwrap()
+            .black(p(1, 0), p(0, 0))
+            .is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let b = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+
+        // Put all the dots to satisfy the negative constraint.
+        let mut dots = vec![];
+        for pt in PtIter::all() {
+            for q in Pt::new(pt.x() + 1, pt.y())
+                .into_iter()
+                .chain(Pt::new(pt.x(), pt.y() + 1))
+            {
+                let (a, c) = (b.raw_get(pt), b.raw_get(q));
+                if RELATIONS[WHITE](a, c) {
+                    dots.push((pt, q, WHITE));
+                } else if RELATIONS[BLACK](a, c) {
+                    dots.push((pt, q, BLACK));
+                }
+            }
+        }
+        let kropki = |dots: &[(Pt, Pt, usize)]| {
+            dots.iter()
+                .fold(Kropki::new().negative(true), |k, &(a, c, kind)| {
+                    if kind == WHITE {
+                        k.white(a, c).unwrap()
+                    } else {
+                        k.black(a, c).unwrap()
+                    }
+                })
+        };
+        kropki(&dots).validate(&b).unwrap();
+        assert!(kropki(&dots[1..]).validate(&b).is_err());
+        assert!(kropki(&dots[1..]).negative(false).validate(&b).is_ok());
+    }
+}
@@ -0,0 +1,173 @@
+use super::common::*;
+use super::outside::{Outside, Side};
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use std::any::Any;
+
+/// A direction of a diagonal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Diagonal {
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Diagonal {
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Diagonal::UpLeft => (-1, -1),
+            Diagonal::UpRight => (1, -1),
+            Diagonal::DownLeft => (-1, 1),
+            Diagonal::DownRight => (1, 1),
+        }
+    }
+}
+
+/// A little killer clue gives the sum of numbers on a diagonal. Numbers may
+/// repeat on the diagonal unless the classic rules forbid it.
+pub struct LittleKiller {
+    clue: Outside,
+    direction: Diagonal,
+    sum: u8,
+    cells: Vec<Pt>,
+}
+
+impl LittleKiller {
+    /// Returns a little killer clue at `clue`. The diagonal starts at the
+    /// grid next to the clue and goes toward `direction`.
+    ///
+    /// It fails when `direction` doesn't go into the board or no combination
+    /// of numbers on the diagonal can make `sum`.
+    pub fn new(clue: Outside, direction: Diagonal, sum: u8) -> NumprResult<Self> {
+        let (dx, dy) = direction.delta();
+        let inward = match clue.side() {
+            Side::Top => dy > 0,
+            Side::Bottom => dy < 0,
+            Side::Left => dx > 0,
+            Side::Right => dx < 0,
+        };
+        if !inward {
+            return NumprError::invalid_constraint("a little killer must point into the board");
+        }
+
+        let mut cells = vec![clue.first()];
+        loop {
+            let p = cells[cells.len() - 1];
+            let x = p.x() as isize + dx;
+            let y = p.y() as isize + dy;
+            match Pt::new(x as usize, y as usize) {
+                Ok(p) if x >= 0 && y >= 0 => cells.push(p),
+                _ => break,
+            }
+        }
+        if (sum as usize) < cells.len() || sum as usize > cells.len() * 9 {
+            return NumprError::invalid_constraint(format!(
+                "{} grids cannot sum to {}",
+                cells.len(),
+                sum
+            ));
+        }
+        Ok(Self {
+            clue,
+            direction,
+            sum,
+            cells,
+        })
+    }
+
+    /// Returns the position of the clue.
+    pub fn clue(&self) -> Outside {
+        self.clue
+    }
+
+    /// Returns the direction of the diagonal.
+    pub fn direction(&self) -> Diagonal {
+        self.direction
+    }
+
+    /// Returns the sum.
+    pub fn sum(&self) -> u8 {
+        self.sum
+    }
+
+    /// Returns grids on the diagonal from the clue.
+    pub fn cells(&self) -> &[Pt] {
+        &self.cells
+    }
+}
+
+impl Constraint for LittleKiller {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        let sum: u32 = values(board, &self.cells)?.iter().map(|&n| n as u32).sum();
+        if sum == self.sum as u32 {
+            Ok(())
+        } else {
+            NumprError::wrong_answer()
+        }
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let lo: i32 = self
+            .cells
+            .iter()
+            .map(|p| min(masks[p.index()]) as i32)
+            .sum();
+        let hi: i32 = self
+            .cells
+            .iter()
+            .map(|p| max(masks[p.index()]) as i32)
+            .sum();
+        let sum = self.sum as i32;
+        let mut changed = false;
+        for p in &self.cells {
+            let m = masks[p.index()];
+            let rest_lo = lo - min(m) as i32;
+            let rest_hi = hi - max(m) as i32;
+            changed |= restrict(masks, p.index(), range(sum - rest_hi, sum - rest_lo))?;
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let top = Outside::new(Side::Top, 6).unwrap();
+        let k = LittleKiller::new(top, Diagonal::DownRight, 6).unwrap();
+        let p = |x, y| Pt::new(x, y).unwrap();
+        assert_eq!(k.cells(), &[p(6, 0), p(7, 1), p(8, 2)]);
+        assert!(LittleKiller::new(top, Diagonal::UpRight, 6).is_err());
+        assert!(LittleKiller::new(top, Diagonal::DownRight, 2).is_err());
+        assert!(LittleKiller::new(top, Diagonal::DownRight, 28).is_err());
+    }
+
+    #[test]
+    fn propagate() {
+        let right = Outside::new(Side::Right, 6).unwrap();
+        let k = LittleKiller::new(right, Diagonal::DownLeft, 6).unwrap();
+        let mut masks = [ALL; SIZE];
+        assert!(k.propagate(&mut masks).unwrap());
+        for p in k.cells() {
+            assert_eq!(masks[p.index()], range(1, 4));
+        }
+
+        let mut b = Board::default();
+        for (p, n) in k.cells().iter().zip([1, 2, 3].iter()) {
+            b.set(*p, *n).unwrap();
+        }
+        k.validate(&b).unwrap();
+        b.set(k.cells()[0], 2).unwrap();
+        assert!(k.validate(&b).is_err());
+    }
+}
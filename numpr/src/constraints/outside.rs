@@ -0,0 +1,94 @@
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+
+/// A side of a board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+/// A position outside a board where a clue is written.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::constraints::{Outside, Side};
+/// use numpr::Pt;
+///
+/// let o = Outside::new(Side::Right, 2).unwrap();
+/// let cells = o.cells();
+/// assert_eq!(cells[0], Pt::new(8, 2).unwrap());
+/// assert_eq!(cells[8], Pt::new(0, 2).unwrap());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Outside {
+    side: Side,
+    index: usize,
+}
+
+impl Outside {
+    /// Returns a position next to the `index`-th column (for `Side::Top` and
+    /// `Side::Bottom`) or row (for `Side::Left` and `Side::Right`).
+    ///
+    /// It fails with `NumprError::IndexOutOfBounds` when `index` is not within
+    /// `[0, 8]`.
+    pub fn new(side: Side, index: usize) -> NumprResult<Self> {
+        match side {
+            Side::Top | Side::Bottom if index >= WIDTH => NumprError::index_out_of_bounds(index, 0),
+            Side::Left | Side::Right if index >= HEIGHT => {
+                NumprError::index_out_of_bounds(0, index)
+            }
+            _ => Ok(Self { side, index }),
+        }
+    }
+
+    /// Returns the side of the board.
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Returns the index of the row or the column.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the grid next to the position.
+    pub fn first(&self) -> Pt {
+        self.cells()[0]
+    }
+
+    /// Returns grids in the row or the column in order of distance from the
+    /// position.
+    pub fn cells(&self) -> Vec<Pt> {
+        let i = self.index;
+        (0..9)
+            .map(|k| match self.side {
+                Side::Top => Pt::new(i, k),
+                Side::Bottom => Pt::new(i, HEIGHT - 1 - k),
+                Side::Left => Pt::new(k, i),
+                Side::Right => Pt::new(WIDTH - 1 - k, i),
+            })
+            .collect::<NumprResult<_>>()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells() {
+        let p = |x, y| Pt::new(x, y).unwrap();
+        assert_eq!(Outside::new(Side::Top, 3).unwrap().first(), p(3, 0));
+        assert_eq!(Outside::new(Side::Bottom, 3).unwrap().first(), p(3, 8));
+        assert_eq!(Outside::new(Side::Left, 3).unwrap().first(), p(0, 3));
+        assert_eq!(Outside::new(Side::Right, 3).unwrap().first(), p(8, 3));
+        assert_eq!(Outside::new(Side::Bottom, 3).unwrap().cells()[8], p(3, 0));
+        assert!(Outside::new(Side::Left, 9).is_err());
+    }
+}
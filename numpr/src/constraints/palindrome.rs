@@ -0,0 +1,92 @@
+use super::common::*;
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use std::any::Any;
+
+/// A palindrome line reads the same from both ends.
+pub struct Palindrome {
+    cells: Vec<Pt>,
+}
+
+impl Palindrome {
+    /// Returns a palindrome on `cells`.
+    ///
+    /// It fails when `cells` isn't a connected line having at least 2 grids.
+    pub fn new(cells: Vec<Pt>) -> NumprResult<Self> {
+        Ok(Self {
+            cells: line(cells, 2, SIZE)?,
+        })
+    }
+
+    /// Returns grids on the line.
+    pub fn cells(&self) -> &[Pt] {
+        &self.cells
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (Pt, Pt)> + '_ {
+        let n = self.cells.len();
+        (0..n / 2).map(move |i| (self.cells[i], self.cells[n - 1 - i]))
+    }
+}
+
+impl Constraint for Palindrome {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        values(board, &self.cells)?;
+        if self.pairs().all(|(a, b)| board.get(a) == board.get(b)) {
+            Ok(())
+        } else {
+            NumprError::wrong_answer()
+        }
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let mut changed = false;
+        for (a, b) in self.pairs() {
+            let m = masks[a.index()] & masks[b.index()];
+            changed |= restrict(masks, a.index(), m)?;
+            changed |= restrict(masks, b.index(), m)?;
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn propagate() {
+        let cells = (0..3).map(|x| Pt::new(x, x).unwrap()).collect();
+        let p = Palindrome::new(cells).unwrap();
+        let mut masks = [ALL; SIZE];
+        masks[0] = 0b1110;
+        masks[20] = 0b11_0000_0110;
+        assert!(p.propagate(&mut masks).unwrap());
+        assert_eq!(masks[0], 0b110);
+        assert_eq!(masks[20], 0b110);
+        assert_eq!(masks[10], ALL);
+
+        masks[20] = 1 << 9;
+        assert!(p.propagate(&mut masks).is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let cells: Vec<Pt> = (0..4).map(|y| Pt::new(0, y).unwrap()).collect();
+        let p = Palindrome::new(cells.clone()).unwrap();
+        let mut b = Board::default();
+        for (p, n) in cells.iter().zip([1, 2, 2, 1].iter()) {
+            b.set(*p, *n).unwrap();
+        }
+        p.validate(&b).unwrap();
+        b.set(cells[3], 3).unwrap();
+        assert!(p.validate(&b).is_err());
+    }
+}
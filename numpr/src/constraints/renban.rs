@@ -0,0 +1,112 @@
+use super::common::*;
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use std::any::Any;
+
+/// A renban line contains a set of consecutive numbers in any order without
+/// repeats.
+pub struct Renban {
+    cells: Vec<Pt>,
+}
+
+impl Renban {
+    /// Returns a renban line on `cells`.
+    ///
+    /// It fails when `cells` isn't a connected line of 2 to 9 grids.
+    pub fn new(cells: Vec<Pt>) -> NumprResult<Self> {
+        Ok(Self {
+            cells: line(cells, 2, 9)?,
+        })
+    }
+
+    /// Returns grids on the line.
+    pub fn cells(&self) -> &[Pt] {
+        &self.cells
+    }
+}
+
+impl Constraint for Renban {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        let v = values(board, &self.cells)?;
+        let mask = v.iter().fold(0u16, |m, &n| m | 1 << n);
+        if mask.count_ones() as usize == v.len() && max(mask) - min(mask) + 1 == v.len() as u8 {
+            Ok(())
+        } else {
+            NumprError::wrong_answer()
+        }
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let n = self.cells.len() as u8;
+
+        // Numbers have to be taken from a window of n consecutive numbers that
+        // every grid on the line can take a number from.
+        let mut allowed = 0;
+        for lo in 1..=(10 - n) {
+            let window = range(lo as i32, (lo + n - 1) as i32);
+            if self.cells.iter().all(|p| masks[p.index()] & window != 0) {
+                allowed |= window;
+            }
+        }
+
+        let mut changed = false;
+        for p in &self.cells {
+            changed |= restrict(masks, p.index(), allowed)?;
+        }
+
+        // Numbers are distinct.
+        for p in &self.cells {
+            let m = masks[p.index()];
+            if m.count_ones() != 1 {
+                continue;
+            }
+            for q in self.cells.iter().filter(|&q| q != p) {
+                changed |= restrict(masks, q.index(), !m)?;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn propagate() {
+        let cells = (0..3).map(|x| Pt::new(x, 0).unwrap()).collect();
+        let r = Renban::new(cells).unwrap();
+        let mut masks = [ALL; SIZE];
+        assert!(!r.propagate(&mut masks).unwrap());
+
+        masks[0] = 1 << 2;
+        assert!(r.propagate(&mut masks).unwrap());
+        assert_eq!(masks[1], range(1, 4) & !(1 << 2));
+        assert_eq!(masks[2], range(1, 4) & !(1 << 2));
+
+        masks[1] = 1 << 9;
+        assert!(r.propagate(&mut masks).is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let cells: Vec<Pt> = (0..4).map(|x| Pt::new(x, 0).unwrap()).collect();
+        let r = Renban::new(cells.clone()).unwrap();
+        let mut b = Board::default();
+        for (p, n) in cells.iter().zip([5, 3, 6, 4].iter()) {
+            b.set(*p, *n).unwrap();
+        }
+        r.validate(&b).unwrap();
+        b.set(cells[3], 7).unwrap();
+        assert!(r.validate(&b).is_err());
+        b.set(cells[3], 5).unwrap();
+        assert!(r.validate(&b).is_err());
+    }
+}
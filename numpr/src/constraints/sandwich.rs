@@ -0,0 +1,159 @@
+use super::common::*;
+use super::outside::Outside;
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::{NumprError, NumprResult};
+use std::any::Any;
+
+/// A sandwich clue gives the sum of numbers between 1 and 9 in a row or a
+/// column.
+pub struct Sandwich {
+    clue: Outside,
+    sum: u8,
+}
+
+// Numbers that can be sandwiched: 2 to 8.
+const FILLINGS: u16 = 0b01_1111_1100;
+
+impl Sandwich {
+    /// Returns a sandwich clue at `clue`.
+    ///
+    /// It fails when `sum` is greater than `2 + 3 + ... + 8 = 35`.
+    pub fn new(clue: Outside, sum: u8) -> NumprResult<Self> {
+        if sum > 35 {
+            return NumprError::invalid_constraint(format!("sandwich sum too large: {}", sum));
+        }
+        Ok(Self { clue, sum })
+    }
+
+    /// Returns the position of the clue.
+    pub fn clue(&self) -> Outside {
+        self.clue
+    }
+
+    /// Returns the sum.
+    pub fn sum(&self) -> u8 {
+        self.sum
+    }
+
+    /// Returns sets of `len` fillings summing to the clue.
+    fn fillings(&self, len: u32) -> impl Iterator<Item = u16> + '_ {
+        (0..1u16 << 7)
+            .map(|s| s << 2)
+            .filter(move |s| s.count_ones() == len)
+            .filter(move |&s| digits(s).map(|n| n as u32).sum::<u32>() == self.sum as u32)
+    }
+}
+
+impl Constraint for Sandwich {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        let v = values(board, &self.clue.cells())?;
+        let i = v.iter().position(|&n| n == 1);
+        let j = v.iter().position(|&n| n == 9);
+        let (i, j) = match (i, j) {
+            (Some(i), Some(j)) => (i.min(j), i.max(j)),
+            _ => return NumprError::wrong_answer(),
+        };
+        if v[i + 1..j].iter().map(|&n| n as u32).sum::<u32>() == self.sum as u32 {
+            Ok(())
+        } else {
+            NumprError::wrong_answer()
+        }
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let cells: Vec<usize> = self.clue.cells().iter().map(|p| p.index()).collect();
+        let mut allowed = [0u16; 9];
+
+        // Try all the positions of the crusts and collect numbers that can be
+        // placed in each grid under them.
+        for i in 0..9 {
+            for j in i + 1..9 {
+                let (mi, mj) = (masks[cells[i]], masks[cells[j]]);
+                let mut crusts = (0, 0);
+                for &(a, b) in &[(1, 9), (9, 1)] {
+                    if mi & (1 << a) != 0 && mj & (1 << b) != 0 {
+                        crusts.0 |= 1 << a;
+                        crusts.1 |= 1 << b;
+                    }
+                }
+                if crusts.0 == 0 {
+                    continue;
+                }
+
+                for s in self.fillings((j - i - 1) as u32) {
+                    let fits = |k: usize| {
+                        let m = masks[cells[k]];
+                        if k > i && k < j {
+                            m & s
+                        } else {
+                            m & FILLINGS & !s
+                        }
+                    };
+                    if (0..9).filter(|&k| k != i && k != j).any(|k| fits(k) == 0) {
+                        continue;
+                    }
+                    for (k, a) in allowed.iter_mut().enumerate() {
+                        *a |= if k == i {
+                            crusts.0
+                        } else if k == j {
+                            crusts.1
+                        } else {
+                            fits(k)
+                        };
+                    }
+                }
+            }
+        }
+
+        let mut changed = false;
+        for (k, &c) in cells.iter().enumerate() {
+            changed |= restrict(masks, c, allowed[k])?;
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::outside::Side;
+    use super::*;
+    use crate::pt::Pt;
+
+    const CRUSTS: u16 = 1 << 1 | 1 << 9;
+
+    #[test]
+    fn propagate() {
+        let s = Sandwich::new(Outside::new(Side::Top, 0).unwrap(), 0).unwrap();
+        let mut masks = [ALL; SIZE];
+        masks[0] = 1 << 1;
+        assert!(s.propagate(&mut masks).unwrap());
+        // 9 has to be right next to 1.
+        assert_eq!(masks[9], 1 << 9);
+        assert_eq!(masks[18], FILLINGS);
+
+        let s = Sandwich::new(Outside::new(Side::Left, 1).unwrap(), 35).unwrap();
+        let mut masks = [ALL; SIZE];
+        assert!(s.propagate(&mut masks).unwrap());
+        assert_eq!(masks[9], CRUSTS);
+        assert_eq!(masks[10], FILLINGS);
+        assert_eq!(masks[17], CRUSTS);
+    }
+
+    #[test]
+    fn validate() {
+        let mut b = Board::default();
+        for (x, n) in [4, 1, 2, 3, 9, 5, 6, 7, 8].iter().enumerate() {
+            b.set(Pt::new(x, 0).unwrap(), *n).unwrap();
+        }
+        let o = Outside::new(Side::Right, 0).unwrap();
+        Sandwich::new(o, 5).unwrap().validate(&b).unwrap();
+        assert!(Sandwich::new(o, 6).unwrap().validate(&b).is_err());
+        assert!(Sandwich::new(o, 36).is_err());
+    }
+}
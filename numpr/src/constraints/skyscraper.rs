@@ -0,0 +1,154 @@
+use super::common::*;
+use super::outside::Outside;
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::{NumprError, NumprResult};
+use std::any::Any;
+
+/// A skyscraper clue gives the number of grids visible from the clue, where
+/// a number hides all the smaller numbers behind it.
+pub struct Skyscraper {
+    clue: Outside,
+    count: u8,
+}
+
+impl Skyscraper {
+    /// Returns a skyscraper clue at `clue`.
+    ///
+    /// It fails when `count` is not within `[1, 9]`.
+    pub fn new(clue: Outside, count: u8) -> NumprResult<Self> {
+        if !(1..=9).contains(&count) {
+            return NumprError::invalid_constraint(format!(
+                "skyscraper count must be 1 to 9: {}",
+                count
+            ));
+        }
+        Ok(Self { clue, count })
+    }
+
+    /// Returns the position of the clue.
+    pub fn clue(&self) -> Outside {
+        self.clue
+    }
+
+    /// Returns the number of visible grids.
+    pub fn count(&self) -> u8 {
+        self.count
+    }
+}
+
+impl Constraint for Skyscraper {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        let v = values(board, &self.clue.cells())?;
+        let mut highest = 0;
+        let mut visible = 0;
+        for n in v {
+            if n > highest {
+                highest = n;
+                visible += 1;
+            }
+        }
+        if visible == self.count {
+            Ok(())
+        } else {
+            NumprError::wrong_answer()
+        }
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let cells = self.clue.cells();
+        if self.count == 1 {
+            return restrict(masks, cells[0].index(), 1 << 9);
+        }
+
+        // At least count - 1 grids have to be visible in front of 9, so the
+        // k-th grid from the clue has at most 10 - count + k.
+        let mut changed = false;
+        for (k, p) in cells.iter().enumerate() {
+            let hi = 10 - self.count as i32 + k as i32;
+            if hi >= 9 {
+                break;
+            }
+            changed |= restrict(masks, p.index(), range(1, hi))?;
+        }
+
+        // Count grids visible from the clue up to the first empty grid, and
+        // check if the rest can make the count.
+        let mut highest = 0;
+        let mut visible = 0;
+        let mut k = 0;
+        while k < cells.len() && masks[cells[k].index()].count_ones() == 1 {
+            let n = min(masks[cells[k].index()]);
+            if n > highest {
+                highest = n;
+                visible += 1;
+            }
+            k += 1;
+        }
+        let upper = visible
+            + cells[k..]
+                .iter()
+                .filter(|p| max(masks[p.index()]) > highest)
+                .count() as u8;
+        let lower = if highest == 9 { visible } else { visible + 1 };
+        if upper < self.count || lower > self.count {
+            return NumprError::unsolvable();
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::outside::Side;
+    use super::*;
+    use crate::pt::Pt;
+
+    #[test]
+    fn propagate() {
+        let o = Outside::new(Side::Bottom, 2).unwrap();
+        let mut masks = [ALL; SIZE];
+        assert!(Skyscraper::new(o, 1)
+            .unwrap()
+            .propagate(&mut masks)
+            .unwrap());
+        assert_eq!(masks[8 * 9 + 2], 1 << 9);
+
+        let mut masks = [ALL; SIZE];
+        assert!(Skyscraper::new(o, 4)
+            .unwrap()
+            .propagate(&mut masks)
+            .unwrap());
+        assert_eq!(masks[8 * 9 + 2], range(1, 6));
+        assert_eq!(masks[7 * 9 + 2], range(1, 7));
+        assert_eq!(masks[5 * 9 + 2], ALL);
+
+        masks[8 * 9 + 2] = 1 << 1;
+        masks[7 * 9 + 2] = 1 << 2;
+        masks[6 * 9 + 2] = 1 << 3;
+        masks[5 * 9 + 2] = 1 << 4;
+        assert!(Skyscraper::new(o, 4)
+            .unwrap()
+            .propagate(&mut masks)
+            .is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let mut b = Board::default();
+        for (x, n) in [2, 1, 5, 3, 4, 8, 6, 9, 7].iter().enumerate() {
+            b.set(Pt::new(x, 4).unwrap(), *n).unwrap();
+        }
+        let left = Outside::new(Side::Left, 4).unwrap();
+        let right = Outside::new(Side::Right, 4).unwrap();
+        Skyscraper::new(left, 4).unwrap().validate(&b).unwrap();
+        Skyscraper::new(right, 2).unwrap().validate(&b).unwrap();
+        assert!(Skyscraper::new(left, 3).unwrap().validate(&b).is_err());
+        assert!(Skyscraper::new(left, 0).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use super::common::*;
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use std::any::Any;
+
+/// Numbers on a thermometer strictly increase from its bulb.
+pub struct Thermometer {
+    cells: Vec<Pt>,
+}
+
+impl Thermometer {
+    /// Returns a thermometer starting at the bulb `cells[0]`.
+    ///
+    /// It fails when `cells` isn't a connected line of 2 to 9 grids.
+    pub fn new(cells: Vec<Pt>) -> NumprResult<Self> {
+        Ok(Self {
+            cells: line(cells, 2, 9)?,
+        })
+    }
+
+    /// Returns grids on the thermometer from its bulb.
+    pub fn cells(&self) -> &[Pt] {
+        &self.cells
+    }
+}
+
+impl Constraint for Thermometer {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        let v = values(board, &self.cells)?;
+        if v.windows(2).all(|w| w[0] < w[1]) {
+            Ok(())
+        } else {
+            NumprError::wrong_answer()
+        }
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let mut changed = false;
+        for w in self.cells.windows(2) {
+            let lo = min(masks[w[0].index()]);
+            changed |= restrict(masks, w[1].index(), above(lo))?;
+        }
+        for w in self.cells.windows(2).rev() {
+            let hi = max(masks[w[1].index()]);
+            changed |= restrict(masks, w[0].index(), below(hi))?;
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn propagate() {
+        let cells: Vec<Pt> = (0..4).map(|x| Pt::new(x, 0).unwrap()).collect();
+        let t = Thermometer::new(cells).unwrap();
+        let mut masks = [ALL; SIZE];
+        masks[2] = 1 << 4;
+        assert!(t.propagate(&mut masks).unwrap());
+        assert_eq!(masks[0], 0b0_0000_0110);
+        assert_eq!(masks[1], 0b0_0000_1100);
+        assert_eq!(masks[3], 0b11_1110_0000);
+        assert!(!t.propagate(&mut masks).unwrap());
+
+        masks[2] = 1 << 2;
+        assert!(t.propagate(&mut masks).is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let cells = vec![Pt::new(0, 0).unwrap(), Pt::new(1, 1).unwrap()];
+        let t = Thermometer::new(cells).unwrap();
+        let mut b = Board::default();
+        assert!(t.validate(&b).is_err());
+        b.set(Pt::new(0, 0).unwrap(), 3).unwrap();
+        b.set(Pt::new(1, 1).unwrap(), 5).unwrap();
+        t.validate(&b).unwrap();
+        b.set(Pt::new(1, 1).unwrap(), 3).unwrap();
+        assert!(t.validate(&b).is_err());
+    }
+}
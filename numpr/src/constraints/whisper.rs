@@ -0,0 +1,90 @@
+use super::common::*;
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use std::any::Any;
+
+/// Adjacent numbers on a German whispers line differ by at least 5.
+pub struct GermanWhispers {
+    cells: Vec<Pt>,
+}
+
+const GAP: i32 = 5;
+
+fn apart(a: u8, b: u8) -> bool {
+    (a as i32 - b as i32).abs() >= GAP
+}
+
+impl GermanWhispers {
+    /// Returns a German whispers line on `cells`.
+    ///
+    /// It fails when `cells` isn't a connected line having at least 2 grids.
+    pub fn new(cells: Vec<Pt>) -> NumprResult<Self> {
+        Ok(Self {
+            cells: line(cells, 2, SIZE)?,
+        })
+    }
+
+    /// Returns grids on the line.
+    pub fn cells(&self) -> &[Pt] {
+        &self.cells
+    }
+}
+
+impl Constraint for GermanWhispers {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        let v = values(board, &self.cells)?;
+        if v.windows(2).all(|w| apart(w[0], w[1])) {
+            Ok(())
+        } else {
+            NumprError::wrong_answer()
+        }
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let mut changed = false;
+        for w in self.cells.windows(2) {
+            changed |= restrict_pair(masks, w[0].index(), w[1].index(), apart)?;
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn propagate() {
+        let cells = (0..3).map(|x| Pt::new(x, 0).unwrap()).collect();
+        let w = GermanWhispers::new(cells).unwrap();
+        let mut masks = [ALL; SIZE];
+        assert!(w.propagate(&mut masks).unwrap());
+        // 5 can never be on a line.
+        assert!(masks[..3].iter().all(|&m| m == ALL & !(1 << 5)));
+
+        masks[1] = 1 << 3;
+        w.propagate(&mut masks).unwrap();
+        assert_eq!(masks[0], range(8, 9));
+        assert_eq!(masks[2], range(8, 9));
+    }
+
+    #[test]
+    fn validate() {
+        let cells: Vec<Pt> = (0..3).map(|x| Pt::new(x, 0).unwrap()).collect();
+        let w = GermanWhispers::new(cells.clone()).unwrap();
+        let mut b = Board::default();
+        for (p, n) in cells.iter().zip([1, 7, 2].iter()) {
+            b.set(*p, *n).unwrap();
+        }
+        w.validate(&b).unwrap();
+        b.set(cells[2], 3).unwrap();
+        assert!(w.validate(&b).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use super::edges::{Edges, Relation};
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::NumprResult;
+use crate::pt::Pt;
+use std::any::Any;
+
+/// XV markers between orthogonally adjacent grids. Numbers beside an X sum to
+/// 10, and numbers beside a V sum to 5.
+#[derive(Default)]
+pub struct XV {
+    edges: Edges,
+}
+
+const X: usize = 0;
+const V: usize = 1;
+const RELATIONS: [Relation; 2] = [|a, b| a + b == 10, |a, b| a + b == 5];
+
+impl XV {
+    /// Returns XV markers without any marker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an X between `a` and `b`.
+    ///
+    /// It fails when `a` and `b` don't share an edge or the edge already has a
+    /// marker.
+    pub fn x(mut self, a: Pt, b: Pt) -> NumprResult<Self> {
+        self.edges.push(a, b, X)?;
+        Ok(self)
+    }
+
+    /// Adds a V between `a` and `b`.
+    ///
+    /// It fails when `a` and `b` don't share an edge or the edge already has a
+    /// marker.
+    pub fn v(mut self, a: Pt, b: Pt) -> NumprResult<Self> {
+        self.edges.push(a, b, V)?;
+        Ok(self)
+    }
+
+    /// Sets the negative constraint. When it's `true`, numbers beside an edge
+    /// without a marker sum to neither 10 nor 5.
+    pub fn negative(mut self, negative: bool) -> Self {
+        self.edges.set_negative(negative);
+        self
+    }
+
+    /// Iterates over grids beside Xs.
+    pub fn xs(&self) -> impl Iterator<Item = (Pt, Pt)> + '_ {
+        self.edges.of(X)
+    }
+
+    /// Iterates over grids beside Vs.
+    pub fn vs(&self) -> impl Iterator<Item = (Pt, Pt)> + '_ {
+        self.edges.of(V)
+    }
+}
+
+impl Constraint for XV {
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    fn validate(&self, board: &Board) -> NumprResult<()> {
+        self.edges.validate(board, &RELATIONS)
+    }
+
+    fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        self.edges.propagate(masks, &RELATIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::common::*;
+    use super::*;
+
+    fn p(x: usize, y: usize) -> Pt {
+        Pt::new(x, y).unwrap()
+    }
+
+    #[test]
+    fn propagate() {
+        let xv = XV::new()
+            .x(p(0, 0), p(1, 0))
+            .unwrap()
+            .v(p(0, 0), p(0, 1))
+            .unwrap();
+        let mut masks = [ALL; SIZE];
+        while xv.propagate(&mut masks).unwrap() {}
+        // The grid beside the V cannot have 5 or more, so does the one beside X.
+        assert_eq!(masks[0], range(1, 4));
+        assert_eq!(masks[9], range(1, 4));
+        assert_eq!(masks[1], range(6, 9));
+    }
+
+    #[test]
+    fn negative() {
+        let xv = XV::new().x(p(0, 0), p(1, 0)).unwrap().negative(true);
+        let mut b = Board::default();
+        for (x, n) in [(0, 3), (1, 7), (2, 1)].iter() {
+            b.set(p(*x, 0), *n).unwrap();
+        }
+        assert!(xv.validate(&b).is_err()); // The rest of the board is empty.
+
+        let mut masks = [ALL; SIZE];
+        masks[1] = 1 << 7;
+        xv.propagate(&mut masks).unwrap();
+        assert_eq!(masks[0], 1 << 3);
+        assert_eq!(masks[2], ALL & !(1 << 3));
+    }
+}
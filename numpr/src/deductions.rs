@@ -0,0 +1,265 @@
+//! Logical deductions eliminating candidates.
+//!
+//! Candidates are bit masks where bit `n` stands for number `n`, which can be
+//! pencil marks of a player as well as computed ones.
+//!
+//! # Examples
+//!
+//! ```
+//! use numpr::deductions;
+//! use numpr::Board;
+//!
+//! let b = Board::default();
+//! let masks = deductions::candidates(&b);
+//! assert_eq!(masks[0], 0b11_1111_1110);
+//! assert!(deductions::eliminations(&b, &masks).is_empty());
+//! ```
+
+use crate::board::Board;
+use crate::consts::*;
+use crate::pt::Pt;
+use crate::solvers::UNITS;
+
+/// A technique finding eliminations, ordered from the simplest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    /// A number in a block is confined to a row or a column, or a number in a
+    /// row or a column is confined to a block.
+    LockedCandidates,
+    /// The given number of grids in a unit share the same number of
+    /// candidates.
+    NakedSubset(usize),
+    /// The given number of numbers in a unit can only be placed at the same
+    /// number of grids.
+    HiddenSubset(usize),
+}
+
+/// A candidate removed by a technique.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Elimination {
+    pub pt: Pt,
+    pub n: u8,
+    pub technique: Technique,
+}
+
+const TECHNIQUES: [Technique; 5] = [
+    Technique::LockedCandidates,
+    Technique::NakedSubset(2),
+    Technique::HiddenSubset(2),
+    Technique::NakedSubset(3),
+    Technique::HiddenSubset(3),
+];
+
+/// Returns candidates of all the empty grids of `board`. Filled grids have no
+/// candidate.
+pub fn candidates(board: &Board) -> [u16; SIZE] {
+    let mut masks = [0; SIZE];
+    for (pt, n) in board.iter() {
+        if n.is_none() {
+            for c in board.candidates(pt, false) {
+                masks[pt.index()] |= 1 << c;
+            }
+        }
+    }
+    masks
+}
+
+/// Returns eliminations found by the simplest technique that finds any.
+///
+/// `masks` holds candidates of empty grids of `board`. Candidates of filled
+/// grids are ignored. The result is empty when no technique makes progress.
+pub fn eliminations(board: &Board, masks: &[u16; SIZE]) -> Vec<Elimination> {
+    let mut m = *masks;
+    for (pt, n) in board.iter() {
+        if n.is_some() {
+            m[pt.index()] = 0;
+        }
+    }
+
+    let units = &UNITS;
+    for &t in &TECHNIQUES {
+        let mut found = [0u16; SIZE];
+        match t {
+            Technique::LockedCandidates => locked_candidates(units, &m, &mut found),
+            Technique::NakedSubset(k) => units
+                .iter()
+                .for_each(|u| naked_subset(u, k, &m, &mut found)),
+            Technique::HiddenSubset(k) => units
+                .iter()
+                .for_each(|u| hidden_subset(u, k, &m, &mut found)),
+        }
+        let e: Vec<Elimination> = (0..SIZE)
+            .flat_map(|i| {
+                (1..=9)
+                    .filter(move |n| found[i] & 1 << n != 0)
+                    .map(move |n| Elimination {
+                        pt: Pt::new(i % WIDTH, i / WIDTH).unwrap(),
+                        n,
+                        technique: t,
+                    })
+            })
+            .collect();
+        if !e.is_empty() {
+            return e;
+        }
+    }
+    Vec::new()
+}
+
+/// Marks `found` with candidates in `m` of `unit` at positions in `cells`, a
+/// bit mask of indices in `unit`.
+fn eliminate(unit: &[u8; 9], cells: u16, digits: u16, m: &[u16; SIZE], found: &mut [u16; SIZE]) {
+    for (j, &i) in unit.iter().enumerate() {
+        let i = i as usize;
+        if cells & 1 << j != 0 {
+            found[i] |= m[i] & digits;
+        }
+    }
+}
+
+/// Returns positions of `n` in `unit` as a bit mask of indices in `unit`.
+fn places(unit: &[u8; 9], n: u8, m: &[u16; SIZE]) -> u16 {
+    unit.iter()
+        .enumerate()
+        .filter(|(_, &i)| m[i as usize] & 1 << n != 0)
+        .fold(0, |a, (j, _)| a | 1 << j)
+}
+
+fn locked_candidates(units: &[[u8; 9]; 27], m: &[u16; SIZE], found: &mut [u16; SIZE]) {
+    // Units are ordered as row, column, and block.
+    for (a, ua) in units.iter().enumerate() {
+        for (b, ub) in units.iter().enumerate() {
+            // Only pairs of a block and a line.
+            if (a % 3 == 2) == (b % 3 == 2) {
+                continue;
+            }
+            for n in 1..=9 {
+                let pa = places(ua, n, m);
+                if pa == 0 {
+                    continue;
+                }
+                let inside = ua
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| pa & 1 << j != 0)
+                    .all(|(_, i)| ub.contains(i));
+                if inside {
+                    let outside = ub
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, i)| !ua.contains(i))
+                        .fold(0, |a, (j, _)| a | 1 << j);
+                    eliminate(ub, outside, 1 << n, m, found);
+                }
+            }
+        }
+    }
+}
+
+fn naked_subset(unit: &[u8; 9], k: usize, m: &[u16; SIZE], found: &mut [u16; SIZE]) {
+    let empty = places_of(unit, m);
+    for cells in subsets(empty, k) {
+        let digits = unit
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| cells & 1 << j != 0)
+            .fold(0, |a, (_, &i)| a | m[i as usize]);
+        if digits.count_ones() as usize == k {
+            eliminate(unit, empty & !cells, digits, m, found);
+        }
+    }
+}
+
+fn hidden_subset(unit: &[u8; 9], k: usize, m: &[u16; SIZE], found: &mut [u16; SIZE]) {
+    let mut placeable = 0;
+    for n in 1..=9 {
+        if places(unit, n, m) != 0 {
+            placeable |= 1 << n;
+        }
+    }
+    for digits in subsets(placeable, k) {
+        let cells = (1..=9)
+            .filter(|n| digits & 1 << n != 0)
+            .fold(0, |a, n| a | places(unit, n, m));
+        if cells.count_ones() as usize == k {
+            eliminate(unit, cells, !digits, m, found);
+        }
+    }
+}
+
+/// Returns positions of empty grids in `unit`.
+fn places_of(unit: &[u8; 9], m: &[u16; SIZE]) -> u16 {
+    unit.iter()
+        .enumerate()
+        .filter(|(_, &i)| m[i as usize] != 0)
+        .fold(0, |a, (j, _)| a | 1 << j)
+}
+
+/// Iterates over subsets of `set` having `k` elements.
+fn subsets(set: u16, k: usize) -> impl Iterator<Item = u16> {
+    (1..1u16 << 10).filter(move |s| s & !set == 0 && s.count_ones() as usize == k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masks(cells: &[(usize, u16)]) -> [u16; SIZE] {
+        let mut m = [0b11_1111_1110; SIZE];
+        for &(i, mask) in cells {
+            m[i] = mask;
+        }
+        m
+    }
+
+    #[test]
+    fn locked_candidates() {
+        // 1 in the top left block is confined to the top row.
+        let mut m = masks(&[]);
+        for &i in &[9, 10, 11, 18, 19, 20] {
+            m[i] &= !0b10;
+        }
+        let e = eliminations(&Board::default(), &m);
+        assert_eq!(e.len(), 6);
+        assert!(e.iter().all(|e| e.n == 1
+            && e.pt.y() == 0
+            && e.pt.x() >= 3
+            && e.technique == Technique::LockedCandidates));
+    }
+
+    #[test]
+    fn naked_pair() {
+        let m = masks(&[(0, 0b110), (1, 0b110)]);
+        let e = eliminations(&Board::default(), &m);
+        // Other grids in the top row and in the top left block.
+        assert_eq!(e.len(), (7 + 6) * 2);
+        assert!(e.iter().all(|e| e.technique == Technique::NakedSubset(2)));
+        assert!(e.iter().all(|e| e.pt.index() > 1 && (e.n == 1 || e.n == 2)));
+    }
+
+    #[test]
+    fn hidden_pair() {
+        // 1 and 2 can only be placed at (0, 0) and (3, 0) in the top row.
+        let mut m = masks(&[]);
+        for &i in &[1, 2, 4, 5, 6, 7, 8] {
+            m[i] &= !0b110;
+        }
+        let e = eliminations(&Board::default(), &m);
+        assert_eq!(e.len(), 14);
+        assert!(e.iter().all(|e| e.technique == Technique::HiddenSubset(2)
+            && (e.pt.index() == 0 || e.pt.index() == 3)
+            && e.n > 2));
+    }
+
+    #[test]
+    fn filled() {
+        let mut b = Board::default();
+        b.set(Pt::new(0, 0).unwrap(), 1).unwrap();
+        let m = candidates(&b);
+        assert_eq!(m[0], 0);
+        assert_eq!(m[1], 0b11_1111_1100);
+        assert_eq!(m[10], 0b11_1111_1100);
+        assert_eq!(m[40], 0b11_1111_1110);
+        assert!(eliminations(&b, &m).is_empty());
+    }
+}
@@ -0,0 +1,466 @@
+//! A search engine using an explicit stack instead of recursion.
+//!
+//! [`Engine`](struct.Engine.html) keeps a single board with candidates of
+//! empty grids, which are updated as numbers are placed. Each change is
+//! recorded in a trail, and going back to a branching point undoes the
+//! changes after it instead of restoring a copy of the board. As the whole
+//! state of a search is in the engine, it can be paused and resumed at any
+//! step, which is also useful to visualize how a puzzle is solved.
+//!
+//! [`IterativeSolver`](../solvers/struct.IterativeSolver.html) and
+//! [`HeuristicSolver`](../solvers/struct.HeuristicSolver.html) are built on
+//! the engine. In the `Engine` group of `benches/solver_benchmark.rs`, ten
+//! generated puzzles took about 200 µs with `HeuristicSolver`, 390 µs with
+//! `IterativeSolver`, and 110 µs with `BitBoardSolver`, which copies its
+//! board at each branch instead; the puzzles differ in each run, so the
+//! numbers only compare solvers within a run.
+//! `NaiveSolver` stays recursive because it's meant to be plain brute force
+//! without placing singles, which the engine always does.
+
+use crate::board::Board;
+use crate::budget::{self, Budget};
+use crate::chooser::Chooser;
+use crate::choosers::Mrv;
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use crate::solvers::{PEERS, UNITS};
+use crate::stats::Collector;
+use rand::prelude::*;
+
+const ALL: u16 = 0b11_1111_1110;
+
+/// An event happened in a step of the search.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// A number was placed in the only candidate of a grid, or in the only
+    /// place of the number in a row, a column, or a block.
+    Forced(Pt, u8),
+    /// A number was placed in a grid having multiple candidates.
+    Guess(Pt, u8),
+    /// A grid was found having no candidates. When hidden singles are
+    /// enabled, it's also the first grid of a row, a column, or a block where
+    /// a number has no place.
+    DeadEnd(Pt),
+    /// All the candidates of a grid have been tried and the search went back
+    /// to the previous branch.
+    Backtrack(Pt),
+    /// The board is completed. The next step continues to search another
+    /// answer.
+    Solved,
+    /// No more answer exists.
+    Exhausted,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum State {
+    // No step has been taken.
+    Ready,
+    Searching,
+    // The current board is completed or has a grid without candidates.
+    Failed,
+    Exhausted,
+}
+
+/// A branching point.
+struct Frame {
+    index: usize,
+    candidates: [u8; 9],
+    len: usize,
+    // The index of the candidate to be tried next.
+    next: usize,
+    // The length of the trail before the branch.
+    mark: usize,
+}
+
+/// A search engine stepping through a search.
+///
+/// `C` is the [`Chooser`](../trait.Chooser.html) deciding the grid to branch
+/// on. `S` is a [`Collector`](../stats/trait.Collector.html) receiving
+/// statistics of the search. It's `()` unless `with_stats` is called.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::engine::{Engine, Step};
+/// use numpr::{choosers, Board};
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let mut e = Engine::new(&Board::default(), choosers::InOrder::new())?;
+/// assert!(matches!(e.step()?, Step::Guess(_, 1)));
+/// assert_eq!(e.depth(), 1);
+///
+/// let a = e.next_answer()?.unwrap();
+/// let b = e.next_answer()?.unwrap();
+/// a.validate()?;
+/// b.validate()?;
+/// assert!(!a.iter().eq(b.iter()));
+/// # Ok(())
+/// # }
+/// ```
+pub struct Engine<C = Mrv, S = ()> {
+    numbers: [u8; SIZE],
+    // Candidates of empty grids. It's 0 for filled grids.
+    masks: [u16; SIZE],
+    // Number of empty grids.
+    empty: usize,
+    // Grids and their masks before each change.
+    trail: Vec<(u8, u16)>,
+    // Grids which may have a single candidate.
+    singles: Vec<u8>,
+    // A grid left without candidates.
+    dead: Option<usize>,
+    stack: Vec<Frame>,
+    state: State,
+    chooser: C,
+    stats: S,
+    shuffle: bool,
+    hidden_singles: bool,
+    budget: Option<Budget>,
+}
+
+fn pt(i: usize) -> Pt {
+    Pt::new(i % WIDTH, i / WIDTH).unwrap()
+}
+
+impl<C: Chooser> Engine<C> {
+    /// Returns an engine searching answers of `board`.
+    ///
+    /// It fails with `NumprError::InvalidValue` when numbers in `board`
+    /// conflict with each other.
+    pub fn new(board: &Board, chooser: C) -> NumprResult<Self> {
+        let mut e = Self {
+            numbers: [0; SIZE],
+            masks: [ALL; SIZE],
+            empty: SIZE,
+            trail: Vec::with_capacity(SIZE * 4),
+            singles: Vec::with_capacity(SIZE),
+            dead: None,
+            stack: Vec::with_capacity(SIZE),
+            state: State::Ready,
+            chooser,
+            stats: (),
+            shuffle: false,
+            hidden_singles: false,
+            budget: None,
+        };
+        for (p, n) in board.iter() {
+            if let Some(n) = n {
+                if e.masks[p.index()] & 1 << n == 0 {
+                    return NumprError::invalid_value(p, n);
+                }
+                e.place(p.index(), n);
+            }
+        }
+        e.trail.clear();
+        Ok(e)
+    }
+}
+
+impl<C: Chooser, S: Collector> Engine<C, S> {
+    /// Returns the engine reporting statistics of the search to `stats`.
+    pub fn with_stats<T: Collector>(self, stats: T) -> Engine<C, T> {
+        Engine {
+            numbers: self.numbers,
+            masks: self.masks,
+            empty: self.empty,
+            trail: self.trail,
+            singles: self.singles,
+            dead: self.dead,
+            stack: self.stack,
+            state: self.state,
+            chooser: self.chooser,
+            stats,
+            shuffle: self.shuffle,
+            hidden_singles: self.hidden_singles,
+            budget: self.budget,
+        }
+    }
+
+    /// Shuffles candidates of each branch before the chooser orders them.
+    pub fn shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// Sets whether to place numbers having a single place in a row, a column,
+    /// or a block before branching. It's disabled by default.
+    pub fn hidden_singles(mut self, enabled: bool) -> Self {
+        self.hidden_singles = enabled;
+        self
+    }
+
+    /// Makes `step` fail with `NumprError::BudgetExceeded` when `budget` runs
+    /// out. The first step and each guess count as a node.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Returns the current board.
+    pub fn board(&self) -> Board {
+        Board::new(&self.numbers).unwrap()
+    }
+
+    /// Returns the number of branches the current board is on.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Advances the search by a step.
+    pub fn step(&mut self) -> NumprResult<Step> {
+        match self.state {
+            State::Ready => {
+                budget::spend(&self.budget)?;
+                self.stats.node(0);
+                self.state = State::Searching;
+            }
+            State::Searching => {}
+            State::Failed => return self.backtrack(),
+            State::Exhausted => return Ok(Step::Exhausted),
+        }
+
+        if let Some(i) = self.dead {
+            self.state = State::Failed;
+            return Ok(Step::DeadEnd(pt(i)));
+        }
+        while let Some(i) = self.singles.pop() {
+            let i = i as usize;
+            if self.numbers[i] != 0 {
+                continue;
+            }
+            let n = self.masks[i].trailing_zeros() as u8;
+            self.place(i, n);
+            self.stats.forced();
+            return Ok(Step::Forced(pt(i), n));
+        }
+        if self.empty == 0 {
+            self.state = State::Failed;
+            return Ok(Step::Solved);
+        }
+        if self.hidden_singles {
+            if let Some(step) = self.hidden_single() {
+                return Ok(step);
+            }
+        }
+
+        let p = self.chooser.choose(&self.masks);
+        let mut frame = Frame {
+            index: p.index(),
+            candidates: [0; 9],
+            len: 0,
+            next: 0,
+            mark: self.trail.len(),
+        };
+        for n in 1..=9 {
+            if self.masks[p.index()] & 1 << n != 0 {
+                frame.candidates[frame.len] = n;
+                frame.len += 1;
+            }
+        }
+        let values = &mut frame.candidates[..frame.len];
+        if self.shuffle {
+            values.shuffle(&mut rand::thread_rng());
+        }
+        self.chooser.order(&self.masks, p, values);
+        self.stack.push(frame);
+        self.guess()
+    }
+
+    /// Runs the search until the next answer is found. It returns `None` when
+    /// no more answer exists.
+    pub fn next_answer(&mut self) -> NumprResult<Option<Board>> {
+        loop {
+            match self.step()? {
+                Step::Solved => return Ok(Some(self.board())),
+                Step::Exhausted => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
+    /// Puts `n` at the grid at the index `i` and removes it from candidates
+    /// of the peers.
+    fn place(&mut self, i: usize, n: u8) {
+        self.trail.push((i as u8, self.masks[i]));
+        self.numbers[i] = n;
+        self.masks[i] = 0;
+        self.empty -= 1;
+        let bit = 1 << n;
+        for &j in &PEERS[i] {
+            let m = self.masks[j as usize];
+            if m & bit == 0 {
+                continue;
+            }
+            self.trail.push((j, m));
+            let m = m & !bit;
+            self.masks[j as usize] = m;
+            if m == 0 {
+                self.dead.get_or_insert(j as usize);
+            } else if m & (m - 1) == 0 {
+                self.singles.push(j);
+            }
+        }
+    }
+
+    /// Places a number having a single place in a row, a column, or a block.
+    fn hidden_single(&mut self) -> Option<Step> {
+        for unit in &UNITS {
+            let (mut once, mut twice, mut placed) = (0u16, 0u16, 0u16);
+            for &i in unit {
+                let m = self.masks[i as usize];
+                twice |= once & m;
+                once |= m;
+                placed |= 1 << self.numbers[i as usize];
+            }
+            if (once | placed) & ALL != ALL {
+                self.state = State::Failed;
+                return Some(Step::DeadEnd(pt(unit[0] as usize)));
+            }
+
+            let singles = once & !twice;
+            if singles == 0 {
+                continue;
+            }
+            let n = singles.trailing_zeros() as u8;
+            let i = unit
+                .iter()
+                .map(|&i| i as usize)
+                .find(|&i| self.masks[i] & 1 << n != 0)
+                .unwrap();
+            self.place(i, n);
+            self.stats.forced();
+            return Some(Step::Forced(pt(i), n));
+        }
+        None
+    }
+
+    /// Tries the next candidate of the last branch.
+    fn guess(&mut self) -> NumprResult<Step> {
+        budget::spend(&self.budget)?;
+        let depth = self.stack.len();
+        let f = self.stack.last_mut().unwrap();
+        let (i, n) = (f.index, f.candidates[f.next]);
+        f.next += 1;
+        self.stats.guess();
+        self.stats.node(depth);
+        self.place(i, n);
+        self.state = State::Searching;
+        Ok(Step::Guess(pt(i), n))
+    }
+
+    fn backtrack(&mut self) -> NumprResult<Step> {
+        let (index, mark, exhausted) = match self.stack.last() {
+            Some(f) => (f.index, f.mark, f.next == f.len),
+            None => {
+                self.state = State::Exhausted;
+                return Ok(Step::Exhausted);
+            }
+        };
+        while self.trail.len() > mark {
+            let (i, m) = self.trail.pop().unwrap();
+            let i = i as usize;
+            if self.numbers[i] != 0 {
+                self.numbers[i] = 0;
+                self.empty += 1;
+            }
+            self.masks[i] = m;
+        }
+        self.singles.clear();
+        self.dead = None;
+        self.stats.backtrack();
+        if !exhausted {
+            return self.guess();
+        }
+        self.stack.pop();
+        Ok(Step::Backtrack(pt(index)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::choosers::{InOrder, Random};
+    use crate::error::NumprError;
+    use crate::pt::PtIter;
+    use crate::solvers::BitParallelSolver;
+
+    fn count<C: Chooser>(board: &Board, chooser: C) -> usize {
+        let mut e = Engine::new(board, chooser).unwrap();
+        let mut n = 0;
+        while let Some(b) = e.next_answer().unwrap() {
+            b.validate().unwrap();
+            n += 1;
+        }
+        n
+    }
+
+    #[test]
+    fn all_answers() {
+        let mut e = Engine::new(&Board::default(), Mrv::new()).unwrap();
+        let mut b = e.next_answer().unwrap().unwrap();
+        for p in PtIter::all().take(WIDTH * 2) {
+            b.set(p, 0).unwrap();
+        }
+        let n = BitParallelSolver::new().count(&b, 1000).unwrap();
+        assert_eq!(count(&b, Mrv::new()), n);
+        assert_eq!(count(&b, InOrder::new()), n);
+        assert_eq!(count(&b, Random::new()), n);
+    }
+
+    #[test]
+    fn steps() {
+        let mut e = Engine::new(&Board::default(), InOrder::new()).unwrap();
+        let answer = e.next_answer().unwrap().unwrap();
+        let mut b = answer;
+        b.set(Pt::new(4, 4).unwrap(), 0).unwrap();
+
+        let mut e = Engine::new(&b, InOrder::new()).unwrap();
+        let n = answer.get(Pt::new(4, 4).unwrap()).unwrap();
+        assert_eq!(e.step().unwrap(), Step::Forced(Pt::new(4, 4).unwrap(), n));
+        assert_eq!(e.step().unwrap(), Step::Solved);
+        assert_eq!(e.step().unwrap(), Step::Exhausted);
+        assert_eq!(e.step().unwrap(), Step::Exhausted);
+        assert!(e.board().iter().eq(answer.iter()));
+    }
+
+    #[test]
+    fn hidden_single() {
+        // 1 can only be placed at (0, 0) in the top left block, though the
+        // grid has other candidates.
+        let mut b = Board::default();
+        b.set(Pt::new(5, 1).unwrap(), 1).unwrap();
+        b.set(Pt::new(7, 2).unwrap(), 1).unwrap();
+        b.set(Pt::new(1, 4).unwrap(), 1).unwrap();
+        b.set(Pt::new(2, 6).unwrap(), 1).unwrap();
+
+        let mut e = Engine::new(&b, InOrder::new())
+            .unwrap()
+            .hidden_singles(true);
+        assert_eq!(e.step().unwrap(), Step::Forced(Pt::new(0, 0).unwrap(), 1));
+        let mut e = Engine::new(&b, InOrder::new()).unwrap();
+        assert!(matches!(e.step().unwrap(), Step::Guess(..)));
+    }
+
+    #[test]
+    fn resume() {
+        let mut e = Engine::new(&Board::default(), Mrv::new())
+            .unwrap()
+            .shuffle(true);
+        for _ in 0..100 {
+            e.step().unwrap();
+        }
+        let depth = e.depth();
+        assert!(depth > 0);
+        e.next_answer().unwrap().unwrap().validate().unwrap();
+    }
+
+    #[test]
+    fn budget() {
+        let mut e = Engine::new(&Board::default(), Mrv::new())
+            .unwrap()
+            .with_budget(Budget::new().max_nodes(3));
+        assert_eq!(e.next_answer().err(), Some(NumprError::BudgetExceeded));
+    }
+}
@@ -8,9 +8,19 @@ pub enum NumprError {
     IndexOutOfBounds(InvalidIndex),
     InvalidBoardLength(usize),
     InvalidValue(Pt, u8),
+    InvalidBoardCount(usize, usize),
+    BoardOutOfBounds(usize, usize),
+    Conflict(Pt, u8, u8),
     WrongAnswer,
     MultipleSolutions,
     Unsolvable,
+    InvalidConstraint(String),
+    BudgetExceeded,
+    GivenModified(Pt),
+    InvalidFormat(String),
+    InvalidTransform(String),
+    Parse(usize, String),
+    Io(String),
 }
 
 pub type NumprResult<T> = std::result::Result<T, NumprError>;
@@ -29,6 +39,18 @@ impl NumprError {
         Err(InvalidValue(pt, n))
     }
 
+    pub fn invalid_board_count<T>(expected: usize, count: usize) -> NumprResult<T> {
+        Err(InvalidBoardCount(expected, count))
+    }
+
+    pub fn board_out_of_bounds<T>(board: usize, boards: usize) -> NumprResult<T> {
+        Err(BoardOutOfBounds(board, boards))
+    }
+
+    pub fn conflict<T>(pt: Pt, n: u8, m: u8) -> NumprResult<T> {
+        Err(Conflict(pt, n, m))
+    }
+
     pub fn wrong_answer<T>() -> NumprResult<T> {
         Err(WrongAnswer)
     }
@@ -40,6 +62,30 @@ impl NumprError {
     pub fn unsolvable<T>() -> NumprResult<T> {
         Err(Unsolvable)
     }
+
+    pub fn invalid_constraint<T>(msg: impl Into<String>) -> NumprResult<T> {
+        Err(InvalidConstraint(msg.into()))
+    }
+
+    pub fn budget_exceeded<T>() -> NumprResult<T> {
+        Err(BudgetExceeded)
+    }
+
+    pub fn given_modified<T>(pt: Pt) -> NumprResult<T> {
+        Err(GivenModified(pt))
+    }
+
+    pub fn invalid_format<T>(msg: impl Into<String>) -> NumprResult<T> {
+        Err(InvalidFormat(msg.into()))
+    }
+
+    pub fn invalid_transform<T>(msg: impl Into<String>) -> NumprResult<T> {
+        Err(InvalidTransform(msg.into()))
+    }
+
+    pub fn parse<T>(line: usize, msg: impl Into<String>) -> NumprResult<T> {
+        Err(Parse(line, msg.into()))
+    }
 }
 
 impl std::fmt::Display for NumprError {
@@ -48,13 +94,40 @@ impl std::fmt::Display for NumprError {
             IndexOutOfBounds(i) => write!(f, "index out of bounds: ({}, {})", i.x, i.y),
             InvalidBoardLength(len) => write!(f, "slice must have length of 81: len = {}", *len),
             InvalidValue(pt, n) => write!(f, "invalid value at ({}, {}): {}", pt.x(), pt.y(), *n),
+            InvalidBoardCount(expected, count) => {
+                write!(f, "expected {} boards: count = {}", *expected, *count)
+            }
+            BoardOutOfBounds(board, boards) => {
+                write!(f, "board out of bounds: {} of {} boards", *board, *boards)
+            }
+            Conflict(pt, n, m) => write!(
+                f,
+                "conflicting values at ({}, {}): {} and {}",
+                pt.x(),
+                pt.y(),
+                *n,
+                *m
+            ),
             WrongAnswer => write!(f, "wrong answer"), // TODO: more details
             MultipleSolutions => write!(f, "the board has multiple solutions"),
             Unsolvable => write!(f, "the solution was not found"),
+            InvalidConstraint(msg) => write!(f, "invalid constraint: {}", msg),
+            BudgetExceeded => write!(f, "the search ran out of its budget"),
+            GivenModified(pt) => write!(f, "cannot modify the given at ({}, {})", pt.x(), pt.y()),
+            InvalidFormat(msg) => write!(f, "invalid format: {}", msg),
+            InvalidTransform(msg) => write!(f, "invalid transformation: {}", msg),
+            Parse(line, msg) => write!(f, "line {}: {}", line, msg),
+            Io(msg) => write!(f, "I/O error: {}", msg),
         }
     }
 }
 
+impl From<std::io::Error> for NumprError {
+    fn from(e: std::io::Error) -> NumprError {
+        Io(e.to_string())
+    }
+}
+
 impl From<NumprError> for String {
     fn from(e: NumprError) -> String {
         format!("{}", e)
@@ -0,0 +1,37 @@
+//! Puzzles shared by tests.
+
+use crate::board::Board;
+
+/// A puzzle rated as `Difficulty::Medium`.
+pub const MEDIUM: &str = "\
+    ...45.789\
+    4.67.9...\
+    .8.....5.\
+    2..5....1\
+    56..91...\
+    .9...4.67\
+    .45.789..\
+    .7.912.4.\
+    9..3.....";
+
+/// A puzzle rated as `Difficulty::Hard`, which needs many guesses without
+/// hidden singles.
+pub const HARD: &str = "\
+    8........\
+    ..36.....\
+    .7..9.2..\
+    .5...7...\
+    ....457..\
+    ...1...3.\
+    ..1....68\
+    ..85...1.\
+    .9....4..";
+
+/// Parses a board in a line having `.` as empty grids.
+pub fn board(s: &str) -> Board {
+    let n: Vec<u8> = s
+        .bytes()
+        .map(|c| if c == b'.' { 0 } else { c - b'0' })
+        .collect();
+    Board::new(&n).unwrap()
+}
@@ -0,0 +1,278 @@
+//! Readers and writers of puzzle collection files.
+//!
+//! Readers are iterators yielding a [`Record`](struct.Record.html) at a time,
+//! so large files are processed without loading them at once. Parse errors
+//! are reported as `NumprError::Parse` having the line number.
+//!
+//! Metadata are stored as pairs of a key and a value. Keys common to the
+//! formats are `name`, `author`, `description`, `comment`, `date`, `source`,
+//! `level`, and `url`.
+//!
+//! # Examples
+//!
+//! ```
+//! use numpr::formats::{Format, Record};
+//! use numpr::{NumprError, Pt};
+//!
+//! # use numpr::NumprResult;
+//! # fn main() -> NumprResult<()> {
+//! let sdm = "\
+//!     003020600900305001001806400008102900700000008006708200002609500800203009005010300\n\
+//!     200080300060070084030500209000105408000000000402706000301007040720040060004010003\n";
+//! let records = Format::Sdm
+//!     .reader(sdm.as_bytes())
+//!     .collect::<NumprResult<Vec<Record>>>()?;
+//! assert_eq!(records[1].puzzle.get(Pt::new(0, 0)?), Some(2));
+//!
+//! let csv = Format::Csv.write(Vec::new(), &records)?;
+//! assert!(String::from_utf8(csv).unwrap().starts_with("quizzes,solutions\n0030"));
+//!
+//! let err = Format::Sdm.reader("1234\n".as_bytes()).next().unwrap();
+//! assert!(matches!(err, Err(NumprError::Parse(1, _))));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::board::Board;
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use crate::puzzle::Puzzle;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+mod csv;
+mod opensudoku;
+mod sdk;
+mod sdm;
+mod ss;
+
+pub use self::csv::{CsvReader, CsvWriter};
+pub use opensudoku::{OpenSudokuReader, OpenSudokuWriter};
+pub use sdk::{SdkReader, SdkWriter};
+pub use sdm::{SdmReader, SdmWriter};
+pub use ss::{SsReader, SsWriter};
+
+/// A puzzle in a collection file.
+#[derive(Clone)]
+pub struct Record {
+    pub puzzle: Board,
+    pub solution: Option<Board>,
+    pub metadata: Vec<(String, String)>,
+}
+
+impl Record {
+    /// Returns a record of `puzzle` without the solution and metadata.
+    pub fn new(puzzle: Board) -> Self {
+        Self {
+            puzzle,
+            solution: None,
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Returns the first value of metadata `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl From<&Puzzle> for Record {
+    fn from(p: &Puzzle) -> Self {
+        Self {
+            puzzle: *p.givens(),
+            solution: Some(*p.solution()),
+            metadata: Vec::new(),
+        }
+    }
+}
+
+/// A type of collection files.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// SadMan Sudoku `.sdk` having metadata headers.
+    Sdk,
+    /// Simple Sudoku `.ss` grids.
+    Ss,
+    /// `.sdm` having a puzzle per line.
+    Sdm,
+    /// OpenSudoku XML.
+    OpenSudoku,
+    /// CSV having `quizzes` and `solutions` columns.
+    Csv,
+}
+
+impl Format {
+    /// Returns the format of `path` guessed from its extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "sdk" => Some(Format::Sdk),
+            "ss" => Some(Format::Ss),
+            "sdm" => Some(Format::Sdm),
+            "opensudoku" | "xml" => Some(Format::OpenSudoku),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+
+    /// Returns a reader of `r` in the format.
+    pub fn reader<'a, R: BufRead + 'a>(
+        self,
+        r: R,
+    ) -> Box<dyn Iterator<Item = NumprResult<Record>> + 'a> {
+        match self {
+            Format::Sdk => Box::new(SdkReader::new(r)),
+            Format::Ss => Box::new(SsReader::new(r)),
+            Format::Sdm => Box::new(SdmReader::new(r)),
+            Format::OpenSudoku => Box::new(OpenSudokuReader::new(r)),
+            Format::Csv => Box::new(CsvReader::new(r)),
+        }
+    }
+
+    /// Writes `records` to `w` in the format and returns `w`.
+    pub fn write<'a, W: Write>(
+        self,
+        w: W,
+        records: impl IntoIterator<Item = &'a Record>,
+    ) -> NumprResult<W> {
+        macro_rules! write_all {
+            ($writer:ident) => {{
+                let mut writer = $writer::new(w)?;
+                for r in records {
+                    writer.write(r)?;
+                }
+                writer.finish()
+            }};
+        }
+        match self {
+            Format::Sdk => write_all!(SdkWriter),
+            Format::Ss => write_all!(SsWriter),
+            Format::Sdm => write_all!(SdmWriter),
+            Format::OpenSudoku => write_all!(OpenSudokuWriter),
+            Format::Csv => write_all!(CsvWriter),
+        }
+    }
+}
+
+/// Lines of a reader with the current line number.
+struct Lines<R> {
+    r: R,
+    line: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+    fn new(r: R) -> Self {
+        Self { r, line: 0 }
+    }
+
+    /// Returns the next line without the line break.
+    fn next(&mut self) -> Option<NumprResult<String>> {
+        let mut s = String::new();
+        match self.r.read_line(&mut s) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.line += 1;
+                let len = s.trim_end_matches(&['\r', '\n'][..]).len();
+                s.truncate(len);
+                Some(Ok(s))
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+
+    /// Returns a parse error at the current line.
+    fn error<T>(&self, msg: impl Into<String>) -> NumprResult<T> {
+        NumprError::parse(self.line, msg)
+    }
+}
+
+/// Parses numbers of grids. `.` and `0` are empty grids.
+fn cells(s: &str) -> Option<Vec<u8>> {
+    s.chars()
+        .map(|c| match c {
+            '.' => Some(0),
+            _ => c.to_digit(10).map(|d| d as u8),
+        })
+        .collect()
+}
+
+/// Parses a board in a line.
+fn board(s: &str) -> Option<Board> {
+    cells(s).and_then(|n| Board::new(&n).ok())
+}
+
+/// Formats `b` in a line with `empty` as empty grids.
+fn line(b: &Board, empty: char) -> String {
+    b.iter()
+        .map(|(_, n)| n.map_or(empty, |n| (b'0' + n) as char))
+        .collect()
+}
+
+/// Formats the `y`-th row of `b`.
+fn row(b: &Board, y: usize, empty: char) -> impl Iterator<Item = char> + '_ {
+    (0..WIDTH).map(move |x| {
+        b.get(Pt::new(x, y).unwrap())
+            .map_or(empty, |n| (b'0' + n) as char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use crate::solvers::HeuristicSolver;
+
+    pub fn records() -> Vec<Record> {
+        (0..3)
+            .map(|i| {
+                let s = HeuristicSolver::new()
+                    .solve(&Board::default(), true)
+                    .unwrap();
+                let mut p = s;
+                for x in 0..9 {
+                    p.set(Pt::new(x, (x + i) % 9).unwrap(), 0).unwrap();
+                }
+                Record {
+                    puzzle: p,
+                    solution: Some(s),
+                    metadata: vec![("author".into(), format!("a{}", i))],
+                }
+            })
+            .collect()
+    }
+
+    pub fn same(a: &Board, b: &Board) -> bool {
+        a.iter().eq(b.iter())
+    }
+
+    #[test]
+    fn round_trip() {
+        let records = records();
+        for &f in &[
+            Format::Sdk,
+            Format::Ss,
+            Format::Sdm,
+            Format::OpenSudoku,
+            Format::Csv,
+        ] {
+            let w = f.write(Vec::new(), &records).unwrap();
+            let r = f.reader(&w[..]).collect::<NumprResult<Vec<_>>>().unwrap();
+            assert_eq!(r.len(), records.len(), "{:?}", f);
+            for (a, b) in r.iter().zip(&records) {
+                assert!(same(&a.puzzle, &b.puzzle), "{:?}", f);
+            }
+        }
+    }
+
+    #[test]
+    fn from_path() {
+        assert_eq!(Format::from_path("a/b.SDK"), Some(Format::Sdk));
+        assert_eq!(Format::from_path("b.opensudoku"), Some(Format::OpenSudoku));
+        assert_eq!(Format::from_path("b.txt"), None);
+        assert_eq!(Format::from_path("csv"), None);
+    }
+}
@@ -0,0 +1,124 @@
+use super::{Lines, Record};
+use crate::error::NumprResult;
+use std::io::{BufRead, Write};
+
+/// A reader of CSV files having `quizzes` and `solutions` columns.
+///
+/// The first line is the header. The `solutions` column is optional, and
+/// other columns are stored as metadata.
+pub struct CsvReader<R> {
+    lines: Lines<R>,
+    header: Option<Vec<String>>,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    pub fn new(r: R) -> Self {
+        Self {
+            lines: Lines::new(r),
+            header: None,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = NumprResult<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(l) => l,
+                Err(e) => return Some(Err(e)),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let header = match &self.header {
+                Some(h) => h,
+                None => {
+                    if !fields.contains(&"quizzes") {
+                        return Some(self.lines.error("no quizzes column"));
+                    }
+                    self.header = Some(fields.iter().map(|f| f.to_string()).collect());
+                    continue;
+                }
+            };
+            if fields.len() != header.len() {
+                let msg = format!("{} fields for {} columns", fields.len(), header.len());
+                return Some(self.lines.error(msg));
+            }
+
+            let mut record = Record::new(crate::board::Board::default());
+            for (key, value) in header.iter().zip(fields) {
+                match (key.as_str(), super::board(value)) {
+                    ("quizzes", Some(b)) => record.puzzle = b,
+                    ("solutions", Some(b)) => record.solution = Some(b),
+                    ("solutions", None) if value.is_empty() => {}
+                    ("quizzes", None) | ("solutions", None) => {
+                        return Some(self.lines.error(format!("invalid {}: {}", key, value)))
+                    }
+                    _ => record.metadata.push((key.clone(), value.to_string())),
+                }
+            }
+            return Some(Ok(record));
+        }
+    }
+}
+
+/// A writer of CSV files having `quizzes` and `solutions` columns. Metadata
+/// aren't written.
+pub struct CsvWriter<W> {
+    w: W,
+}
+
+impl<W: Write> CsvWriter<W> {
+    /// Returns a writer after writing the header.
+    pub fn new(mut w: W) -> NumprResult<Self> {
+        writeln!(w, "quizzes,solutions")?;
+        Ok(Self { w })
+    }
+
+    pub fn write(&mut self, r: &Record) -> NumprResult<()> {
+        let solution = r
+            .solution
+            .as_ref()
+            .map_or(String::new(), |s| super::line(s, '0'));
+        writeln!(self.w, "{},{}", super::line(&r.puzzle, '0'), solution)?;
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn finish(mut self) -> NumprResult<W> {
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NumprError;
+    use crate::formats::tests;
+
+    #[test]
+    fn read() {
+        let records = tests::records();
+        let w = crate::formats::Format::Csv
+            .write(Vec::new(), &records)
+            .unwrap();
+        let r: Vec<_> = CsvReader::new(&w[..]).map(Result::unwrap).collect();
+        let (a, b) = (r[1].solution.unwrap(), records[1].solution.unwrap());
+        assert!(tests::same(&a, &b));
+
+        let s = format!("id,quizzes\n1,{}\n2,{}\n", "0".repeat(81), "0".repeat(82));
+        let mut r = CsvReader::new(s.as_bytes());
+        let first = r.next().unwrap().unwrap();
+        assert!(first.solution.is_none());
+        assert_eq!(first.get("id"), Some("1"));
+        assert!(matches!(r.next(), Some(Err(NumprError::Parse(3, _)))));
+        assert!(matches!(
+            CsvReader::new("a,b\n".as_bytes()).next(),
+            Some(Err(NumprError::Parse(1, _)))
+        ));
+    }
+}
@@ -0,0 +1,207 @@
+use super::{Lines, Record};
+use crate::error::NumprResult;
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+
+/// Metadata keys for collection elements.
+const KEYS: [(&str, &str); 8] = [
+    ("name", "name"),
+    ("author", "author"),
+    ("description", "description"),
+    ("comment", "comment"),
+    ("created", "date"),
+    ("source", "source"),
+    ("level", "level"),
+    ("sourceURL", "url"),
+];
+
+/// A reader of OpenSudoku XML files.
+///
+/// Each `<game data="..."/>` is a puzzle, and elements of the collection
+/// like `<author>` are stored as metadata of all the puzzles. It isn't a
+/// general XML parser: an element has to fit in a line.
+pub struct OpenSudokuReader<R> {
+    lines: Lines<R>,
+    metadata: Vec<(String, String)>,
+    pending: VecDeque<Record>,
+}
+
+impl<R: BufRead> OpenSudokuReader<R> {
+    pub fn new(r: R) -> Self {
+        Self {
+            lines: Lines::new(r),
+            metadata: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn parse(&mut self, line: &str) -> NumprResult<()> {
+        for &(tag, key) in &KEYS {
+            if let Some(text) = element(line, tag) {
+                self.metadata.push((key.to_string(), unescape(text)));
+            }
+        }
+        for (i, _) in line.match_indices("<game") {
+            let tag = match line[i..].find('>') {
+                Some(end) => &line[i..i + end],
+                None => return self.lines.error("unterminated game element"),
+            };
+            let data = match attribute(tag, "data") {
+                Some(d) => d,
+                None => return self.lines.error("no data attribute"),
+            };
+            match super::board(data) {
+                Some(b) => self.pending.push_back(Record {
+                    puzzle: b,
+                    solution: None,
+                    metadata: self.metadata.clone(),
+                }),
+                None => return self.lines.error(format!("invalid puzzle: {}", data)),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for OpenSudokuReader<R> {
+    type Item = NumprResult<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            let line = match self.lines.next()? {
+                Ok(l) => l,
+                Err(e) => return Some(Err(e)),
+            };
+            if let Err(e) = self.parse(&line) {
+                self.pending.clear();
+                return Some(Err(e));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+/// A writer of OpenSudoku XML files. Solutions aren't written.
+///
+/// Metadata of the first record are written as those of the collection.
+pub struct OpenSudokuWriter<W> {
+    w: W,
+    first: bool,
+}
+
+impl<W: Write> OpenSudokuWriter<W> {
+    /// Returns a writer after writing the XML declaration.
+    pub fn new(mut w: W) -> NumprResult<Self> {
+        writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(w, "<opensudoku>")?;
+        Ok(Self { w, first: true })
+    }
+
+    pub fn write(&mut self, r: &Record) -> NumprResult<()> {
+        if std::mem::replace(&mut self.first, false) {
+            for (key, value) in &r.metadata {
+                if let Some((tag, _)) = KEYS.iter().find(|(_, k)| k == key) {
+                    writeln!(self.w, "  <{0}>{1}</{0}>", tag, escape(value))?;
+                }
+            }
+        }
+        writeln!(
+            self.w,
+            r#"  <game data="{}"/>"#,
+            super::line(&r.puzzle, '0')
+        )?;
+        Ok(())
+    }
+
+    /// Closes the collection, flushes, and returns the underlying writer.
+    pub fn finish(mut self) -> NumprResult<W> {
+        writeln!(self.w, "</opensudoku>")?;
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
+
+/// Returns the text of `<tag>text</tag>` in `line`.
+fn element<'a>(line: &'a str, tag: &str) -> Option<&'a str> {
+    let start = line.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let len = line[start..].find(&format!("</{}>", tag))?;
+    Some(&line[start..start + len])
+}
+
+/// Returns the value of attribute `name="value"` in `tag`.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!(" {}=\"", name);
+    let start = tag.find(&pattern)? + pattern.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+const ENTITIES: [(&str, &str); 5] = [
+    ("&", "&amp;"),
+    ("<", "&lt;"),
+    (">", "&gt;"),
+    ("\"", "&quot;"),
+    ("'", "&apos;"),
+];
+
+fn escape(s: &str) -> String {
+    ENTITIES
+        .iter()
+        .fold(s.to_string(), |s, (c, e)| s.replace(c, e))
+}
+
+fn unescape(s: &str) -> String {
+    ENTITIES
+        .iter()
+        .rev()
+        .fold(s.to_string(), |s, (c, e)| s.replace(e, c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NumprError;
+    use crate::formats::tests;
+    use crate::pt::Pt;
+
+    #[test]
+    fn read() {
+        let s = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <opensudoku>\n\
+             <name>Easy &amp; fun</name><created>2020-01-01</created>\n\
+             <game data=\"{0}\"/><game data=\"7{1}\" />\n\
+             <game data=\"{0}\" >\n\
+             <game data=\"123\"/>\n\
+             </opensudoku>\n",
+            "0".repeat(81),
+            "0".repeat(80)
+        );
+        let mut r = OpenSudokuReader::new(s.as_bytes());
+        let a = r.next().unwrap().unwrap();
+        assert_eq!(a.get("name"), Some("Easy & fun"));
+        assert_eq!(a.get("date"), Some("2020-01-01"));
+        let b = r.next().unwrap().unwrap();
+        assert_eq!(b.puzzle.get(Pt::new(0, 0).unwrap()), Some(7));
+        assert!(r.next().unwrap().is_ok());
+        assert!(matches!(r.next(), Some(Err(NumprError::Parse(6, _)))));
+        assert!(r.next().is_none());
+    }
+
+    #[test]
+    fn write() {
+        let mut records = tests::records();
+        records[0].metadata.push(("name".into(), "<A & B>".into()));
+        let w = crate::formats::Format::OpenSudoku
+            .write(Vec::new(), &records)
+            .unwrap();
+        let s = String::from_utf8(w).unwrap();
+        assert!(s.contains("<name>&lt;A &amp; B&gt;</name>"));
+        assert!(s.ends_with("</opensudoku>\n"));
+        let r: Vec<_> = OpenSudokuReader::new(s.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(r[2].get("name"), Some("<A & B>"));
+        assert_eq!(r[2].get("author"), Some("a0"));
+    }
+}
@@ -0,0 +1,165 @@
+use super::{Lines, Record};
+use crate::board::Board;
+use crate::consts::*;
+use crate::error::NumprResult;
+use std::io::{BufRead, Write};
+
+/// Metadata keys for header letters.
+const KEYS: [(char, &str); 8] = [
+    ('A', "author"),
+    ('B', "date"),
+    ('C', "comment"),
+    ('D', "description"),
+    ('L', "level"),
+    ('N', "name"),
+    ('S', "source"),
+    ('U', "url"),
+];
+
+/// A reader of SadMan Sudoku `.sdk` files.
+///
+/// A puzzle is 9 rows where `.` or `0` are empty grids, following optional
+/// metadata headers like `#Aauthor`. Header letters are converted to the
+/// common metadata keys, and unknown letters are kept as keys. Only the
+/// `[Puzzle]` section is read when the file has sections.
+pub struct SdkReader<R> {
+    lines: Lines<R>,
+    skip: bool,
+}
+
+impl<R: BufRead> SdkReader<R> {
+    pub fn new(r: R) -> Self {
+        Self {
+            lines: Lines::new(r),
+            skip: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SdkReader<R> {
+    type Item = NumprResult<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut cells = Vec::with_capacity(SIZE);
+        let mut metadata = Vec::new();
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(l)) => l,
+                Some(Err(e)) => return Some(Err(e)),
+                None if cells.is_empty() && metadata.is_empty() => return None,
+                None => return Some(self.lines.error("incomplete puzzle")),
+            };
+            let line = line.trim();
+            if let Some(section) = line.strip_prefix('[') {
+                self.skip = section != "Puzzle]";
+                continue;
+            }
+            if self.skip || line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('#') {
+                if !cells.is_empty() {
+                    return Some(self.lines.error("header inside a puzzle"));
+                }
+                let mut chars = header.chars();
+                let letter = match chars.next() {
+                    Some(c) => c,
+                    None => continue,
+                };
+                let key = KEYS
+                    .iter()
+                    .find(|(l, _)| *l == letter)
+                    .map_or(letter.to_string(), |(_, k)| k.to_string());
+                metadata.push((key, chars.as_str().trim().to_string()));
+                continue;
+            }
+            match super::cells(line) {
+                Some(row) if row.len() == WIDTH => cells.extend(row),
+                _ => return Some(self.lines.error(format!("invalid row: {}", line))),
+            }
+            if cells.len() == SIZE {
+                return Some(Board::new(&cells).map(|puzzle| Record {
+                    puzzle,
+                    solution: None,
+                    metadata,
+                }));
+            }
+        }
+    }
+}
+
+/// A writer of SadMan Sudoku `.sdk` files. Solutions aren't written.
+///
+/// Metadata having unknown keys are written as comments.
+pub struct SdkWriter<W> {
+    w: W,
+}
+
+impl<W: Write> SdkWriter<W> {
+    pub fn new(w: W) -> NumprResult<Self> {
+        Ok(Self { w })
+    }
+
+    pub fn write(&mut self, r: &Record) -> NumprResult<()> {
+        for (key, value) in &r.metadata {
+            match KEYS.iter().find(|(_, k)| k == key) {
+                Some((letter, _)) => writeln!(self.w, "#{}{}", letter, value)?,
+                None => writeln!(self.w, "#C{}: {}", key, value)?,
+            }
+        }
+        for y in 0..HEIGHT {
+            writeln!(
+                self.w,
+                "{}",
+                super::row(&r.puzzle, y, '.').collect::<String>()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn finish(mut self) -> NumprResult<W> {
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NumprError;
+    use crate::formats::tests;
+
+    #[test]
+    fn metadata() {
+        let mut records = tests::records();
+        records[0]
+            .metadata
+            .push(("difficulty".into(), "hard".into()));
+        let w = crate::formats::Format::Sdk
+            .write(Vec::new(), &records)
+            .unwrap();
+        let s = String::from_utf8(w).unwrap();
+        assert!(s.starts_with("#Aa0\n#Cdifficulty: hard\n"));
+        let r: Vec<_> = SdkReader::new(s.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(r[0].get("author"), Some("a0"));
+        assert_eq!(r[0].get("comment"), Some("difficulty: hard"));
+        assert_eq!(r[2].get("author"), Some("a2"));
+    }
+
+    #[test]
+    fn sections() {
+        let s = format!(
+            "[Puzzle]\n#Xvalue\n{}[State]\n{}",
+            "1........\n".repeat(9),
+            "2........\n".repeat(9)
+        );
+        let r: Vec<_> = SdkReader::new(s.as_bytes()).collect();
+        assert_eq!(r.len(), 1);
+        assert_eq!(r[0].as_ref().unwrap().get("X"), Some("value"));
+
+        let s = format!("#Aa\n{}#Ab\n", "1........\n".repeat(4));
+        let mut r = SdkReader::new(s.as_bytes());
+        assert!(matches!(r.next(), Some(Err(NumprError::Parse(6, _)))));
+    }
+}
@@ -0,0 +1,76 @@
+use super::{Lines, Record};
+use crate::error::NumprResult;
+use std::io::{BufRead, Write};
+
+/// A reader of `.sdm` files having a puzzle in 81 characters per line.
+///
+/// `0` or `.` are empty grids. Empty lines are skipped.
+pub struct SdmReader<R> {
+    lines: Lines<R>,
+}
+
+impl<R: BufRead> SdmReader<R> {
+    pub fn new(r: R) -> Self {
+        Self {
+            lines: Lines::new(r),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SdmReader<R> {
+    type Item = NumprResult<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(l) => l,
+                Err(e) => return Some(Err(e)),
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            return Some(match super::board(line) {
+                Some(b) => Ok(Record::new(b)),
+                None => self.lines.error(format!("invalid puzzle: {}", line)),
+            });
+        }
+    }
+}
+
+/// A writer of `.sdm` files. Solutions and metadata aren't written.
+pub struct SdmWriter<W> {
+    w: W,
+}
+
+impl<W: Write> SdmWriter<W> {
+    pub fn new(w: W) -> NumprResult<Self> {
+        Ok(Self { w })
+    }
+
+    pub fn write(&mut self, r: &Record) -> NumprResult<()> {
+        writeln!(self.w, "{}", super::line(&r.puzzle, '0'))?;
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn finish(mut self) -> NumprResult<W> {
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NumprError;
+
+    #[test]
+    fn errors() {
+        let s = format!("\n{}\n\n{}x\n", "0".repeat(81), "0".repeat(80));
+        let mut r = SdmReader::new(s.as_bytes());
+        assert!(r.next().unwrap().is_ok());
+        assert!(matches!(r.next(), Some(Err(NumprError::Parse(4, _)))));
+        assert!(r.next().is_none());
+    }
+}
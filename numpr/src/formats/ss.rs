@@ -0,0 +1,124 @@
+use super::{Lines, Record};
+use crate::board::Board;
+use crate::consts::*;
+use crate::error::NumprResult;
+use std::io::{BufRead, Write};
+
+/// A reader of Simple Sudoku `.ss` files.
+///
+/// A puzzle is 9 rows where `.`, `0`, or `X` are empty grids. `|` between
+/// blocks, lines consisting of `-`, `+`, and `*` between bands, and empty
+/// lines between puzzles are skipped.
+pub struct SsReader<R> {
+    lines: Lines<R>,
+}
+
+impl<R: BufRead> SsReader<R> {
+    pub fn new(r: R) -> Self {
+        Self {
+            lines: Lines::new(r),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SsReader<R> {
+    type Item = NumprResult<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut cells = Vec::with_capacity(SIZE);
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(l)) => l,
+                Some(Err(e)) => return Some(Err(e)),
+                None if cells.is_empty() => return None,
+                None => return Some(self.lines.error("incomplete puzzle")),
+            };
+            let line: String = line
+                .chars()
+                .filter(|c| !c.is_whitespace() && *c != '|')
+                .map(|c| if c == 'X' || c == 'x' { '.' } else { c })
+                .collect();
+            if line.chars().all(|c| matches!(c, '-' | '+' | '*')) {
+                continue;
+            }
+            match super::cells(&line) {
+                Some(row) if row.len() == WIDTH => cells.extend(row),
+                _ => return Some(self.lines.error(format!("invalid row: {}", line))),
+            }
+            if cells.len() == SIZE {
+                return Some(Board::new(&cells).map(Record::new));
+            }
+        }
+    }
+}
+
+/// A writer of Simple Sudoku `.ss` files. Solutions and metadata aren't
+/// written.
+pub struct SsWriter<W> {
+    w: W,
+    first: bool,
+}
+
+impl<W: Write> SsWriter<W> {
+    pub fn new(w: W) -> NumprResult<Self> {
+        Ok(Self { w, first: true })
+    }
+
+    pub fn write(&mut self, r: &Record) -> NumprResult<()> {
+        if !std::mem::replace(&mut self.first, false) {
+            writeln!(self.w)?;
+        }
+        for y in 0..HEIGHT {
+            if y > 0 && y % BLOCK_HEIGHT == 0 {
+                writeln!(self.w, "---+---+---")?;
+            }
+            let row: Vec<char> = super::row(&r.puzzle, y, '.').collect();
+            let blocks: Vec<String> = row
+                .chunks(BLOCK_WIDTH)
+                .map(|c| c.iter().collect())
+                .collect();
+            writeln!(self.w, "{}", blocks.join("|"))?;
+        }
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn finish(mut self) -> NumprResult<W> {
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NumprError;
+    use crate::pt::Pt;
+
+    #[test]
+    fn read() {
+        let s = "\
+            *-----------*\n\
+            |1..|...|...|\n\
+            |...|...|...|\n\
+            |...|...|...|\n\
+            |---+---+---|\n\
+            |...|.X.|...|\n\
+            |...|...|...|\n\
+            |...|...|...|\n\
+            |---+---+---|\n\
+            |...|...|...|\n\
+            |...|...|...|\n\
+            |...|...|..9|\n\
+            *-----------*\n\
+            \n\
+            123|...|...\n\
+            ...|...\n";
+        let mut r = SsReader::new(s.as_bytes());
+        let b = r.next().unwrap().unwrap().puzzle;
+        assert_eq!(b.get(Pt::new(0, 0).unwrap()), Some(1));
+        assert_eq!(b.get(Pt::new(4, 4).unwrap()), None);
+        assert_eq!(b.get(Pt::new(8, 8).unwrap()), Some(9));
+        assert!(matches!(r.next(), Some(Err(NumprError::Parse(16, _)))));
+    }
+}
@@ -0,0 +1,866 @@
+//! A play session of a puzzle.
+//!
+//! [`Game`](struct.Game.html) keeps numbers entered by a player apart from the
+//! givens, together with pencil marks, colors, and the history of actions.
+//!
+//! # Examples
+//!
+//! ```
+//! use numpr::game::{Game, Mark};
+//! use numpr::{solvers, Board, Pt, Puzzle, Solver};
+//!
+//! # use numpr::NumprResult;
+//! # fn main() -> NumprResult<()> {
+//! let answer = solvers::HeuristicSolver::new().solve(&Board::default(), true)?;
+//! let pt = Pt::new(4, 4)?;
+//! let mut givens = answer;
+//! givens.set(pt, 0)?;
+//!
+//! let mut game = Game::new(Puzzle::new(givens)?);
+//! game.toggle_mark(pt, Mark::Center, 1)?;
+//! assert!(game.enter(pt, answer.get(pt).unwrap())?);
+//! assert!(game.is_solved());
+//! assert!(game.enter(Pt::new(0, 0)?, 1).is_err());
+//!
+//! game.undo();
+//! assert!(!game.is_solved());
+//!
+//! let loaded = Game::load(&game.save())?;
+//! assert_eq!(loaded.marks(pt, Mark::Center).collect::<Vec<_>>(), vec![1]);
+//! assert!(loaded.can_redo());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::board::Board;
+use crate::consts::*;
+use crate::deductions::{self, Elimination};
+use crate::error::{NumprError, NumprResult};
+use crate::pt::{Pt, PtIter};
+use crate::puzzle::Puzzle;
+use crate::rules::Rules;
+use crate::solvers::UNITS;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// The version of the format written by [`Game::save`](struct.Game.html#method.save).
+pub const SAVE_VERSION: u32 = 1;
+
+const MAGIC: &str = "numpr-game";
+const LAYERS: [Layer; 4] = [Layer::Value, Layer::Corner, Layer::Center, Layer::Color];
+
+/// A kind of pencil marks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mark {
+    /// Numbers that must be placed at one of the marked grids in a block.
+    Corner,
+    /// Candidates of a grid.
+    Center,
+}
+
+/// A hint for the next move, ordered from the most urgent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Hint {
+    /// The number entered at the grid isn't the answer.
+    Mistake(Pt),
+    /// The number is the only candidate of the grid.
+    NakedSingle(Pt, u8),
+    /// The grid is the only place of the number in a row, a column, or a
+    /// block.
+    HiddenSingle(Pt, u8),
+    /// The answer of the grid, which needs other techniques to find.
+    Answer(Pt, u8),
+}
+
+/// A layer of a grid modified by actions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Layer {
+    /// The number entered by the player. 0 means empty.
+    Value,
+    /// Corner pencil marks.
+    Corner,
+    /// Center pencil marks.
+    Center,
+    /// The color. 0 means no color, and other values are defined by apps.
+    Color,
+}
+
+impl Layer {
+    fn name(self) -> &'static str {
+        match self {
+            Layer::Value => "value",
+            Layer::Corner => "corner",
+            Layer::Center => "center",
+            Layer::Color => "color",
+        }
+    }
+
+    fn max(self) -> u16 {
+        match self {
+            Layer::Value => 9,
+            Layer::Corner | Layer::Center => 0b11_1111_1110,
+            Layer::Color => 0xff,
+        }
+    }
+}
+
+impl From<Mark> for Layer {
+    fn from(m: Mark) -> Self {
+        match m {
+            Mark::Corner => Layer::Corner,
+            Mark::Center => Layer::Center,
+        }
+    }
+}
+
+/// A change of a layer of a grid. Pencil marks are bit masks where bit `n`
+/// stands for number `n`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub pt: Pt,
+    pub layer: Layer,
+    pub old: u16,
+    pub new: u16,
+}
+
+/// A play session of a puzzle.
+///
+/// Every action modifying the grids can be undone and redone. Actions that
+/// don't change anything aren't recorded.
+#[derive(Clone)]
+pub struct Game {
+    puzzle: Puzzle,
+    layers: [[u16; SIZE]; 4],
+    undo: Vec<Vec<Change>>,
+    redo: Vec<Vec<Change>>,
+    mistakes: u32,
+    elapsed: Duration,
+    started: Option<Instant>,
+}
+
+impl Game {
+    /// Starts a game of `puzzle`. The clock starts running.
+    pub fn new(puzzle: Puzzle) -> Self {
+        Self {
+            puzzle,
+            layers: [[0; SIZE]; 4],
+            undo: Vec::new(),
+            redo: Vec::new(),
+            mistakes: 0,
+            elapsed: Duration::default(),
+            started: Some(Instant::now()),
+        }
+    }
+
+    /// Returns the puzzle.
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    /// Returns the givens and the numbers entered by the player.
+    pub fn board(&self) -> Board {
+        let mut b = *self.puzzle.givens();
+        for (i, &n) in self.layer(Layer::Value).iter().enumerate() {
+            if n != 0 {
+                b.set(pt(i), n as u8).unwrap();
+            }
+        }
+        b
+    }
+
+    /// Returns the number at `pt`, which is either given or entered.
+    pub fn get(&self, pt: Pt) -> Option<u8> {
+        self.puzzle
+            .givens()
+            .get(pt)
+            .or(match self.raw(pt, Layer::Value) {
+                0 => None,
+                n => Some(n as u8),
+            })
+    }
+
+    /// Returns `true` when the grid at `pt` is given.
+    pub fn is_given(&self, pt: Pt) -> bool {
+        self.puzzle.is_given(pt)
+    }
+
+    /// Enters `n` at `pt` and returns whether it's the answer. A wrong number
+    /// counts as a mistake.
+    ///
+    /// `n` is also removed from pencil marks in the same row, column, and
+    /// block as a part of the action.
+    ///
+    /// It fails with `NumprError::GivenModified` when `pt` is given.
+    pub fn enter(&mut self, pt: Pt, n: u8) -> NumprResult<bool> {
+        if n == 0 || n > 9 {
+            return NumprError::invalid_value(pt, n);
+        }
+        self.check_editable(pt)?;
+        let correct = self.puzzle.check_entry(pt, n);
+        let mut changes = vec![self.change(pt, Layer::Value, n as u16)];
+        let mut seen = [false; SIZE];
+        seen[pt.index()] = true;
+        let peers = PtIter::row(pt)
+            .chain(PtIter::col(pt))
+            .chain(PtIter::block(pt));
+        for p in peers {
+            if std::mem::replace(&mut seen[p.index()], true) {
+                continue;
+            }
+            for &layer in &[Layer::Corner, Layer::Center] {
+                changes.push(self.change(p, layer, self.raw(p, layer) & !(1 << n)));
+            }
+        }
+        if self.commit(changes) && !correct {
+            self.mistakes += 1;
+        }
+        if self.is_solved() {
+            self.pause();
+        }
+        Ok(correct)
+    }
+
+    /// Erases the number entered at `pt`.
+    ///
+    /// It fails with `NumprError::GivenModified` when `pt` is given.
+    pub fn erase(&mut self, pt: Pt) -> NumprResult<()> {
+        self.check_editable(pt)?;
+        self.commit(vec![self.change(pt, Layer::Value, 0)]);
+        Ok(())
+    }
+
+    /// Adds pencil mark `n` at `pt`, or removes it if it's already marked.
+    ///
+    /// It fails with `NumprError::GivenModified` when `pt` is given.
+    pub fn toggle_mark(&mut self, pt: Pt, mark: Mark, n: u8) -> NumprResult<()> {
+        if n == 0 || n > 9 {
+            return NumprError::invalid_value(pt, n);
+        }
+        self.check_editable(pt)?;
+        let layer = mark.into();
+        let masks = self.raw(pt, layer) ^ (1 << n);
+        self.commit(vec![self.change(pt, layer, masks)]);
+        Ok(())
+    }
+
+    /// Removes all the pencil marks of `mark` at `pt`.
+    ///
+    /// It fails with `NumprError::GivenModified` when `pt` is given.
+    pub fn clear_marks(&mut self, pt: Pt, mark: Mark) -> NumprResult<()> {
+        self.check_editable(pt)?;
+        self.commit(vec![self.change(pt, mark.into(), 0)]);
+        Ok(())
+    }
+
+    /// Iterates over the pencil marks of `mark` at `pt` in ascending order.
+    pub fn marks(&self, pt: Pt, mark: Mark) -> impl Iterator<Item = u8> {
+        let masks = self.raw(pt, mark.into());
+        (1..=9).filter(move |n| masks & (1 << n) != 0)
+    }
+
+    /// Replaces center pencil marks of all the empty grids with their
+    /// candidates, as a single action.
+    pub fn auto_notes(&mut self) {
+        let board = self.board();
+        let masks = deductions::candidates(&board);
+        let changes = (0..SIZE)
+            .filter(|&i| board.raw_get(pt(i)) == 0)
+            .map(|i| self.change(pt(i), Layer::Center, masks[i]))
+            .collect();
+        self.commit(changes);
+    }
+
+    /// Removes center pencil marks eliminated by the simplest technique in
+    /// [`deductions`](../deductions/index.html) that makes progress, as a
+    /// single action. It returns the eliminations.
+    ///
+    /// Deductions are based on the center pencil marks, excluding numbers
+    /// already placed in the same row, column, or block. Candidates of grids
+    /// having no mark are computed from the board, but marks aren't added to
+    /// them.
+    pub fn eliminate(&mut self) -> Vec<Elimination> {
+        let board = self.board();
+        let mut masks = deductions::candidates(&board);
+        for (i, m) in masks.iter_mut().enumerate() {
+            let marks = self.layer(Layer::Center)[i];
+            if marks != 0 {
+                *m &= marks;
+            }
+        }
+        let eliminations: Vec<_> = deductions::eliminations(&board, &masks)
+            .into_iter()
+            .filter(|e| self.raw(e.pt, Layer::Center) & 1 << e.n != 0)
+            .collect();
+        let mut changes: Vec<Change> = Vec::new();
+        for e in &eliminations {
+            match changes.iter_mut().find(|c| c.pt == e.pt) {
+                Some(c) => c.new &= !(1 << e.n),
+                None => changes.push(self.change(
+                    e.pt,
+                    Layer::Center,
+                    masks[e.pt.index()] & !(1 << e.n),
+                )),
+            }
+        }
+        self.commit(changes);
+        eliminations
+    }
+
+    /// Returns grids having the same number as another grid in the same row,
+    /// column, or block, ordered from the top left corner.
+    pub fn conflicts(&self) -> Vec<Pt> {
+        let board = self.board();
+        PtIter::all()
+            .filter(|&p| {
+                let n = board.raw_get(p);
+                n != 0
+                    && PtIter::row(p)
+                        .chain(PtIter::col(p))
+                        .chain(PtIter::block(p))
+                        .any(|q| q != p && board.raw_get(q) == n)
+            })
+            .collect()
+    }
+
+    /// Returns a hint for the next move, or `None` when the board is filled
+    /// with the answer.
+    ///
+    /// Wrong numbers are pointed out first. Otherwise, it looks for singles
+    /// based on the numbers on the board, and falls back to the answer of the
+    /// grid having the fewest candidates.
+    pub fn hint(&self) -> Option<Hint> {
+        let board = self.board();
+        if let Some(p) = PtIter::all().find(|&p| {
+            let n = self.raw(p, Layer::Value) as u8;
+            n != 0 && !self.puzzle.check_entry(p, n)
+        }) {
+            return Some(Hint::Mistake(p));
+        }
+
+        let masks = deductions::candidates(&board);
+        let digit = |m: u16| m.trailing_zeros() as u8;
+        if let Some(i) = (0..SIZE).find(|&i| masks[i].count_ones() == 1) {
+            return Some(Hint::NakedSingle(pt(i), digit(masks[i])));
+        }
+        for unit in UNITS.iter() {
+            for n in 1..=9 {
+                let mut places = unit.iter().filter(|&&i| masks[i as usize] & 1 << n != 0);
+                if let (Some(&i), None) = (places.next(), places.next()) {
+                    return Some(Hint::HiddenSingle(pt(i as usize), n));
+                }
+            }
+        }
+        (0..SIZE)
+            .filter(|&i| board.raw_get(pt(i)) == 0)
+            .min_by_key(|&i| masks[i].count_ones())
+            .map(|i| Hint::Answer(pt(i), self.puzzle.solution().raw_get(pt(i))))
+    }
+
+    /// Paints the grid at `pt`, including given ones. 0 removes the color.
+    pub fn set_color(&mut self, pt: Pt, color: u8) {
+        self.commit(vec![self.change(pt, Layer::Color, color as u16)]);
+    }
+
+    /// Returns the color at `pt`. 0 means no color.
+    pub fn color(&self, pt: Pt) -> u8 {
+        self.raw(pt, Layer::Color) as u8
+    }
+
+    /// Undoes the last action. It returns `false` when there's nothing to
+    /// undo.
+    pub fn undo(&mut self) -> bool {
+        let action = match self.undo.pop() {
+            Some(a) => a,
+            None => return false,
+        };
+        for c in action.iter().rev() {
+            self.layers[c.layer as usize][c.pt.index()] = c.old;
+        }
+        self.redo.push(action);
+        true
+    }
+
+    /// Redoes the last undone action. It returns `false` when there's nothing
+    /// to redo. Redoing a wrong number doesn't count as another mistake.
+    pub fn redo(&mut self) -> bool {
+        let action = match self.redo.pop() {
+            Some(a) => a,
+            None => return false,
+        };
+        for c in &action {
+            self.layers[c.layer as usize][c.pt.index()] = c.new;
+        }
+        self.undo.push(action);
+        true
+    }
+
+    /// Returns `true` when there's an action to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` when there's an action to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Returns the actions that can be undone, from the oldest.
+    pub fn history(&self) -> &[Vec<Change>] {
+        &self.undo
+    }
+
+    /// Returns the number of wrong numbers entered so far. Undoing them
+    /// doesn't decrease the count.
+    pub fn mistakes(&self) -> u32 {
+        self.mistakes
+    }
+
+    /// Returns the time played so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed + self.started.map(|s| s.elapsed()).unwrap_or_default()
+    }
+
+    /// Stops the clock. It stops automatically once the puzzle is solved.
+    pub fn pause(&mut self) {
+        if let Some(s) = self.started.take() {
+            self.elapsed += s.elapsed();
+        }
+    }
+
+    /// Restarts the clock.
+    pub fn resume(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    /// Returns `true` when the clock is stopped.
+    pub fn is_paused(&self) -> bool {
+        self.started.is_none()
+    }
+
+    /// Returns `true` when the board is completed with the answer.
+    pub fn is_solved(&self) -> bool {
+        self.puzzle.is_solved(&self.board())
+    }
+
+    /// Serializes the game including its history to a line-oriented text.
+    ///
+    /// The first line holds the format version, so that future versions can
+    /// keep loading old saves. Variant rules aren't saved.
+    pub fn save(&self) -> String {
+        let mut s = format!("{} {}\n", MAGIC, SAVE_VERSION);
+        let board = |b: &Board| -> String {
+            b.iter()
+                .map(|(_, n)| (b'0' + n.unwrap_or(0)) as char)
+                .collect()
+        };
+        writeln!(s, "givens {}", board(self.puzzle.givens())).unwrap();
+        writeln!(s, "solution {}", board(self.puzzle.solution())).unwrap();
+        for &layer in &LAYERS {
+            write!(s, "{}", layer.name()).unwrap();
+            for (i, v) in self.layer(layer).iter().enumerate() {
+                s.push(if i == 0 { ' ' } else { ',' });
+                write!(s, "{:x}", v).unwrap();
+            }
+            s.push('\n');
+        }
+        writeln!(s, "mistakes {}", self.mistakes).unwrap();
+        writeln!(s, "elapsed {}", self.elapsed().as_millis()).unwrap();
+        if let Some(seed) = self.puzzle.seed() {
+            writeln!(s, "seed {}", seed).unwrap();
+        }
+        for (key, actions) in [("undo", &self.undo), ("redo", &self.redo)].iter() {
+            for a in actions.iter() {
+                write!(s, "{}", key).unwrap();
+                for c in a {
+                    let name = c.layer.name();
+                    write!(s, " {}:{}:{:x}:{:x}", c.pt.index(), name, c.old, c.new).unwrap();
+                }
+                s.push('\n');
+            }
+        }
+        s
+    }
+
+    /// Restores a classic game saved by
+    /// [`Game::save`](struct.Game.html#method.save).
+    ///
+    /// It fails with `NumprError::InvalidFormat` when `s` is malformed or
+    /// written in an unsupported version, and with
+    /// `NumprError::GivenModified` when the entries or the history modify a
+    /// given.
+    pub fn load(s: &str) -> NumprResult<Self> {
+        Self::load_with_rules(s, Rules::new())
+    }
+
+    /// Restores a game saved by [`Game::save`](struct.Game.html#method.save)
+    /// with variant `rules`.
+    pub fn load_with_rules(s: &str, rules: Rules) -> NumprResult<Self> {
+        let mut lines = s.lines();
+        match lines.next().and_then(|l| l.strip_prefix(MAGIC)) {
+            Some(v) if v.trim() == SAVE_VERSION.to_string() => {}
+            Some(v) => return NumprError::invalid_format(format!("unsupported version: {}", v)),
+            None => return NumprError::invalid_format("not a saved game"),
+        }
+
+        let (mut givens, mut solution, mut seed) = (None, None, None);
+        let mut layers = [[0; SIZE]; 4];
+        let (mut undo, mut redo) = (Vec::new(), Vec::new());
+        let (mut mistakes, mut elapsed) = (0, 0);
+        for line in lines.filter(|l| !l.is_empty()) {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "givens" => givens = Some(parse_board(value)?),
+                "solution" => solution = Some(parse_board(value)?),
+                "mistakes" => mistakes = parse_num(value)?,
+                "elapsed" => elapsed = parse_num(value)?,
+                "seed" => seed = Some(parse_num(value)?),
+                "undo" => undo.push(parse_action(value)?),
+                "redo" => redo.push(parse_action(value)?),
+                _ => match LAYERS.iter().find(|l| l.name() == key) {
+                    Some(&layer) => layers[layer as usize] = parse_layer(layer, value)?,
+                    None => return NumprError::invalid_format(format!("unknown key: {}", key)),
+                },
+            }
+        }
+
+        let mut puzzle = match (givens, solution) {
+            (Some(g), Some(s)) => Puzzle::from_parts(g, s, rules)?,
+            _ => return NumprError::invalid_format("missing givens or solution"),
+        };
+        if let Some(seed) = seed {
+            puzzle = puzzle.with_seed(seed);
+        }
+        let entries = &layers[Layer::Value as usize];
+        if let Some(i) = (0..SIZE).find(|&i| puzzle.is_given(pt(i)) && entries[i] != 0) {
+            return NumprError::given_modified(pt(i));
+        }
+        // Undoing or redoing these changes would bypass `check_editable`.
+        if let Some(c) = undo
+            .iter()
+            .chain(&redo)
+            .flatten()
+            .find(|c| c.layer == Layer::Value && puzzle.is_given(c.pt))
+        {
+            return NumprError::given_modified(c.pt);
+        }
+        let mut game = Self {
+            puzzle,
+            layers,
+            undo,
+            redo,
+            mistakes,
+            elapsed: Duration::from_millis(elapsed),
+            started: None,
+        };
+        if !game.is_solved() {
+            game.resume();
+        }
+        Ok(game)
+    }
+
+    fn layer(&self, layer: Layer) -> &[u16; SIZE] {
+        &self.layers[layer as usize]
+    }
+
+    fn raw(&self, pt: Pt, layer: Layer) -> u16 {
+        self.layer(layer)[pt.index()]
+    }
+
+    fn change(&self, pt: Pt, layer: Layer, new: u16) -> Change {
+        Change {
+            pt,
+            layer,
+            old: self.raw(pt, layer),
+            new,
+        }
+    }
+
+    fn check_editable(&self, pt: Pt) -> NumprResult<()> {
+        if self.is_given(pt) {
+            return NumprError::given_modified(pt);
+        }
+        Ok(())
+    }
+
+    /// Applies `changes` as a single action and returns `false` if it didn't
+    /// change anything.
+    fn commit(&mut self, mut changes: Vec<Change>) -> bool {
+        changes.retain(|c| c.old != c.new);
+        if changes.is_empty() {
+            return false;
+        }
+        for c in &changes {
+            self.layers[c.layer as usize][c.pt.index()] = c.new;
+        }
+        self.undo.push(changes);
+        self.redo.clear();
+        true
+    }
+}
+
+fn pt(i: usize) -> Pt {
+    Pt::new(i % WIDTH, i / WIDTH).unwrap()
+}
+
+fn parse_num<T: std::str::FromStr>(s: &str) -> NumprResult<T> {
+    s.trim()
+        .parse()
+        .or_else(|_| NumprError::invalid_format(format!("not a number: {}", s)))
+}
+
+fn parse_board(s: &str) -> NumprResult<Board> {
+    let n = s
+        .trim()
+        .chars()
+        .map(|c| c.to_digit(10).map(|d| d as u8))
+        .collect::<Option<Vec<_>>>();
+    match n {
+        Some(n) if n.len() == SIZE => Board::new(&n),
+        _ => NumprError::invalid_format(format!("not a board: {}", s)),
+    }
+}
+
+fn parse_value(layer: Layer, s: &str) -> NumprResult<u16> {
+    match u16::from_str_radix(s, 16) {
+        Ok(v)
+            if v <= layer.max() && (matches!(layer, Layer::Value | Layer::Color) || v & 1 == 0) =>
+        {
+            Ok(v)
+        }
+        _ => NumprError::invalid_format(format!("invalid {}: {}", layer.name(), s)),
+    }
+}
+
+fn parse_layer(layer: Layer, s: &str) -> NumprResult<[u16; SIZE]> {
+    let values = s
+        .split(',')
+        .map(|v| parse_value(layer, v))
+        .collect::<NumprResult<Vec<_>>>()?;
+    if values.len() != SIZE {
+        return NumprError::invalid_format(format!(
+            "{} needs {} values: len = {}",
+            layer.name(),
+            SIZE,
+            values.len()
+        ));
+    }
+    let mut a = [0; SIZE];
+    a.copy_from_slice(&values);
+    Ok(a)
+}
+
+fn parse_action(s: &str) -> NumprResult<Vec<Change>> {
+    s.split_whitespace()
+        .map(|c| {
+            let f: Vec<&str> = c.split(':').collect();
+            let (i, layer) = match f.as_slice() {
+                [i, name, _, _] => (
+                    parse_num::<usize>(i)?,
+                    LAYERS.iter().find(|l| l.name() == *name),
+                ),
+                _ => return NumprError::invalid_format(format!("invalid change: {}", c)),
+            };
+            match layer {
+                Some(&layer) if i < SIZE => Ok(Change {
+                    pt: pt(i),
+                    layer,
+                    old: parse_value(layer, f[2])?,
+                    new: parse_value(layer, f[3])?,
+                }),
+                _ => NumprError::invalid_format(format!("invalid change: {}", c)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::solver::Solver;
+    use crate::solvers::HeuristicSolver;
+
+    fn game() -> (Game, Board) {
+        let answer = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        let mut givens = answer;
+        for x in 0..9 {
+            givens.set(Pt::new(x, x).unwrap(), 0).unwrap();
+        }
+        (Game::new(Puzzle::new(givens).unwrap()), answer)
+    }
+
+    #[test]
+    fn givens() {
+        let (mut g, _) = game();
+        let pt = Pt::new(1, 0).unwrap();
+        assert_eq!(g.enter(pt, 1), Err(NumprError::GivenModified(pt)));
+        assert_eq!(g.erase(pt), Err(NumprError::GivenModified(pt)));
+        assert!(g.toggle_mark(pt, Mark::Corner, 1).is_err());
+        g.set_color(pt, 3);
+        assert_eq!(g.color(pt), 3);
+        assert!(g.enter(Pt::new(0, 0).unwrap(), 10).is_err());
+    }
+
+    #[test]
+    fn undo_redo() {
+        let (mut g, answer) = game();
+        let pt = Pt::new(0, 0).unwrap();
+        let n = answer.get(pt).unwrap();
+        assert!(!g.enter(pt, n % 9 + 1).unwrap());
+        assert!(g.enter(pt, n).unwrap());
+        g.enter(pt, n).unwrap();
+        assert_eq!(g.mistakes(), 1);
+        assert_eq!(g.history().len(), 2);
+
+        g.toggle_mark(pt, Mark::Center, 3).unwrap();
+        g.toggle_mark(pt, Mark::Center, 5).unwrap();
+        g.toggle_mark(pt, Mark::Center, 3).unwrap();
+        assert!(g.marks(pt, Mark::Center).eq(vec![5]));
+        assert!(g.undo());
+        assert!(g.marks(pt, Mark::Center).eq(vec![3, 5]));
+        assert!(g.undo() && g.undo() && g.undo());
+        assert_eq!(g.get(pt), Some(n % 9 + 1));
+        assert!(g.redo());
+        assert_eq!(g.get(pt), Some(n));
+        assert_eq!(g.mistakes(), 1);
+
+        g.erase(pt).unwrap();
+        assert!(!g.can_redo());
+        assert_eq!(g.get(pt), None);
+        assert!(g.undo() && g.undo() && g.undo());
+        assert!(!g.undo());
+    }
+
+    #[test]
+    fn notes() {
+        let (mut g, answer) = game();
+        g.auto_notes();
+        let (a, b) = (Pt::new(0, 0).unwrap(), Pt::new(1, 1).unwrap());
+        assert!(g.marks(a, Mark::Center).eq(answer.get(a)));
+        assert!(g
+            .marks(Pt::new(1, 0).unwrap(), Mark::Center)
+            .next()
+            .is_none());
+
+        // Both (0, 0) and (1, 1) are in the top left block.
+        let n = answer.get(b).unwrap();
+        g.toggle_mark(a, Mark::Corner, n).unwrap();
+        g.toggle_mark(a, Mark::Center, n).unwrap();
+        g.enter(b, n).unwrap();
+        assert_eq!(g.marks(a, Mark::Corner).count(), 0);
+        assert!(g.marks(a, Mark::Center).eq(answer.get(a)));
+        assert!(g.marks(b, Mark::Center).eq(Some(n)));
+        g.undo();
+        assert!(g.marks(a, Mark::Corner).eq(Some(n)));
+        assert_eq!(g.marks(a, Mark::Center).count(), 2);
+    }
+
+    #[test]
+    fn eliminate() {
+        let (mut g, _) = game();
+        assert!(g.eliminate().is_empty());
+        assert!(!g.can_undo());
+
+        let medium = fixtures::board(fixtures::MEDIUM);
+        let mut g = Game::new(Puzzle::new(medium).unwrap());
+        let pt = Pt::new(1, 0).unwrap();
+        g.auto_notes();
+        g.clear_marks(pt, Mark::Center).unwrap();
+        let e = g.eliminate();
+        assert!(!e.is_empty());
+        assert!(e.iter().all(|e| e.pt != pt));
+        for e in &e {
+            assert!(g.marks(e.pt, Mark::Center).all(|n| n != e.n));
+            assert!(!g.puzzle().check_entry(e.pt, e.n));
+        }
+        assert_eq!(g.history().len(), 3);
+        g.undo();
+        assert!(e
+            .iter()
+            .all(|e| g.marks(e.pt, Mark::Center).any(|n| n == e.n)));
+    }
+
+    #[test]
+    fn hint() {
+        let (mut g, answer) = game();
+        let pt = Pt::new(0, 0).unwrap();
+        let n = answer.get(pt).unwrap();
+        assert!(g.conflicts().is_empty());
+        assert_eq!(g.hint(), Some(Hint::NakedSingle(pt, n)));
+
+        g.enter(pt, n % 9 + 1).unwrap();
+        let c = g.conflicts();
+        assert!(c.contains(&pt) && c.len() > 1);
+        assert_eq!(g.hint(), Some(Hint::Mistake(pt)));
+
+        for x in 0..9 {
+            let p = Pt::new(x, x).unwrap();
+            g.enter(p, answer.get(p).unwrap()).unwrap();
+        }
+        assert!(g.conflicts().is_empty());
+        assert_eq!(g.hint(), None);
+    }
+
+    #[test]
+    fn clock() {
+        let (mut g, answer) = game();
+        assert!(!g.is_paused());
+        g.pause();
+        let t = g.elapsed();
+        assert_eq!(g.elapsed(), t);
+        g.resume();
+        for x in 0..9 {
+            let pt = Pt::new(x, x).unwrap();
+            g.enter(pt, answer.get(pt).unwrap()).unwrap();
+        }
+        assert!(g.is_solved());
+        assert!(g.is_paused());
+    }
+
+    #[test]
+    fn save_load() {
+        let (mut g, answer) = game();
+        let pt = Pt::new(2, 2).unwrap();
+        let n = answer.get(pt).unwrap();
+        g.enter(pt, n % 9 + 1).unwrap();
+        g.toggle_mark(pt, Mark::Corner, 9).unwrap();
+        g.set_color(Pt::new(8, 8).unwrap(), 255);
+        g.undo();
+        g.pause();
+
+        let s = g.save();
+        let l = Game::load(&s).unwrap();
+        assert!(l.board().iter().eq(g.board().iter()));
+        assert!(l.marks(pt, Mark::Corner).eq(vec![9]));
+        assert_eq!(l.color(Pt::new(8, 8).unwrap()), 0);
+        assert_eq!(l.mistakes(), 1);
+        assert_eq!(l.history(), g.history());
+        assert!(l.elapsed() >= Duration::from_millis(g.elapsed().as_millis() as u64));
+        assert_eq!(l.save().lines().nth(2), s.lines().nth(2));
+
+        let err = |s: &str| match Game::load(s) {
+            Err(NumprError::InvalidFormat(_)) => {}
+            _ => panic!("unexpected result"),
+        };
+        err("");
+        err(&s.replace("numpr-game 1", "numpr-game 2"));
+        err(&s.replace("mistakes", "mistake"));
+        err(&s.replace("redo ", "redo 81:value:0:1 "));
+        let pt = Pt::new(1, 0).unwrap();
+        let entries = format!("value 0,{},", n);
+        assert_eq!(
+            Game::load(&s.replacen("value 0,0,", &entries, 1)).err(),
+            Some(NumprError::GivenModified(pt))
+        );
+        assert_eq!(
+            Game::load(&s.replace("redo ", "redo 1:value:0:5 ")).err(),
+            Some(NumprError::GivenModified(pt))
+        );
+        err(&s.replacen("value 0,", "value ", 1));
+        err(&s.replacen("givens ", "givens 0", 1));
+    }
+}
@@ -1,3 +1,9 @@
 mod naive;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod seeded;
 
 pub use naive::NaiveGenerator;
+#[cfg(feature = "parallel")]
+pub use parallel::ParallelGenerator;
+pub use seeded::SeededGenerator;
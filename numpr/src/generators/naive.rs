@@ -1,5 +1,5 @@
 use crate::board::Board;
-use crate::error::NumprResult;
+use crate::error::{NumprError, NumprResult};
 use crate::generator::Generator;
 use crate::pt::{Pt, PtIter};
 use crate::solver::Solver;
@@ -36,9 +36,13 @@ impl Generator for NaiveGenerator {
             b.set(pt, 0)?;
 
             let u = unique_factory();
-            if let Err(_) = u.check(&b, &solver_factory) {
-                b.set(pt, prev_state)?;
-                continue;
+            match u.check(&b, &solver_factory) {
+                Ok(()) => {}
+                Err(NumprError::BudgetExceeded) => return NumprError::budget_exceeded(),
+                Err(_) => {
+                    b.set(pt, prev_state)?;
+                    continue;
+                }
             }
 
             self.max_empty_grid -= 1;
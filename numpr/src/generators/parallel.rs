@@ -0,0 +1,132 @@
+use crate::board::Board;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use crate::solver::Solver;
+use crate::unique::UniquenessChecker;
+use rand::prelude::*;
+use rayon::prelude::*;
+use std::collections::VecDeque;
+
+/// Generates puzzles in the same way as
+/// [`NaiveGenerator`](struct.NaiveGenerator.html), but checks several grids
+/// to be removed in parallel.
+///
+/// Each round tries removing as many grids as the threads from the current
+/// board. The first grid whose removal keeps the answer unique is removed.
+/// Grids before it are never tried again because removing more grids can't
+/// make the answer unique again, and grids after it that succeeded are tried
+/// again with the new board. So, the result is the same as removing grids
+/// one by one in the same order.
+///
+/// It doesn't implement [`Generator`](../trait.Generator.html) because the
+/// factories are called from several threads, which needs them to be `Sync`.
+///
+/// This generator is available with the `parallel` feature.
+pub struct ParallelGenerator {
+    max_empty_grid: u32,
+}
+
+impl ParallelGenerator {
+    pub fn new(max_empty_grid: u32) -> ParallelGenerator {
+        ParallelGenerator { max_empty_grid }
+    }
+
+    /// Generates a puzzle from `board` in the same way as
+    /// [`Generator::generate`](../trait.Generator.html#tymethod.generate).
+    pub fn generate<S, U>(
+        self,
+        board: &Board,
+        solver_factory: impl Fn() -> S + Sync,
+        unique_factory: impl Fn() -> U + Sync,
+    ) -> NumprResult<Board>
+    where
+        S: Solver,
+        U: UniquenessChecker,
+    {
+        let mut targets: Vec<Pt> = board
+            .iter()
+            .filter(|(_, n)| n.is_some())
+            .map(|(pt, _)| pt)
+            .collect();
+        targets.shuffle(&mut rand::thread_rng());
+        let mut targets: VecDeque<Pt> = targets.into();
+        let width = rayon::current_num_threads();
+
+        let mut b = *board;
+        let mut removed = 0;
+        while removed < self.max_empty_grid && !targets.is_empty() {
+            let n = width.min(targets.len());
+            let round: Vec<Pt> = targets.drain(..n).collect();
+            let unique: Vec<bool> = round
+                .par_iter()
+                .map(|&pt| {
+                    let mut t = b;
+                    t.set(pt, 0)?;
+                    match unique_factory().check(&t, &solver_factory) {
+                        Ok(()) => Ok(true),
+                        Err(NumprError::BudgetExceeded) => NumprError::budget_exceeded(),
+                        Err(_) => Ok(false),
+                    }
+                })
+                .collect::<NumprResult<_>>()?;
+
+            if let Some(k) = unique.iter().position(|&u| u) {
+                b.set(round[k], 0)?;
+                removed += 1;
+                for (i, &pt) in round.iter().enumerate().skip(k + 1).rev() {
+                    if unique[i] {
+                        targets.push_front(pt);
+                    }
+                }
+            }
+        }
+        Ok(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::placer::Placer;
+    use crate::placers::NaivePlacer;
+    use crate::solvers::HeuristicSolver;
+    use crate::uniques::NaiveUniquenessChecker;
+
+    #[test]
+    fn generate() {
+        let b = NaivePlacer::new().place(HeuristicSolver::new()).unwrap();
+        let g = ParallelGenerator::new(crate::consts::SIZE as u32)
+            .generate(&b, HeuristicSolver::new, NaiveUniquenessChecker::new)
+            .unwrap();
+
+        NaiveUniquenessChecker::new()
+            .check(&g, HeuristicSolver::new)
+            .unwrap();
+        let answer = HeuristicSolver::new().solve(&g, true).unwrap();
+        assert!(b.iter().eq(answer.iter()));
+
+        // No more grid can be removed.
+        for (pt, _) in g.iter().filter(|(_, n)| n.is_some()) {
+            let mut t = g;
+            t.set(pt, 0).unwrap();
+            assert!(NaiveUniquenessChecker::new()
+                .check(&t, HeuristicSolver::new)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn max_empty_grid() {
+        let b = NaivePlacer::new().place(HeuristicSolver::new()).unwrap();
+        let g = ParallelGenerator::new(10)
+            .generate(&b, HeuristicSolver::new, NaiveUniquenessChecker::new)
+            .unwrap();
+        assert_eq!(g.iter().filter(|(_, n)| n.is_none()).count(), 10);
+
+        // Grids already empty aren't counted.
+        let g = ParallelGenerator::new(5)
+            .generate(&g, HeuristicSolver::new, NaiveUniquenessChecker::new)
+            .unwrap();
+        assert_eq!(g.iter().filter(|(_, n)| n.is_none()).count(), 15);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::board::Board;
+use crate::budget::{self, Budget};
+use crate::error::{NumprError, NumprResult};
+use crate::generator::Generator;
+use crate::pt::{Pt, PtIter};
+use crate::puzzle::{Puzzle, Symmetry};
+use crate::rating::{self, Difficulty};
+use crate::rules::Rules;
+use crate::solver::Solver;
+use crate::solvers::BitParallelSolver;
+use crate::unique::UniquenessChecker;
+use crate::uniques::NaiveUniquenessChecker;
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// A generator reproducing the same puzzle from the same seed.
+///
+/// Grids are removed in groups keeping `symmetry`, so the puzzle may have
+/// more givens than ones generated without symmetry.
+///
+/// Random numbers are drawn from ChaCha8 keyed by the seed and used without
+/// any distribution of `rand`, so the same seed gives the same puzzle on any
+/// platform and with any version of `rand`.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::generators::SeededGenerator;
+/// use numpr::Symmetry;
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let p = SeededGenerator::new(42).symmetry(Symmetry::Rotational180).puzzle()?;
+/// let q = SeededGenerator::new(42).symmetry(Symmetry::Rotational180).puzzle()?;
+/// assert_eq!(p.id(), q.id());
+/// assert_eq!(p.seed(), Some(42));
+/// # Ok(())
+/// # }
+/// ```
+pub struct SeededGenerator {
+    seed: u64,
+    rng: ChaCha8Rng,
+    symmetry: Symmetry,
+    difficulty: Option<Difficulty>,
+    budget: Option<Budget>,
+}
+
+impl SeededGenerator {
+    /// Returns a generator seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: rng(seed),
+            symmetry: Symmetry::None,
+            difficulty: None,
+            budget: None,
+        }
+    }
+
+    /// Keeps `symmetry` of positions of givens. No symmetry is kept by
+    /// default.
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    /// Aims at `difficulty` by keeping givens whose removal would make the
+    /// puzzle harder. The puzzle is never harder than `difficulty`, but it can
+    /// be easier when no more grid can be removed before reaching it.
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    /// Fails with `NumprError::BudgetExceeded` when `budget` runs out while
+    /// generating a puzzle.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Generates an answer and a puzzle from it with the seed recorded.
+    pub fn puzzle(mut self) -> NumprResult<Puzzle> {
+        let mut answer = Board::default();
+        if !fill(&mut answer, &mut self.rng, &self.budget)? {
+            return NumprError::unsolvable();
+        }
+        let seed = self.seed;
+        let budget = self.budget.clone();
+        let p = match budget {
+            Some(b) => {
+                let solver_factory = || BitParallelSolver::new().with_budget(b.clone());
+                let unique_factory = || NaiveUniquenessChecker::new().with_budget(b.clone());
+                let givens = self.generate(&answer, solver_factory, unique_factory)?;
+                Puzzle::with_budget(givens, Rules::new(), b)?
+            }
+            None => {
+                let givens =
+                    self.generate(&answer, BitParallelSolver::new, NaiveUniquenessChecker::new)?;
+                Puzzle::new(givens)?
+            }
+        };
+        Ok(p.with_seed(seed))
+    }
+}
+
+impl Generator for SeededGenerator {
+    fn generate<S, U>(
+        mut self,
+        board: &Board,
+        solver_factory: impl Fn() -> S,
+        unique_factory: impl Fn() -> U,
+    ) -> NumprResult<Board>
+    where
+        S: Solver,
+        U: UniquenessChecker,
+    {
+        let mut targets: Vec<Pt> = PtIter::all().collect();
+        shuffle(&mut targets, &mut self.rng);
+
+        let mut b = *board;
+        for pt in targets {
+            if b.get(pt).is_none() {
+                // Already removed with a symmetric grid.
+                continue;
+            }
+            let orbit = self.symmetry.orbit(pt);
+            let prev: Vec<u8> = orbit.iter().map(|&p| b.raw_get(p)).collect();
+            for &p in &orbit {
+                b.set(p, 0)?;
+            }
+
+            let removed = match unique_factory().check(&b, &solver_factory) {
+                Ok(()) => match self.difficulty {
+                    Some(d) => rating::grade(&b)? <= d,
+                    None => true,
+                },
+                Err(NumprError::BudgetExceeded) => return NumprError::budget_exceeded(),
+                Err(_) => false,
+            };
+            if !removed {
+                for (&p, &n) in orbit.iter().zip(&prev) {
+                    b.set(p, n)?;
+                }
+            }
+        }
+        Ok(b)
+    }
+}
+
+/// Returns ChaCha8 keyed by `seed` in little endian followed by zeros.
+fn rng(seed: u64) -> ChaCha8Rng {
+    let mut key = [0; 32];
+    key[..8].copy_from_slice(&seed.to_le_bytes());
+    ChaCha8Rng::from_seed(key)
+}
+
+/// Shuffles `v` by Fisher-Yates. The bias of the modulo is negligible for
+/// slices as short as a board.
+fn shuffle<T>(v: &mut [T], rng: &mut ChaCha8Rng) {
+    for i in (1..v.len()).rev() {
+        let j = rng.next_u64() % (i as u64 + 1);
+        v.swap(i, j as usize);
+    }
+}
+
+/// Fills empty grids of `b` trying candidates in an order chosen by `rng`.
+fn fill(b: &mut Board, rng: &mut ChaCha8Rng, budget: &Option<Budget>) -> NumprResult<bool> {
+    budget::spend(budget)?;
+    // The grid having the fewest candidates.
+    let next = b
+        .iter()
+        .filter(|(_, n)| n.is_none())
+        .map(|(pt, _)| {
+            let c: Vec<u8> = b.candidates(pt, false).into_iter().collect();
+            (pt, c)
+        })
+        .min_by_key(|(_, c)| c.len());
+    let (pt, mut candidates) = match next {
+        Some(n) => n,
+        None => return Ok(true),
+    };
+    shuffle(&mut candidates, rng);
+    for n in candidates {
+        b.set(pt, n).unwrap();
+        if fill(b, rng, budget)? {
+            return Ok(true);
+        }
+    }
+    b.set(pt, 0).unwrap();
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reproducible() {
+        let p = SeededGenerator::new(1).puzzle().unwrap();
+        assert!(p.solution().validate().is_ok());
+        assert!(p.clues() < 40);
+        let q = SeededGenerator::new(1).puzzle().unwrap();
+        assert!(p.givens().iter().eq(q.givens().iter()));
+        let r = SeededGenerator::new(2).puzzle().unwrap();
+        assert!(!p.givens().iter().eq(r.givens().iter()));
+
+        // Pinned so that updating dependencies can't change puzzles of seeds.
+        let golden: Vec<u8> =
+            "000000007004000020300020400000050009000060300020084076070009210080000000512000800"
+                .bytes()
+                .map(|c| c - b'0')
+                .collect();
+        assert!(p.givens().iter().eq(Board::new(&golden).unwrap().iter()));
+    }
+
+    #[test]
+    fn difficulty() {
+        for &d in &[Difficulty::Easy, Difficulty::Medium] {
+            let p = SeededGenerator::new(4).difficulty(d).puzzle().unwrap();
+            assert!(p.difficulty().unwrap() <= d);
+        }
+        let p = SeededGenerator::new(4)
+            .difficulty(Difficulty::Easy)
+            .puzzle()
+            .unwrap();
+        assert_eq!(p.difficulty(), Some(Difficulty::Easy));
+        assert!(p.clues() < 50);
+    }
+
+    #[test]
+    fn budget() {
+        let b = Budget::new().max_nodes(10);
+        assert!(matches!(
+            SeededGenerator::new(1).with_budget(b).puzzle(),
+            Err(NumprError::BudgetExceeded)
+        ));
+        let b = Budget::new().max_nodes(1_000_000);
+        let p = SeededGenerator::new(1).with_budget(b).puzzle().unwrap();
+        let q = SeededGenerator::new(1).puzzle().unwrap();
+        assert_eq!(p.id(), q.id());
+    }
+
+    #[test]
+    fn symmetry() {
+        for &s in &[
+            Symmetry::Rotational90,
+            Symmetry::Horizontal,
+            Symmetry::AntiDiagonal,
+        ] {
+            let p = SeededGenerator::new(3).symmetry(s).puzzle().unwrap();
+            let b = p.givens();
+            assert!(PtIter::all().all(|pt| s
+                .orbit(pt)
+                .iter()
+                .all(|&q| b.get(q).is_some() == b.get(pt).is_some())));
+        }
+    }
+}
@@ -3,26 +3,55 @@
 //! `numpr` provides utilities to solve, generate, and evaluate number place
 //! puzzles.
 
+#[cfg(feature = "parallel")]
+pub mod batch;
+mod bitboard;
 mod board;
+pub mod budget;
+pub mod canonical;
+mod chooser;
+pub mod choosers;
+mod constraint;
+pub mod constraints;
 mod consts;
+pub mod deductions;
+pub mod engine;
 pub mod error;
+#[cfg(test)]
+mod fixtures;
+pub mod formats;
+pub mod game;
 mod generator;
 pub mod generators;
+pub mod multi;
 mod placer;
 pub mod placers;
 pub mod pt;
+mod puzzle;
+pub mod rating;
+pub mod render;
+mod rules;
 mod solver;
 pub mod solvers;
+pub mod stats;
+pub mod stream;
+mod transform;
 mod unique;
 pub mod uniques;
 
+pub use bitboard::BitBoard;
 pub use board::Board;
+pub use chooser::Chooser;
+pub use constraint::Constraint;
 pub use consts::*;
 pub use error::{NumprError, NumprResult};
 pub use generator::Generator;
 pub use placer::Placer;
 pub use pt::{Pt, PtIter};
+pub use puzzle::{Puzzle, Symmetry};
+pub use rules::Rules;
 pub use solver::Solver;
+pub use transform::Transform;
 pub use unique::UniquenessChecker;
 
 // TODO: reorganize modules. For example, move placer.rs to placers/trait.rs.
@@ -0,0 +1,11 @@
+//! Puzzles made of several overlapping boards such as Samurai.
+
+mod board;
+mod generator;
+mod layout;
+mod solver;
+
+pub use board::MultiBoard;
+pub use generator::MultiGenerator;
+pub use layout::Layout;
+pub use solver::MultiSolver;
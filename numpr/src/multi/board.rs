@@ -0,0 +1,160 @@
+use super::layout::Layout;
+use crate::board::Board;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::{Pt, PtIter};
+
+/// A puzzle made of several [`Board`](../struct.Board.html)s sharing grids.
+#[derive(Clone)]
+pub struct MultiBoard {
+    layout: Layout,
+    numbers: Vec<u8>,
+}
+
+impl MultiBoard {
+    /// Returns an empty board having `layout`.
+    pub fn new(layout: Layout) -> Self {
+        let (w, h) = layout.size();
+        Self {
+            layout,
+            numbers: vec![0; w * h],
+        }
+    }
+
+    /// Returns a board made of `boards`, which must be listed in the same
+    /// order as in `layout`.
+    ///
+    /// Shared grids can be empty in some of the boards. It fails with
+    /// `NumprError::InvalidBoardCount` when the number of boards doesn't
+    /// match the layout, and with `NumprError::Conflict` when two boards have
+    /// different numbers in a shared grid.
+    pub fn from_boards(layout: Layout, boards: &[Board]) -> NumprResult<Self> {
+        if boards.len() != layout.boards() {
+            return NumprError::invalid_board_count(layout.boards(), boards.len());
+        }
+
+        let mut b = Self::new(layout);
+        for (i, board) in boards.iter().enumerate() {
+            for (pt, n) in board.iter() {
+                let n = match n {
+                    Some(n) => n,
+                    None => continue,
+                };
+                match b.get(i, pt)? {
+                    Some(m) if m != n => return NumprError::conflict(pt, m, n),
+                    _ => b.set(i, pt, n)?,
+                }
+            }
+        }
+        Ok(b)
+    }
+
+    /// Returns the layout.
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Returns the number at `pt` in the `board`-th board.
+    ///
+    /// It fails with `NumprError::BoardOutOfBounds` when the layout doesn't
+    /// have the `board`-th board.
+    pub fn get(&self, board: usize, pt: Pt) -> NumprResult<Option<u8>> {
+        Ok(match self.numbers[self.index(board, pt)?] {
+            0 => None,
+            n => Some(n),
+        })
+    }
+
+    /// Puts `n` at `pt` in the `board`-th board, which is also visible from
+    /// other boards sharing the grid. `0` clears the grid.
+    ///
+    /// It fails with `NumprError::BoardOutOfBounds` when the layout doesn't
+    /// have the `board`-th board.
+    pub fn set(&mut self, board: usize, pt: Pt, n: u8) -> NumprResult<()> {
+        if n > 9 {
+            return NumprError::invalid_value(pt, n);
+        }
+        let i = self.index(board, pt)?;
+        self.numbers[i] = n;
+        Ok(())
+    }
+
+    /// Returns the `board`-th board as a `Board`.
+    ///
+    /// It fails with `NumprError::BoardOutOfBounds` when the layout doesn't
+    /// have the `board`-th board.
+    pub fn board(&self, board: usize) -> NumprResult<Board> {
+        let mut b = Board::default();
+        for pt in PtIter::all() {
+            b.set(pt, self.numbers[self.index(board, pt)?])?;
+        }
+        Ok(b)
+    }
+
+    /// Checks if all the boards are solved.
+    pub fn validate(&self) -> NumprResult<()> {
+        for i in 0..self.layout.boards() {
+            self.board(i)?.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of filled grids on the canvas.
+    pub fn filled(&self) -> usize {
+        self.numbers.iter().filter(|&&n| n != 0).count()
+    }
+
+    /// Returns the index on the canvas of `pt` in the `board`-th board.
+    fn index(&self, board: usize, pt: Pt) -> NumprResult<usize> {
+        if board >= self.layout.boards() {
+            return NumprError::board_out_of_bounds(board, self.layout.boards());
+        }
+        Ok(self.layout.index(board, pt))
+    }
+
+    pub(crate) fn raw(&self) -> &[u8] {
+        &self.numbers
+    }
+
+    pub(crate) fn raw_set(&mut self, i: usize, n: u8) {
+        self.numbers[i] = n;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared() {
+        let mut b = MultiBoard::new(Layout::twin());
+        let corner = Pt::new(8, 8).unwrap();
+        b.set(0, corner, 5).unwrap();
+        assert_eq!(b.get(1, Pt::new(2, 2).unwrap()), Ok(Some(5)));
+        assert_eq!(b.board(1).unwrap().get(Pt::new(2, 2).unwrap()), Some(5));
+        assert_eq!(b.filled(), 1);
+        assert!(b.set(1, corner, 10).is_err());
+        assert_eq!(b.get(2, corner), Err(NumprError::BoardOutOfBounds(2, 2)));
+        assert!(b.set(2, corner, 1).is_err());
+        assert!(b.board(2).is_err());
+    }
+
+    #[test]
+    fn from_boards() {
+        let mut first = Board::default();
+        let mut second = Board::default();
+        first.set(Pt::new(7, 7).unwrap(), 3).unwrap();
+        second.set(Pt::new(1, 1).unwrap(), 3).unwrap();
+        let b = MultiBoard::from_boards(Layout::twin(), &[first, second]).unwrap();
+        assert_eq!(b.filled(), 1);
+
+        second.set(Pt::new(1, 1).unwrap(), 4).unwrap();
+        assert_eq!(
+            MultiBoard::from_boards(Layout::twin(), &[first, second]).err(),
+            Some(NumprError::Conflict(Pt::new(1, 1).unwrap(), 3, 4))
+        );
+        assert_eq!(
+            MultiBoard::from_boards(Layout::twin(), &[first]).err(),
+            Some(NumprError::InvalidBoardCount(2, 1))
+        );
+    }
+}
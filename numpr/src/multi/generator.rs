@@ -0,0 +1,73 @@
+use super::board::MultiBoard;
+use super::solver::MultiSolver;
+use crate::error::NumprResult;
+use rand::prelude::*;
+
+/// Generates a puzzle from a solved [`MultiBoard`](struct.MultiBoard.html) by
+/// removing numbers while the puzzle has a unique answer.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::multi::{Layout, MultiBoard, MultiGenerator, MultiSolver};
+///
+/// let answer = MultiSolver::new()
+///     .solve(&MultiBoard::new(Layout::twin()), true)
+///     .unwrap();
+/// let puzzle = MultiGenerator::new(100).generate(&answer).unwrap();
+/// MultiSolver::new().check(&puzzle).unwrap();
+/// ```
+pub struct MultiGenerator {
+    max_empty_grid: u32,
+}
+
+impl MultiGenerator {
+    /// Returns a generator removing at most `max_empty_grid` numbers.
+    pub fn new(max_empty_grid: u32) -> Self {
+        Self { max_empty_grid }
+    }
+
+    /// Generates a puzzle whose answer is `board`.
+    pub fn generate(mut self, board: &MultiBoard) -> NumprResult<MultiBoard> {
+        let mut targets: Vec<usize> = (0..board.raw().len())
+            .filter(|&i| board.raw()[i] != 0)
+            .collect();
+        targets.shuffle(&mut rand::thread_rng());
+
+        let mut b = board.clone();
+        for i in targets {
+            if self.max_empty_grid == 0 {
+                break;
+            }
+            let prev_state = b.raw()[i];
+            b.raw_set(i, 0);
+            if MultiSolver::new().check(&b).is_err() {
+                b.raw_set(i, prev_state);
+                continue;
+            }
+            self.max_empty_grid -= 1;
+        }
+        Ok(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::layout::Layout;
+    use super::*;
+
+    #[test]
+    fn generate() {
+        let layout = Layout::twin();
+        let answer = MultiSolver::new()
+            .solve(&MultiBoard::new(layout.clone()), true)
+            .unwrap();
+        let puzzle = MultiGenerator::new(layout.cells() as u32)
+            .generate(&answer)
+            .unwrap();
+        assert!(puzzle.filled() < layout.cells());
+
+        let b = MultiSolver::new().solve(&puzzle, false).unwrap();
+        assert_eq!(b.raw(), answer.raw());
+    }
+}
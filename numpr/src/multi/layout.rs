@@ -0,0 +1,160 @@
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::{Pt, PtIter};
+
+/// Layout of a puzzle made of several overlapping 9x9 boards.
+///
+/// Each board is placed on a canvas at its origin. Boards sharing a part of
+/// the canvas share the numbers in it. Origins must be aligned to 3x3 blocks so
+/// that overlapping regions consist of whole blocks.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::multi::Layout;
+///
+/// let l = Layout::samurai();
+/// assert_eq!(l.boards(), 5);
+/// assert_eq!(l.size(), (21, 21));
+/// assert_eq!(l.cells(), 369);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    origins: Vec<(usize, usize)>,
+    width: usize,
+    height: usize,
+}
+
+impl Layout {
+    /// Returns a layout having boards at `origins`.
+    ///
+    /// It fails when there's no board, an origin isn't aligned to blocks, or
+    /// two boards are at the same origin.
+    pub fn new(origins: &[(usize, usize)]) -> NumprResult<Self> {
+        if origins.is_empty() {
+            return NumprError::invalid_constraint("a layout needs at least one board");
+        }
+        for (i, &(x, y)) in origins.iter().enumerate() {
+            if x % BLOCK_WIDTH != 0 || y % BLOCK_HEIGHT != 0 {
+                return NumprError::invalid_constraint(format!(
+                    "origin ({}, {}) isn't aligned to blocks",
+                    x, y
+                ));
+            }
+            if origins[..i].contains(&(x, y)) {
+                return NumprError::invalid_constraint(format!(
+                    "two boards are placed at ({}, {})",
+                    x, y
+                ));
+            }
+        }
+        Ok(Self {
+            origins: origins.to_vec(),
+            width: origins.iter().map(|o| o.0).max().unwrap() + WIDTH,
+            height: origins.iter().map(|o| o.1).max().unwrap() + HEIGHT,
+        })
+    }
+
+    /// Returns the layout of Samurai: four boards sharing their corner blocks
+    /// with a board in the center.
+    pub fn samurai() -> Self {
+        Self::new(&[(0, 0), (12, 0), (6, 6), (0, 12), (12, 12)]).unwrap()
+    }
+
+    /// Returns a layout of two boards sharing a corner block.
+    pub fn twin() -> Self {
+        Self::new(&[(0, 0), (6, 6)]).unwrap()
+    }
+
+    /// Returns the layout of Butterfly: four boards overlapping on a 12x12
+    /// canvas.
+    pub fn butterfly() -> Self {
+        Self::new(&[(0, 0), (3, 0), (0, 3), (3, 3)]).unwrap()
+    }
+
+    /// Returns the number of boards.
+    pub fn boards(&self) -> usize {
+        self.origins.len()
+    }
+
+    /// Returns the origin of the `board`-th board.
+    pub fn origin(&self, board: usize) -> (usize, usize) {
+        self.origins[board]
+    }
+
+    /// Returns the width and the height of the canvas.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns the number of grids on the canvas covered by any board.
+    pub fn cells(&self) -> usize {
+        (0..self.width * self.height)
+            .filter(|&i| self.covered(i % self.width, i / self.width))
+            .count()
+    }
+
+    /// Returns `true` when `(x, y)` on the canvas is covered by any board.
+    pub fn covered(&self, x: usize, y: usize) -> bool {
+        self.origins
+            .iter()
+            .any(|&(ox, oy)| x >= ox && x < ox + WIDTH && y >= oy && y < oy + HEIGHT)
+    }
+
+    /// Returns the index on the canvas of `pt` in the `board`-th board.
+    pub fn index(&self, board: usize, pt: Pt) -> usize {
+        let (ox, oy) = self.origins[board];
+        (oy + pt.y()) * self.width + ox + pt.x()
+    }
+
+    /// Returns units, which are rows, columns, and blocks of all boards, as
+    /// indices on the canvas.
+    pub(crate) fn units(&self) -> Vec<[usize; 9]> {
+        let mut units = vec![];
+        for b in 0..self.boards() {
+            let unit = |it: &mut dyn Iterator<Item = Pt>| {
+                let mut u = [0; 9];
+                for (k, p) in it.enumerate() {
+                    u[k] = self.index(b, p);
+                }
+                u
+            };
+            for i in 0..9 {
+                let block = Pt::new(i % 3 * BLOCK_WIDTH, i / 3 * BLOCK_HEIGHT).unwrap();
+                units.push(unit(&mut PtIter::row(Pt::new(0, i).unwrap())));
+                units.push(unit(&mut PtIter::col(Pt::new(i, 0).unwrap())));
+                units.push(unit(&mut PtIter::block(block)));
+            }
+        }
+        units.sort_unstable();
+        units.dedup();
+        units
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert!(Layout::new(&[]).is_err());
+        assert!(Layout::new(&[(0, 0), (4, 0)]).is_err());
+        assert!(Layout::new(&[(3, 3), (3, 3)]).is_err());
+
+        let l = Layout::twin();
+        assert_eq!(l.size(), (15, 15));
+        assert_eq!(l.cells(), 81 * 2 - 9);
+        assert!(l.covered(14, 14));
+        assert!(!l.covered(14, 0));
+        assert_eq!(l.index(1, Pt::new(0, 0).unwrap()), 6 * 15 + 6);
+        assert_eq!(Layout::butterfly().cells(), 144);
+    }
+
+    #[test]
+    fn units() {
+        // Shared blocks are counted only once.
+        assert_eq!(Layout::twin().units().len(), 27 * 2 - 1);
+        assert_eq!(Layout::samurai().units().len(), 27 * 5 - 4);
+    }
+}
@@ -0,0 +1,230 @@
+use super::board::MultiBoard;
+use super::layout::Layout;
+use crate::constraints::common::*;
+use crate::error::{NumprError, NumprResult};
+use rand::prelude::*;
+
+/// Solves all the boards of a [`MultiBoard`](struct.MultiBoard.html) jointly.
+///
+/// It propagates naked and hidden singles across the boards and branches on
+/// the grid having the fewest candidates.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::multi::{Layout, MultiBoard, MultiSolver};
+///
+/// let b = MultiSolver::new()
+///     .solve(&MultiBoard::new(Layout::samurai()), true)
+///     .unwrap();
+/// b.validate().unwrap();
+/// ```
+#[derive(Default)]
+pub struct MultiSolver {}
+
+struct Geometry {
+    cells: Vec<usize>,
+    units: Vec<[usize; 9]>,
+    peers: Vec<Vec<usize>>,
+}
+
+impl Geometry {
+    fn new(layout: &Layout) -> Self {
+        let (w, h) = layout.size();
+        let cells: Vec<usize> = (0..w * h)
+            .filter(|&i| layout.covered(i % w, i / w))
+            .collect();
+        let units = layout.units();
+        let mut peers = vec![vec![]; w * h];
+        for u in &units {
+            for &i in u {
+                peers[i].extend(u.iter().filter(|&&j| j != i));
+            }
+        }
+        for p in &mut peers {
+            p.sort_unstable();
+            p.dedup();
+        }
+        Self {
+            cells,
+            units,
+            peers,
+        }
+    }
+}
+
+impl MultiSolver {
+    /// Returns a new `MultiSolver`.
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Solves the given puzzle. Passing `true` to `random` generates a random
+    /// answer when the puzzle has multiple answers.
+    pub fn solve(self, board: &MultiBoard, random: bool) -> NumprResult<MultiBoard> {
+        let mut found = self.search_all(board, random, 1)?;
+        match found.pop() {
+            Some(b) => Ok(b),
+            None => NumprError::unsolvable(),
+        }
+    }
+
+    /// Returns the number of answers, counting up to `limit`.
+    pub fn count(self, board: &MultiBoard, limit: usize) -> NumprResult<usize> {
+        Ok(self.search_all(board, false, limit)?.len())
+    }
+
+    /// Checks if the given puzzle has exactly one answer.
+    ///
+    /// It returns `NumprError::MultipleSolutions` or `NumprError::Unsolvable`
+    /// otherwise.
+    pub fn check(self, board: &MultiBoard) -> NumprResult<()> {
+        match self.count(board, 2)? {
+            0 => NumprError::unsolvable(),
+            1 => Ok(()),
+            _ => NumprError::multiple_solutions(),
+        }
+    }
+
+    fn search_all(
+        &self,
+        board: &MultiBoard,
+        random: bool,
+        limit: usize,
+    ) -> NumprResult<Vec<MultiBoard>> {
+        let g = Geometry::new(board.layout());
+        let mut masks = vec![0; board.raw().len()];
+        for &i in &g.cells {
+            masks[i] = match board.raw()[i] {
+                0 => ALL,
+                n => 1 << n,
+            };
+        }
+
+        let mut found = vec![];
+        self.search(&g, masks, random, limit, &mut found);
+        Ok(found
+            .into_iter()
+            .map(|masks| {
+                let mut b = board.clone();
+                for &i in &g.cells {
+                    b.raw_set(i, min(masks[i]));
+                }
+                b
+            })
+            .collect())
+    }
+
+    fn search(
+        &self,
+        g: &Geometry,
+        mut masks: Vec<u16>,
+        random: bool,
+        limit: usize,
+        found: &mut Vec<Vec<u16>>,
+    ) {
+        if self.propagate(g, &mut masks).is_err() {
+            return;
+        }
+
+        let next = g
+            .cells
+            .iter()
+            .cloned()
+            .filter(|&i| masks[i].count_ones() > 1)
+            .min_by_key(|&i| masks[i].count_ones());
+        let i = match next {
+            Some(i) => i,
+            None => {
+                found.push(masks);
+                return;
+            }
+        };
+
+        let mut candidates: Vec<u8> = digits(masks[i]).collect();
+        if random {
+            candidates.shuffle(&mut rand::thread_rng());
+        }
+        for n in candidates {
+            if found.len() >= limit {
+                return;
+            }
+            let mut next = masks.clone();
+            next[i] = 1 << n;
+            self.search(g, next, random, limit, found);
+        }
+    }
+
+    fn propagate(&self, g: &Geometry, masks: &mut [u16]) -> NumprResult<()> {
+        loop {
+            let mut changed = false;
+
+            // Naked singles
+            for &i in &g.cells {
+                let m = masks[i];
+                if m.count_ones() != 1 {
+                    continue;
+                }
+                for &p in &g.peers[i] {
+                    changed |= restrict(masks, p, !m)?;
+                }
+            }
+
+            // Hidden singles
+            for u in &g.units {
+                for n in 1..=9 {
+                    let mut it = u.iter().filter(|&&i| masks[i] & (1 << n) != 0);
+                    match (it.next(), it.next()) {
+                        (None, _) => return NumprError::unsolvable(),
+                        (Some(&i), None) if masks[i] != 1 << n => {
+                            masks[i] = 1 << n;
+                            changed = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pt::Pt;
+
+    #[test]
+    fn solve() {
+        for layout in [Layout::twin(), Layout::butterfly(), Layout::samurai()].iter() {
+            let b = MultiSolver::new()
+                .solve(&MultiBoard::new(layout.clone()), true)
+                .unwrap();
+            b.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn unsolvable() {
+        let mut b = MultiBoard::new(Layout::twin());
+        b.set(0, Pt::new(6, 6).unwrap(), 1).unwrap();
+        b.set(1, Pt::new(8, 0).unwrap(), 1).unwrap();
+        assert_eq!(MultiSolver::new().count(&b, 1), Ok(0));
+        assert!(MultiSolver::new().check(&b).is_err());
+    }
+
+    #[test]
+    fn check() {
+        let b = MultiSolver::new()
+            .solve(&MultiBoard::new(Layout::samurai()), true)
+            .unwrap();
+        MultiSolver::new().check(&b).unwrap();
+        assert_eq!(
+            MultiSolver::new().check(&MultiBoard::new(Layout::twin())),
+            NumprError::multiple_solutions()
+        );
+    }
+}
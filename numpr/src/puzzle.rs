@@ -0,0 +1,458 @@
+use crate::board::Board;
+use crate::budget::Budget;
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::{Pt, PtIter};
+use crate::rating::{self, Difficulty};
+use crate::rules::Rules;
+use crate::solver::Solver;
+use crate::solvers::{BitParallelSolver, VariantSolver};
+use crate::stream::GeneratedPuzzle;
+use crate::transform::Transform;
+use crate::unique::UniquenessChecker;
+use crate::uniques::NaiveUniquenessChecker;
+use std::fmt;
+use std::str::FromStr;
+
+/// Symmetry of positions of givens.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// The same after rotating by 90 degrees.
+    Rotational90,
+    /// The same after rotating by 180 degrees.
+    Rotational180,
+    /// The same after flipping left and right.
+    Horizontal,
+    /// The same after flipping top and bottom.
+    Vertical,
+    /// The same after flipping along the diagonal from the top left corner.
+    Diagonal,
+    /// The same after flipping along the diagonal from the top right corner.
+    AntiDiagonal,
+    /// No symmetry.
+    None,
+}
+
+/// Maps `(x, y)` to the symmetric position given the largest `x` and `y`.
+type Mapping = fn(usize, usize, usize, usize) -> (usize, usize);
+
+impl Symmetry {
+    const ALL: [Symmetry; 6] = [
+        Symmetry::Rotational90,
+        Symmetry::Rotational180,
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Diagonal,
+        Symmetry::AntiDiagonal,
+    ];
+
+    /// Returns the strongest symmetry of positions of filled grids in `board`.
+    pub fn detect(board: &Board) -> Self {
+        for &s in &Self::ALL {
+            let symmetric =
+                PtIter::all().all(|p| board.get(p).is_some() == board.get(s.map(p)).is_some());
+            if symmetric {
+                return s;
+            }
+        }
+        Symmetry::None
+    }
+
+    /// Returns positions of `pt` and its symmetric ones, which are filled or
+    /// emptied together to keep the symmetry.
+    pub(crate) fn orbit(self, pt: Pt) -> Vec<Pt> {
+        let mut orbit = vec![pt];
+        let mut p = self.map(pt);
+        while !orbit.contains(&p) {
+            orbit.push(p);
+            p = self.map(p);
+        }
+        orbit
+    }
+
+    fn map(self, pt: Pt) -> Pt {
+        let (w, h) = (WIDTH - 1, HEIGHT - 1);
+        let f: Mapping = match self {
+            Symmetry::Rotational90 => |x, y, w, _| (w - y, x),
+            Symmetry::Rotational180 => |x, y, w, h| (w - x, h - y),
+            Symmetry::Horizontal => |x, y, w, _| (w - x, y),
+            Symmetry::Vertical => |x, y, _, h| (x, h - y),
+            Symmetry::Diagonal => |x, y, _, _| (y, x),
+            Symmetry::AntiDiagonal => |x, y, w, h| (h - y, w - x),
+            Symmetry::None => return pt,
+        };
+        let (x, y) = f(pt.x(), pt.y(), w, h);
+        Pt::new(x, y).unwrap()
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = NumprError;
+
+    /// Parses a name in snake case, such as `rotational180` or `none`.
+    fn from_str(s: &str) -> NumprResult<Self> {
+        Self::ALL
+            .iter()
+            .chain(&[Symmetry::None])
+            .find(|sym| sym.to_string() == s)
+            .copied()
+            .map_or_else(
+                || NumprError::invalid_format(format!("unknown symmetry: {}", s)),
+                Ok,
+            )
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Symmetry::Rotational90 => "rotational90",
+            Symmetry::Rotational180 => "rotational180",
+            Symmetry::Horizontal => "horizontal",
+            Symmetry::Vertical => "vertical",
+            Symmetry::Diagonal => "diagonal",
+            Symmetry::AntiDiagonal => "anti_diagonal",
+            Symmetry::None => "none",
+        })
+    }
+}
+
+/// A puzzle with its answer and metadata.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::{solvers, Board, Pt, Puzzle, Solver};
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let answer = solvers::HeuristicSolver::new().solve(&Board::default(), true)?;
+/// let mut givens = answer;
+/// givens.set(Pt::new(4, 4)?, 0)?;
+///
+/// let p = Puzzle::new(givens)?;
+/// assert!(!p.is_given(Pt::new(4, 4)?));
+/// assert!(p.check_entry(Pt::new(4, 4)?, answer.get(Pt::new(4, 4)?).unwrap()));
+/// assert!(p.is_solved(&answer));
+/// assert!(!p.is_solved(&givens));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Puzzle {
+    givens: Board,
+    solution: Board,
+    rules: Rules,
+    difficulty: Option<Difficulty>,
+    seed: Option<u64>,
+}
+
+impl Puzzle {
+    /// Returns a classic puzzle having `givens`.
+    ///
+    /// It fails with `NumprError::MultipleSolutions` or
+    /// `NumprError::Unsolvable` when the puzzle doesn't have exactly one
+    /// answer.
+    pub fn new(givens: Board) -> NumprResult<Self> {
+        Self::with_rules(givens, Rules::new())
+    }
+
+    /// Returns a puzzle having `givens` and variant `rules`.
+    ///
+    /// It fails with `NumprError::MultipleSolutions` or
+    /// `NumprError::Unsolvable` when the puzzle doesn't have exactly one
+    /// answer. Difficulty is only rated for classic puzzles.
+    pub fn with_rules(givens: Board, rules: Rules) -> NumprResult<Self> {
+        Self::build(givens, rules, None)
+    }
+
+    /// Returns a puzzle like `with_rules`, but fails with
+    /// `NumprError::BudgetExceeded` when the searches checking the answer run
+    /// out of `budget`.
+    pub fn with_budget(givens: Board, rules: Rules, budget: Budget) -> NumprResult<Self> {
+        Self::build(givens, rules, Some(budget))
+    }
+
+    fn build(givens: Board, rules: Rules, budget: Option<Budget>) -> NumprResult<Self> {
+        let (solution, difficulty) = if rules.is_classic() {
+            let solver = match &budget {
+                Some(b) => BitParallelSolver::new().with_budget(b.clone()),
+                None => BitParallelSolver::new(),
+            };
+            match solver.count(&givens, 2)? {
+                0 => return NumprError::unsolvable(),
+                1 => {}
+                _ => return NumprError::multiple_solutions(),
+            }
+            let difficulty = rating::grade(&givens)?;
+            (solver.solve(&givens, false)?, Some(difficulty))
+        } else {
+            let factory = || match &budget {
+                Some(b) => VariantSolver::new(rules.clone()).with_budget(b.clone()),
+                None => VariantSolver::new(rules.clone()),
+            };
+            let checker = match &budget {
+                Some(b) => NaiveUniquenessChecker::new().with_budget(b.clone()),
+                None => NaiveUniquenessChecker::new(),
+            };
+            checker.check(&givens, factory)?;
+            (factory().solve(&givens, false)?, None)
+        };
+        Ok(Self {
+            givens,
+            solution,
+            rules,
+            difficulty,
+            seed: None,
+        })
+    }
+
+    /// Returns a puzzle having the known `solution` without searching it.
+    ///
+    /// It fails with `NumprError::WrongAnswer` when `solution` violates
+    /// `rules` or doesn't match `givens`. Uniqueness isn't checked.
+    pub fn from_parts(givens: Board, solution: Board, rules: Rules) -> NumprResult<Self> {
+        rules.validate(&solution)?;
+        for (pt, n) in givens.iter() {
+            if n.is_some() && n != solution.get(pt) {
+                return NumprError::wrong_answer();
+            }
+        }
+        let difficulty = if rules.is_classic() {
+            rating::rate(&givens).ok()
+        } else {
+            None
+        };
+        Ok(Self {
+            givens,
+            solution,
+            rules,
+            difficulty,
+            seed: None,
+        })
+    }
+
+    /// Records the seed used to generate the puzzle.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns the givens.
+    pub fn givens(&self) -> &Board {
+        &self.givens
+    }
+
+    /// Returns the answer.
+    pub fn solution(&self) -> &Board {
+        &self.solution
+    }
+
+    /// Returns the variant rules.
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Returns the difficulty. It's `None` for variant puzzles.
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.difficulty
+    }
+
+    /// Returns the symmetry of positions of the givens.
+    pub fn symmetry(&self) -> Symmetry {
+        Symmetry::detect(&self.givens)
+    }
+
+    /// Returns the seed used to generate the puzzle, if recorded.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns the number of givens.
+    pub fn clues(&self) -> usize {
+        self.givens.iter().filter(|(_, n)| n.is_some()).count()
+    }
+
+    /// Returns `true` when the grid at `pt` is given.
+    pub fn is_given(&self, pt: Pt) -> bool {
+        self.givens.get(pt).is_some()
+    }
+
+    /// Returns `true` when `n` is the answer at `pt`.
+    pub fn check_entry(&self, pt: Pt, n: u8) -> bool {
+        self.solution.get(pt) == Some(n)
+    }
+
+    /// Returns `true` when `board` is the answer.
+    pub fn is_solved(&self, board: &Board) -> bool {
+        board.iter().eq(self.solution.iter())
+    }
+
+    /// Returns the puzzle disguised by `t`. Uniqueness and difficulty are kept
+    /// without checking them again. The seed isn't kept because it generates
+    /// the original puzzle.
+    ///
+    /// It fails with `NumprError::InvalidTransform` for variant puzzles
+    /// because constraints can't be transformed.
+    pub fn transform(&self, t: &Transform) -> NumprResult<Self> {
+        if !self.rules.is_classic() {
+            return NumprError::invalid_transform("variant puzzles can't be transformed");
+        }
+        Ok(Self {
+            givens: t.apply(&self.givens),
+            solution: t.apply(&self.solution),
+            rules: self.rules.clone(),
+            difficulty: self.difficulty,
+            seed: None,
+        })
+    }
+
+    /// Returns an identifier of the puzzle, which is 16 hexadecimal digits
+    /// computed from the givens. It's stable across versions and platforms.
+    ///
+    /// Variant rules aren't taken into account, so variant puzzles having the
+    /// same givens have the same identifier.
+    pub fn id(&self) -> String {
+        // 64-bit FNV-1a
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+        for (_, n) in self.givens.iter() {
+            h ^= n.unwrap_or(0) as u64;
+            h = h.wrapping_mul(0x100_0000_01b3);
+        }
+        format!("{:016x}", h)
+    }
+}
+
+impl From<GeneratedPuzzle> for Puzzle {
+    fn from(p: GeneratedPuzzle) -> Self {
+        Self {
+            givens: p.puzzle,
+            solution: p.solution,
+            rules: Rules::new(),
+            difficulty: Some(p.difficulty),
+            seed: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::Thermometer;
+    use crate::solvers::HeuristicSolver;
+
+    fn answer() -> Board {
+        HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap()
+    }
+
+    #[test]
+    fn classic() {
+        let answer = answer();
+        let mut givens = answer;
+        for x in 0..9 {
+            givens.set(Pt::new(x, x).unwrap(), 0).unwrap();
+        }
+        let p = Puzzle::new(givens).unwrap().with_seed(42);
+        assert!(p.is_solved(&answer));
+        assert_eq!(p.clues(), SIZE - 9);
+        assert_eq!(p.difficulty(), Some(Difficulty::Easy));
+        // The main diagonal is also symmetric under rotation.
+        assert_eq!(p.symmetry(), Symmetry::Rotational180);
+        assert_eq!(p.seed(), Some(42));
+        let pt = Pt::new(0, 0).unwrap();
+        assert!(!p.is_given(pt));
+        let n = answer.get(pt).unwrap();
+        assert!(p.check_entry(pt, n));
+        assert!(!p.check_entry(pt, n % 9 + 1));
+
+        assert!(Puzzle::new(Board::default()).is_err());
+        assert!(matches!(
+            Puzzle::with_budget(Board::default(), Rules::new(), Budget::new().max_nodes(0)),
+            Err(NumprError::BudgetExceeded)
+        ));
+        let q = Puzzle::with_budget(givens, Rules::new(), Budget::new().max_nodes(1000)).unwrap();
+        assert_eq!(q.id(), p.id());
+    }
+
+    #[test]
+    fn variant() {
+        let cells: Vec<Pt> = (0..9).map(|x| Pt::new(x, 0).unwrap()).collect();
+        let rules = Rules::new().with(Thermometer::new(cells).unwrap());
+        let answer = VariantSolver::new(rules.clone())
+            .solve(&Board::default(), true)
+            .unwrap();
+        let mut givens = answer;
+        for x in 0..9 {
+            givens.set(Pt::new(x, 0).unwrap(), 0).unwrap();
+        }
+        assert!(Puzzle::new(givens).is_ok());
+        let p = Puzzle::with_rules(givens, rules.clone()).unwrap();
+        assert!(p.is_solved(&answer));
+        assert_eq!(p.difficulty(), None);
+
+        let p = Puzzle::from_parts(givens, answer, rules.clone()).unwrap();
+        assert!(p.is_solved(&answer));
+        assert!(Puzzle::from_parts(givens, givens, rules).is_err());
+    }
+
+    #[test]
+    fn symmetry() {
+        let mut b = Board::default();
+        assert_eq!(Symmetry::detect(&b), Symmetry::Rotational90);
+        b.set(Pt::new(0, 0).unwrap(), 1).unwrap();
+        b.set(Pt::new(8, 8).unwrap(), 1).unwrap();
+        assert_eq!(Symmetry::detect(&b), Symmetry::Rotational180);
+        b.set(Pt::new(8, 8).unwrap(), 0).unwrap();
+        assert_eq!(Symmetry::detect(&b), Symmetry::Diagonal);
+        b.set(Pt::new(1, 0).unwrap(), 1).unwrap();
+        assert_eq!(Symmetry::detect(&b), Symmetry::None);
+        b.set(Pt::new(7, 0).unwrap(), 1).unwrap();
+        b.set(Pt::new(8, 0).unwrap(), 1).unwrap();
+        assert_eq!(Symmetry::detect(&b), Symmetry::Horizontal);
+
+        let pt = Pt::new(1, 0).unwrap();
+        assert_eq!(Symmetry::Rotational90.orbit(pt).len(), 4);
+        assert_eq!(Symmetry::Diagonal.orbit(Pt::new(3, 3).unwrap()).len(), 1);
+        assert_eq!(Symmetry::None.orbit(pt), vec![pt]);
+        for s in "rotational90 anti_diagonal none".split(' ') {
+            assert_eq!(s.parse::<Symmetry>().unwrap().to_string(), s);
+        }
+        assert!("rotational".parse::<Symmetry>().is_err());
+    }
+
+    #[test]
+    fn transform() {
+        let answer = answer();
+        let mut givens = answer;
+        givens.set(Pt::new(0, 0).unwrap(), 0).unwrap();
+        let p = Puzzle::new(givens).unwrap().with_seed(1);
+        let t = Transform::rotate(1);
+        let q = p.transform(&t).unwrap();
+        assert_eq!(q.seed(), None);
+        assert!(!q.is_given(Pt::new(8, 0).unwrap()));
+        assert!(q.is_solved(&t.apply(&answer)));
+        assert_eq!(q.difficulty(), p.difficulty());
+        assert_ne!(q.id(), p.id());
+
+        let cells: Vec<Pt> = (0..9).map(|x| Pt::new(x, 0).unwrap()).collect();
+        let rules = Rules::new().with(Thermometer::new(cells).unwrap());
+        let v = Puzzle { rules, ..p };
+        assert!(matches!(
+            v.transform(&t),
+            Err(NumprError::InvalidTransform(_))
+        ));
+    }
+
+    #[test]
+    fn id() {
+        let mut givens = answer();
+        givens.set(Pt::new(0, 0).unwrap(), 0).unwrap();
+        let a = Puzzle::new(givens).unwrap();
+        assert_eq!(a.id().len(), 16);
+        assert_eq!(a.id(), Puzzle::new(givens).unwrap().id());
+        givens.set(Pt::new(1, 0).unwrap(), 0).unwrap();
+        assert_ne!(a.id(), Puzzle::new(givens).unwrap().id());
+    }
+}
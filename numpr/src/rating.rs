@@ -0,0 +1,172 @@
+//! Rating difficulty of puzzles.
+
+use crate::bitboard::BitBoard;
+use crate::board::Board;
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::solvers::{BitParallelSolver, UNITS};
+use std::fmt;
+use std::str::FromStr;
+
+/// Difficulty of a puzzle, ordered from the easiest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Solvable only by filling grids having a single candidate.
+    Easy,
+    /// Solvable by also placing numbers having a single place in a row, a
+    /// column, or a block.
+    Medium,
+    /// Requires other techniques or guesses.
+    Hard,
+}
+
+impl FromStr for Difficulty {
+    type Err = NumprError;
+
+    /// Parses a name in lowercase, such as `easy`.
+    fn from_str(s: &str) -> NumprResult<Self> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => NumprError::invalid_format(format!("unknown difficulty: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        })
+    }
+}
+
+/// Rates the difficulty of the given puzzle.
+///
+/// It fails with `NumprError::MultipleSolutions` or `NumprError::Unsolvable`
+/// when the puzzle doesn't have exactly one answer.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::rating::{self, Difficulty};
+/// use numpr::{solvers, Board, Pt, Solver};
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let mut b = solvers::HeuristicSolver::new().solve(&Board::default(), true)?;
+/// b.set(Pt::new(4, 4)?, 0)?;
+/// assert_eq!(rating::rate(&b)?, Difficulty::Easy);
+/// # Ok(())
+/// # }
+/// ```
+pub fn rate(board: &Board) -> NumprResult<Difficulty> {
+    match BitParallelSolver::new().count(board, 2)? {
+        0 => return NumprError::unsolvable(),
+        1 => {}
+        _ => return NumprError::multiple_solutions(),
+    }
+    grade(board)
+}
+
+/// Rates the difficulty of `board`, which must have a unique answer, by the
+/// techniques needed to solve it.
+pub(crate) fn grade(board: &Board) -> NumprResult<Difficulty> {
+    let mut b = BitBoard::new(board)?;
+    let mut difficulty = Difficulty::Easy;
+    loop {
+        if naked_singles(&mut b) {
+            continue;
+        }
+        if (0..SIZE).all(|i| b.raw_get(i) != 0) {
+            return Ok(difficulty);
+        }
+        if hidden_singles(&mut b) {
+            difficulty = Difficulty::Medium;
+            continue;
+        }
+        return Ok(Difficulty::Hard);
+    }
+}
+
+/// Fills grids having a single candidate. It returns `true` when any grid is
+/// filled.
+fn naked_singles(b: &mut BitBoard) -> bool {
+    let mut progress = false;
+    for i in 0..SIZE {
+        if b.raw_get(i) != 0 {
+            continue;
+        }
+        let m = b.raw_candidates(i);
+        if m.count_ones() == 1 {
+            b.place(i, m.trailing_zeros() as u8);
+            progress = true;
+        }
+    }
+    progress
+}
+
+/// Places numbers having a single place in a unit. It returns `true` when any
+/// number is placed.
+fn hidden_singles(b: &mut BitBoard) -> bool {
+    let mut progress = false;
+    for unit in &UNITS {
+        for n in 1..=9 {
+            let mut places = unit
+                .iter()
+                .map(|&i| i as usize)
+                .filter(|&i| b.raw_get(i) == 0 && b.raw_candidates(i) & 1 << n != 0);
+            if let (Some(i), None) = (places.next(), places.next()) {
+                b.place(i, n);
+                progress = true;
+            }
+        }
+    }
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, HARD, MEDIUM};
+    use crate::pt::Pt;
+    use crate::solvers::HeuristicSolver;
+    use crate::Solver;
+
+    #[test]
+    fn rate() {
+        let mut b = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        for x in 0..9 {
+            b.set(Pt::new(x, x).unwrap(), 0).unwrap();
+        }
+        assert_eq!(super::rate(&b).unwrap(), Difficulty::Easy);
+        assert_eq!(
+            super::rate(&fixtures::board(MEDIUM)).unwrap(),
+            Difficulty::Medium
+        );
+        assert_eq!(
+            super::rate(&fixtures::board(HARD)).unwrap(),
+            Difficulty::Hard
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("medium".parse::<Difficulty>().unwrap(), Difficulty::Medium);
+        assert_eq!(Difficulty::Hard.to_string(), "hard");
+        assert!("Easy".parse::<Difficulty>().is_err());
+    }
+
+    #[test]
+    fn not_unique() {
+        assert!(super::rate(&Board::default()).is_err());
+        let mut b = fixtures::board(HARD);
+        b.set(Pt::new(8, 0).unwrap(), 8).unwrap();
+        assert!(super::rate(&b).is_err());
+    }
+}
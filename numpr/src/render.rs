@@ -0,0 +1,675 @@
+//! Rendering boards, puzzles, and games.
+//!
+//! A [`Scene`](struct.Scene.html) is a snapshot of what to draw, and a
+//! [`Renderer`](struct.Renderer.html) draws it onto a
+//! [`Canvas`](trait.Canvas.html) with its style. Built-in variant
+//! constraints in the rules are drawn as well. The output only depends on
+//! its input, so it can be compared with snapshots.
+//!
+//! Scenes are written to SVG by [`SvgCanvas`](struct.SvgCanvas.html) and to
+//! PDF by [`PdfDocument`](struct.PdfDocument.html), which also makes printable
+//! booklets with [`Booklet`](struct.Booklet.html).
+//!
+//! # Examples
+//!
+//! ```
+//! use numpr::render::{Renderer, Scene};
+//! use numpr::{solvers, Board, Pt, Puzzle, Solver};
+//!
+//! # use numpr::NumprResult;
+//! # fn main() -> NumprResult<()> {
+//! let answer = solvers::HeuristicSolver::new().solve(&Board::default(), true)?;
+//! let mut givens = answer;
+//! givens.set(Pt::new(4, 4)?, 0)?;
+//! let puzzle = Puzzle::new(givens)?;
+//!
+//! let renderer = Renderer::new().cell_size(40.).highlight(Pt::new(4, 4)?);
+//! let svg = renderer.svg(&Scene::from(&puzzle));
+//! assert!(svg.starts_with("<svg "));
+//! assert_eq!(svg, renderer.svg(&Scene::from(&puzzle)));
+//! assert_eq!(svg.matches("<text ").count(), 80);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::board::Board;
+use crate::constraints::{
+    Arrow, Diagonal, GermanWhispers, Inequality, Kropki, LittleKiller, Palindrome, Renban,
+    Sandwich, Side, Skyscraper, Thermometer, XV,
+};
+use crate::consts::*;
+use crate::game::{Game, Mark};
+use crate::pt::{Pt, PtIter};
+use crate::puzzle::Puzzle;
+use crate::rules::Rules;
+use std::fmt;
+
+mod pdf;
+mod svg;
+
+pub use pdf::{Booklet, PageSize, PdfCanvas, PdfDocument};
+pub use svg::SvgCanvas;
+
+/// An RGB color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const BLACK: Color = Color(0, 0, 0);
+    pub const WHITE: Color = Color(255, 255, 255);
+}
+
+impl fmt::Display for Color {
+    /// Formats as `#rrggbb`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Text drawn on a canvas.
+pub struct Text<'a> {
+    /// The horizontal center.
+    pub x: f64,
+    /// The vertical center.
+    pub y: f64,
+    pub size: f64,
+    pub bold: bool,
+    pub color: Color,
+    pub text: &'a str,
+}
+
+/// A trait for a surface drawing shapes. Coordinates grow rightward and
+/// downward.
+pub trait Canvas {
+    /// Fills a rectangle.
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: Color);
+
+    /// Draws lines connecting `points` with round joints.
+    fn line(&mut self, points: &[(f64, f64)], width: f64, color: Color);
+
+    /// Draws a circle filled with `fill` and outlined with `stroke` having
+    /// the color and the width.
+    fn circle(&mut self, x: f64, y: f64, r: f64, fill: Option<Color>, stroke: Option<(Color, f64)>);
+
+    /// Draws text.
+    fn text(&mut self, text: &Text);
+}
+
+/// What is drawn in a grid.
+#[derive(Copy, Clone, Default)]
+struct Cell {
+    value: u8,
+    given: bool,
+    corner: u16,
+    center: u16,
+    color: u8,
+}
+
+/// A snapshot of a board, a puzzle, or a game to draw.
+#[derive(Clone)]
+pub struct Scene {
+    cells: [Cell; SIZE],
+    rules: Rules,
+}
+
+impl Scene {
+    /// Returns a scene of `puzzle` filled with the solution, where numbers
+    /// not given are drawn as entered ones.
+    pub fn solution(puzzle: &Puzzle) -> Self {
+        let mut s = Self::from(puzzle);
+        for (pt, n) in puzzle.solution().iter() {
+            s.cells[pt.index()].value = n.unwrap_or(0);
+        }
+        s
+    }
+
+    /// Returns the scene with variant `rules`.
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    fn cell(&self, pt: Pt) -> &Cell {
+        &self.cells[pt.index()]
+    }
+}
+
+impl From<&Board> for Scene {
+    /// Draws all the numbers of `board` as givens.
+    fn from(board: &Board) -> Self {
+        let mut cells = [Cell::default(); SIZE];
+        for (pt, n) in board.iter() {
+            cells[pt.index()] = Cell {
+                value: n.unwrap_or(0),
+                given: n.is_some(),
+                ..Cell::default()
+            };
+        }
+        Self {
+            cells,
+            rules: Rules::new(),
+        }
+    }
+}
+
+impl From<&Puzzle> for Scene {
+    /// Draws the givens of `puzzle` with its rules.
+    fn from(puzzle: &Puzzle) -> Self {
+        Self::from(puzzle.givens()).with_rules(puzzle.rules().clone())
+    }
+}
+
+impl From<&Game> for Scene {
+    /// Draws numbers, pencil marks, and colors of `game`.
+    fn from(game: &Game) -> Self {
+        let mut s = Self::from(game.puzzle());
+        for pt in PtIter::all() {
+            let masks = |m| game.marks(pt, m).fold(0, |a, n| a | 1 << n);
+            let c = &mut s.cells[pt.index()];
+            c.value = game.get(pt).unwrap_or(0);
+            c.corner = masks(Mark::Corner);
+            c.center = masks(Mark::Center);
+            c.color = game.color(pt);
+        }
+        s
+    }
+}
+
+/// A renderer of scenes with its style.
+#[derive(Clone)]
+pub struct Renderer {
+    cell: f64,
+    font_family: String,
+    given: Color,
+    entry: Color,
+    mark: Color,
+    highlight: Color,
+    palette: Vec<Color>,
+    marks: bool,
+    highlights: Vec<Pt>,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self {
+            cell: 48.,
+            font_family: "sans-serif".to_string(),
+            given: Color::BLACK,
+            entry: Color(0x1e, 0x5a, 0xc8),
+            mark: Color(0x50, 0x50, 0x50),
+            highlight: Color(0xff, 0xf0, 0x8c),
+            palette: vec![
+                Color(0xff, 0xc8, 0xc8),
+                Color(0xff, 0xe0, 0xb4),
+                Color(0xff, 0xff, 0xb4),
+                Color(0xc8, 0xf0, 0xc8),
+                Color(0xb4, 0xe6, 0xff),
+                Color(0xc8, 0xc8, 0xff),
+                Color(0xe6, 0xc8, 0xff),
+                Color(0xdc, 0xdc, 0xdc),
+                Color(0xff, 0xc8, 0xf0),
+            ],
+            marks: true,
+            highlights: Vec::new(),
+        }
+    }
+}
+
+const LIGHT: Color = Color(0xc8, 0xc8, 0xc8);
+const GRAY: Color = Color(0x96, 0x96, 0x96);
+
+impl Renderer {
+    /// Returns a renderer with the default style.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the length of a side of a grid. It's 48 by default.
+    pub fn cell_size(mut self, size: f64) -> Self {
+        self.cell = size;
+        self
+    }
+
+    /// Sets the font family. It's only used by canvases supporting it.
+    pub fn font_family(mut self, family: impl Into<String>) -> Self {
+        self.font_family = family.into();
+        self
+    }
+
+    /// Sets the color of givens, which are drawn in bold.
+    pub fn given_color(mut self, color: Color) -> Self {
+        self.given = color;
+        self
+    }
+
+    /// Sets the color of numbers filled by players or solvers.
+    pub fn entry_color(mut self, color: Color) -> Self {
+        self.entry = color;
+        self
+    }
+
+    /// Sets the color of pencil marks.
+    pub fn mark_color(mut self, color: Color) -> Self {
+        self.mark = color;
+        self
+    }
+
+    /// Sets the background color of highlighted grids.
+    pub fn highlight_color(mut self, color: Color) -> Self {
+        self.highlight = color;
+        self
+    }
+
+    /// Sets colors of grids painted in games, where color `n` is
+    /// `palette[n - 1]`.
+    pub fn palette(mut self, palette: Vec<Color>) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Sets whether to draw pencil marks. They are drawn by default.
+    pub fn pencil_marks(mut self, marks: bool) -> Self {
+        self.marks = marks;
+        self
+    }
+
+    /// Highlights the grid at `pt`.
+    pub fn highlight(mut self, pt: Pt) -> Self {
+        self.highlights.push(pt);
+        self
+    }
+
+    /// Returns the font family.
+    pub fn family(&self) -> &str {
+        &self.font_family
+    }
+
+    /// Returns the width and the height of `scene`.
+    pub fn size(&self, scene: &Scene) -> (f64, f64) {
+        let side = self.cell * WIDTH as f64 + self.margin(scene) * 2.;
+        (side, side)
+    }
+
+    /// Renders `scene` to an SVG document.
+    pub fn svg(&self, scene: &Scene) -> String {
+        let (w, h) = self.size(scene);
+        let mut canvas = SvgCanvas::new(w, h, &self.font_family);
+        self.draw(scene, &mut canvas);
+        canvas.finish()
+    }
+
+    /// Draws `scene` onto `canvas` with its top left corner at the origin.
+    pub fn draw(&self, scene: &Scene, canvas: &mut impl Canvas) {
+        let (w, h) = self.size(scene);
+        canvas.rect(0., 0., w, h, Color::WHITE);
+        self.draw_backgrounds(scene, canvas);
+        self.draw_lines(scene, canvas);
+        self.draw_grid(scene, canvas);
+        self.draw_markers(scene, canvas);
+        self.draw_outside(scene, canvas);
+        self.draw_numbers(scene, canvas);
+    }
+
+    /// Returns the space around the grids, which is wider when the rules have
+    /// clues outside.
+    fn margin(&self, scene: &Scene) -> f64 {
+        let outside = scene
+            .rules
+            .constraints()
+            .filter_map(|c| c.as_any())
+            .any(|c| c.is::<Sandwich>() || c.is::<Skyscraper>() || c.is::<LittleKiller>());
+        self.cell * if outside { 1. } else { 0.25 }
+    }
+
+    /// Returns the center of the grid at `(x, y)`, which can be outside.
+    fn at(&self, scene: &Scene, x: f64, y: f64) -> (f64, f64) {
+        let m = self.margin(scene);
+        (m + (x + 0.5) * self.cell, m + (y + 0.5) * self.cell)
+    }
+
+    fn center(&self, scene: &Scene, pt: Pt) -> (f64, f64) {
+        self.at(scene, pt.x() as f64, pt.y() as f64)
+    }
+
+    fn text(
+        &self,
+        canvas: &mut impl Canvas,
+        (x, y): (f64, f64),
+        size: f64,
+        color: Color,
+        text: &str,
+    ) {
+        canvas.text(&Text {
+            x,
+            y,
+            size: size * self.cell,
+            bold: false,
+            color,
+            text,
+        });
+    }
+
+    fn draw_backgrounds(&self, scene: &Scene, canvas: &mut impl Canvas) {
+        let c = self.cell;
+        for pt in PtIter::all() {
+            let color = scene.cell(pt).color as usize;
+            let fill = if self.highlights.contains(&pt) {
+                Some(self.highlight)
+            } else if color > 0 && !self.palette.is_empty() {
+                Some(self.palette[(color - 1) % self.palette.len()])
+            } else {
+                None
+            };
+            if let Some(fill) = fill {
+                let (x, y) = self.center(scene, pt);
+                canvas.rect(x - c / 2., y - c / 2., c, c, fill);
+            }
+        }
+    }
+
+    /// Draws constraints along lines of grids.
+    fn draw_lines(&self, scene: &Scene, canvas: &mut impl Canvas) {
+        let c = self.cell;
+        let points = |cells: &[Pt]| -> Vec<(f64, f64)> {
+            cells.iter().map(|&p| self.center(scene, p)).collect()
+        };
+        for k in scene.rules.constraints().filter_map(|k| k.as_any()) {
+            if let Some(t) = k.downcast_ref::<Thermometer>() {
+                let (x, y) = self.center(scene, t.cells()[0]);
+                canvas.circle(x, y, c * 0.35, Some(LIGHT), None);
+                canvas.line(&points(t.cells()), c * 0.25, LIGHT);
+            } else if let Some(r) = k.downcast_ref::<Renban>() {
+                canvas.line(&points(r.cells()), c * 0.3, Color(0xe6, 0xc8, 0xf5));
+            } else if let Some(w) = k.downcast_ref::<GermanWhispers>() {
+                canvas.line(&points(w.cells()), c * 0.15, Color(0x64, 0xc8, 0x64));
+            } else if let Some(p) = k.downcast_ref::<Palindrome>() {
+                canvas.line(&points(p.cells()), c * 0.15, GRAY);
+            } else if let Some(a) = k.downcast_ref::<Arrow>() {
+                let (r, width) = (c * 0.4, c * 0.04);
+                let (cx, cy) = self.center(scene, a.circle());
+                canvas.circle(cx, cy, r, Some(Color::WHITE), Some((GRAY, width)));
+                let mut p = points(a.arrow());
+                let (dx, dy) = unit((cx, cy), p[0]);
+                p.insert(0, (cx + dx * r, cy + dy * r));
+                canvas.line(&p, width, GRAY);
+                let head = arrow_head(p[p.len() - 2], p[p.len() - 1], c * 0.2);
+                canvas.line(&head, width, GRAY);
+            }
+        }
+    }
+
+    fn draw_grid(&self, scene: &Scene, canvas: &mut impl Canvas) {
+        let c = self.cell;
+        let (x0, y0) = self.at(scene, -0.5, -0.5);
+        let len = c * WIDTH as f64;
+        for i in 0..=WIDTH {
+            let (width, color) = if i % BLOCK_WIDTH == 0 {
+                (c * 0.06, Color::BLACK)
+            } else {
+                (c * 0.02, GRAY)
+            };
+            let d = i as f64 * c;
+            canvas.line(&[(x0 + d, y0), (x0 + d, y0 + len)], width, color);
+            canvas.line(&[(x0, y0 + d), (x0 + len, y0 + d)], width, color);
+        }
+    }
+
+    /// Draws markers on edges between grids.
+    fn draw_markers(&self, scene: &Scene, canvas: &mut impl Canvas) {
+        let c = self.cell;
+        let mid = |a: Pt, b: Pt| {
+            let ((ax, ay), (bx, by)) = (self.center(scene, a), self.center(scene, b));
+            ((ax + bx) / 2., (ay + by) / 2.)
+        };
+        for k in scene.rules.constraints().filter_map(|k| k.as_any()) {
+            if let Some(kropki) = k.downcast_ref::<Kropki>() {
+                let stroke = Some((Color::BLACK, c * 0.03));
+                for (a, b) in kropki.whites() {
+                    let (x, y) = mid(a, b);
+                    canvas.circle(x, y, c * 0.12, Some(Color::WHITE), stroke);
+                }
+                for (a, b) in kropki.blacks() {
+                    let (x, y) = mid(a, b);
+                    canvas.circle(x, y, c * 0.12, Some(Color::BLACK), stroke);
+                }
+            } else if let Some(xv) = k.downcast_ref::<XV>() {
+                let signs = xv.xs().map(|e| (e, "X")).chain(xv.vs().map(|e| (e, "V")));
+                for ((a, b), sign) in signs {
+                    let (x, y) = mid(a, b);
+                    canvas.circle(x, y, c * 0.15, Some(Color::WHITE), None);
+                    canvas.text(&Text {
+                        x,
+                        y,
+                        size: c * 0.3,
+                        bold: true,
+                        color: Color::BLACK,
+                        text: sign,
+                    });
+                }
+            } else if let Some(ineq) = k.downcast_ref::<Inequality>() {
+                for (less, greater) in ineq.signs() {
+                    // The chevron points at the lesser grid.
+                    let (x, y) = mid(less, greater);
+                    let (dx, dy) = unit((x, y), self.center(scene, less));
+                    let tip = (x + dx * c * 0.08, y + dy * c * 0.08);
+                    canvas.line(&arrow_head((x, y), tip, c * 0.16), c * 0.04, Color::BLACK);
+                }
+            }
+        }
+    }
+
+    /// Draws clues outside the grids.
+    fn draw_outside(&self, scene: &Scene, canvas: &mut impl Canvas) {
+        let c = self.cell;
+        for k in scene.rules.constraints().filter_map(|k| k.as_any()) {
+            let (clue, value) = if let Some(s) = k.downcast_ref::<Sandwich>() {
+                (s.clue(), s.sum())
+            } else if let Some(s) = k.downcast_ref::<Skyscraper>() {
+                (s.clue(), s.count())
+            } else if let Some(l) = k.downcast_ref::<LittleKiller>() {
+                let (dx, dy) = match l.direction() {
+                    Diagonal::UpLeft => (-1., -1.),
+                    Diagonal::UpRight => (1., -1.),
+                    Diagonal::DownLeft => (-1., 1.),
+                    Diagonal::DownRight => (1., 1.),
+                };
+                let first = l.cells()[0];
+                let (x, y) = self.at(scene, first.x() as f64 - dx, first.y() as f64 - dy);
+                self.text(
+                    canvas,
+                    (x - dx * c * 0.1, y - dy * c * 0.1),
+                    0.35,
+                    Color::BLACK,
+                    &l.sum().to_string(),
+                );
+                let from = (x + dx * c * 0.2, y + dy * c * 0.2);
+                let to = (x + dx * c * 0.4, y + dy * c * 0.4);
+                canvas.line(&[from, to], c * 0.03, Color::BLACK);
+                canvas.line(&arrow_head(from, to, c * 0.1), c * 0.03, Color::BLACK);
+                continue;
+            } else {
+                continue;
+            };
+            let (dx, dy) = match clue.side() {
+                Side::Top => (0., -1.),
+                Side::Right => (1., 0.),
+                Side::Bottom => (0., 1.),
+                Side::Left => (-1., 0.),
+            };
+            let first = clue.first();
+            let pos = self.at(scene, first.x() as f64 + dx, first.y() as f64 + dy);
+            self.text(canvas, pos, 0.5, Color::BLACK, &value.to_string());
+        }
+    }
+
+    fn draw_numbers(&self, scene: &Scene, canvas: &mut impl Canvas) {
+        const CORNERS: [(f64, f64); 9] = [
+            (-0.32, -0.3),
+            (0.32, -0.3),
+            (-0.32, 0.32),
+            (0.32, 0.32),
+            (0., -0.3),
+            (0., 0.32),
+            (-0.32, 0.),
+            (0.32, 0.),
+            (0., 0.),
+        ];
+        let c = self.cell;
+        for pt in PtIter::all() {
+            let cell = scene.cell(pt);
+            let (x, y) = self.center(scene, pt);
+            if cell.value != 0 {
+                canvas.text(&Text {
+                    x,
+                    y,
+                    size: c * 0.6,
+                    bold: cell.given,
+                    color: if cell.given { self.given } else { self.entry },
+                    text: &cell.value.to_string(),
+                });
+                continue;
+            }
+            if !self.marks {
+                continue;
+            }
+            let digits = |m: u16| (1..=9).filter(move |n| m & 1 << n != 0);
+            if cell.center != 0 {
+                let s: String = digits(cell.center).map(|n| (b'0' + n) as char).collect();
+                let size = if s.len() > 5 { 0.16 } else { 0.22 };
+                self.text(canvas, (x, y), size, self.mark, &s);
+            }
+            for (n, (dx, dy)) in digits(cell.corner).zip(CORNERS.iter()) {
+                self.text(
+                    canvas,
+                    (x + dx * c, y + dy * c),
+                    0.2,
+                    self.mark,
+                    &n.to_string(),
+                );
+            }
+        }
+    }
+}
+
+/// Formats `v` with at most 2 decimal places.
+fn num(v: f64) -> String {
+    let s = format!("{:.2}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    match s {
+        "-0" | "" => "0".to_string(),
+        _ => s.to_string(),
+    }
+}
+
+/// Returns the unit vector from `a` to `b`.
+fn unit(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    (dx / len, dy / len)
+}
+
+/// Returns points of a head of an arrow from `from` to `to` having sides of
+/// `len`.
+fn arrow_head(from: (f64, f64), to: (f64, f64), len: f64) -> [(f64, f64); 3] {
+    let (dx, dy) = unit(from, to);
+    // Rotate the reversed direction by 30 degrees each way.
+    let (s, c) = (0.5, 0.75f64.sqrt());
+    let side = |sign: f64| {
+        let (x, y) = (-dx * c - sign * -dy * s, -dx * sign * s + -dy * c);
+        (to.0 + x * len, to.1 + y * len)
+    };
+    [side(1.), to, side(-1.)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::Outside;
+    use crate::solver::Solver;
+
+    #[test]
+    fn num() {
+        assert_eq!(super::num(1.), "1");
+        assert_eq!(super::num(2.5), "2.5");
+        assert_eq!(super::num(1. / 3.), "0.33");
+        assert_eq!(super::num(-0.001), "0");
+        assert_eq!(super::num(10.), "10");
+    }
+
+    #[test]
+    fn board() {
+        let mut b = Board::default();
+        b.set(Pt::new(0, 0).unwrap(), 5).unwrap();
+        let svg = Renderer::new().cell_size(10.).svg(&Scene::from(&b));
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"95\" height=\"95\" \
+             viewBox=\"0 0 95 95\" font-family=\"sans-serif\">\n\
+             <rect x=\"0\" y=\"0\" width=\"95\" height=\"95\" fill=\"#ffffff\"/>\n\
+             <polyline points=\"2.5,2.5 2.5,92.5\" fill=\"none\" stroke=\"#000000\" \
+             stroke-width=\"0.6\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n"
+        ));
+        assert!(svg.contains(
+            "<text x=\"7.5\" y=\"7.5\" font-size=\"6\" font-weight=\"bold\" fill=\"#000000\" \
+             text-anchor=\"middle\" dominant-baseline=\"central\">5</text>\n"
+        ));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polyline ").count(), 20);
+    }
+
+    #[test]
+    fn game() {
+        let answer = crate::solvers::HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        let mut givens = answer;
+        let (a, b) = (Pt::new(0, 0).unwrap(), Pt::new(4, 4).unwrap());
+        givens.set(a, 0).unwrap();
+        givens.set(b, 0).unwrap();
+        let mut g = Game::new(Puzzle::new(givens).unwrap());
+        g.toggle_mark(a, Mark::Center, 1).unwrap();
+        g.toggle_mark(a, Mark::Corner, 2).unwrap();
+        g.enter(b, answer.get(b).unwrap()).unwrap();
+        g.set_color(b, 1);
+
+        let r = Renderer::new().cell_size(10.);
+        let svg = r.svg(&Scene::from(&g));
+        assert_eq!(svg.matches("font-weight=\"bold\"").count(), 79);
+        assert_eq!(svg.matches("fill=\"#1e5ac8\"").count(), 1);
+        assert_eq!(svg.matches("fill=\"#505050\"").count(), 2);
+        assert_eq!(svg.matches("fill=\"#ffc8c8\"").count(), 1);
+        let svg = r.pencil_marks(false).svg(&Scene::from(&g));
+        assert_eq!(svg.matches("fill=\"#505050\"").count(), 0);
+    }
+
+    #[test]
+    fn variants() {
+        let p = |x, y| Pt::new(x, y).unwrap();
+        let rules = Rules::new()
+            .with(Thermometer::new(vec![p(0, 0), p(1, 0), p(2, 0)]).unwrap())
+            .with(Arrow::new(p(4, 4), vec![p(5, 5), p(6, 6)]).unwrap())
+            .with(
+                Kropki::new()
+                    .white(p(0, 8), p(1, 8))
+                    .unwrap()
+                    .black(p(2, 8), p(2, 7))
+                    .unwrap(),
+            )
+            .with(XV::new().x(p(8, 0), p(8, 1)).unwrap())
+            .with(Inequality::new().less(p(5, 0), p(6, 0)).unwrap())
+            .with(Sandwich::new(Outside::new(Side::Top, 3).unwrap(), 10).unwrap())
+            .with(
+                LittleKiller::new(Outside::new(Side::Left, 2).unwrap(), Diagonal::UpRight, 12)
+                    .unwrap(),
+            );
+        let s = Scene::from(&Board::default()).with_rules(rules);
+        let r = Renderer::new().cell_size(10.);
+        assert_eq!(r.size(&s), (110., 110.));
+        let svg = r.svg(&s);
+        assert_eq!(svg, r.svg(&s));
+        // The bulb, the arrow, 2 dots, and the background of X.
+        assert_eq!(svg.matches("<circle ").count(), 5);
+        assert!(svg.contains(">X</text>"));
+        assert!(svg.contains(">10</text>"));
+        assert!(svg.contains(">12</text>"));
+    }
+}
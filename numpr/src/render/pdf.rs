@@ -0,0 +1,487 @@
+use super::{num, Canvas, Color, Renderer, Scene, Text};
+use crate::error::NumprResult;
+use crate::puzzle::Puzzle;
+use std::fmt::Write as _;
+use std::io::Write;
+
+/// A size of paper.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PageSize {
+    /// 210 x 297 mm.
+    A4,
+    /// 8.5 x 11 inches.
+    Letter,
+}
+
+impl PageSize {
+    /// Returns the width and the height in points.
+    pub fn points(self) -> (f64, f64) {
+        match self {
+            PageSize::A4 => (595.28, 841.89),
+            PageSize::Letter => (612., 792.),
+        }
+    }
+}
+
+/// A PDF document drawn with the standard Helvetica fonts, which don't need
+/// to be embedded.
+///
+/// The document has no timestamp, so the same drawing always results in the
+/// same bytes.
+pub struct PdfDocument {
+    size: PageSize,
+    pages: Vec<PdfCanvas>,
+}
+
+impl PdfDocument {
+    /// Returns an empty document having pages of `size`.
+    pub fn new(size: PageSize) -> Self {
+        Self {
+            size,
+            pages: Vec::new(),
+        }
+    }
+
+    /// Adds a page and returns its canvas.
+    pub fn add_page(&mut self) -> &mut PdfCanvas {
+        let (_, height) = self.size.points();
+        self.pages.push(PdfCanvas {
+            height,
+            origin: (0., 0.),
+            out: String::new(),
+        });
+        self.pages.last_mut().unwrap()
+    }
+
+    /// Returns the number of pages.
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns `true` when there's no page.
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Writes the document to `w` and returns `w`.
+    pub fn write<W: Write>(&self, mut w: W) -> NumprResult<W> {
+        let (width, height) = self.size.points();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            String::new(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+             /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold \
+             /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        let mut kids = Vec::new();
+        for page in &self.pages {
+            let id = objects.len() + 1;
+            kids.push(format!("{} 0 R", id));
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                num(width),
+                num(height),
+                id + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.out.len(),
+                page.out
+            ));
+        }
+        objects[1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            kids.len()
+        );
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (i, o) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            write!(out, "{} 0 obj\n{}\nendobj\n", i + 1, o).unwrap();
+        }
+        let xref = out.len();
+        write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).unwrap();
+        for o in offsets {
+            writeln!(out, "{:010} 00000 n ", o).unwrap();
+        }
+        write!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .unwrap();
+        w.write_all(out.as_bytes())?;
+        Ok(w)
+    }
+}
+
+/// A canvas drawing a page of a [`PdfDocument`](struct.PdfDocument.html).
+///
+/// Coordinates are in points from the top left corner of the page, shifted by
+/// the origin.
+pub struct PdfCanvas {
+    height: f64,
+    origin: (f64, f64),
+    out: String,
+}
+
+impl PdfCanvas {
+    /// Moves the origin to `(x, y)` of the page.
+    pub fn set_origin(&mut self, x: f64, y: f64) {
+        self.origin = (x, y);
+    }
+
+    fn pt(&self, x: f64, y: f64) -> String {
+        format!(
+            "{} {}",
+            num(self.origin.0 + x),
+            num(self.height - self.origin.1 - y)
+        )
+    }
+
+    fn color(&mut self, c: Color, op: &str) {
+        let f = |v: u8| num(v as f64 / 255.);
+        writeln!(self.out, "{} {} {} {}", f(c.0), f(c.1), f(c.2), op).unwrap();
+    }
+}
+
+impl Canvas for PdfCanvas {
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: Color) {
+        self.color(fill, "rg");
+        let corner = self.pt(x, y + height);
+        writeln!(self.out, "{} {} {} re f", corner, num(width), num(height)).unwrap();
+    }
+
+    fn line(&mut self, points: &[(f64, f64)], width: f64, color: Color) {
+        self.color(color, "RG");
+        writeln!(self.out, "{} w 1 J 1 j", num(width)).unwrap();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            let op = if i == 0 { "m" } else { "l" };
+            writeln!(self.out, "{} {}", self.pt(x, y), op).unwrap();
+        }
+        self.out.push_str("S\n");
+    }
+
+    fn circle(
+        &mut self,
+        x: f64,
+        y: f64,
+        r: f64,
+        fill: Option<Color>,
+        stroke: Option<(Color, f64)>,
+    ) {
+        if let Some(c) = fill {
+            self.color(c, "rg");
+        }
+        if let Some((c, w)) = stroke {
+            self.color(c, "RG");
+            writeln!(self.out, "{} w", num(w)).unwrap();
+        }
+        // 4 Bezier curves approximating quarter arcs.
+        let k = r * 0.552_285;
+        writeln!(self.out, "{} m", self.pt(x + r, y)).unwrap();
+        let arcs = [
+            [(x + r, y + k), (x + k, y + r), (x, y + r)],
+            [(x - k, y + r), (x - r, y + k), (x - r, y)],
+            [(x - r, y - k), (x - k, y - r), (x, y - r)],
+            [(x + k, y - r), (x + r, y - k), (x + r, y)],
+        ];
+        for a in &arcs {
+            let p: Vec<String> = a.iter().map(|&(x, y)| self.pt(x, y)).collect();
+            writeln!(self.out, "{} c", p.join(" ")).unwrap();
+        }
+        let op = match (fill, stroke) {
+            (Some(_), Some(_)) => "b",
+            (Some(_), None) => "f",
+            (None, Some(_)) => "s",
+            (None, None) => "n",
+        };
+        writeln!(self.out, "{}", op).unwrap();
+    }
+
+    fn text(&mut self, text: &Text) {
+        self.color(text.color, "rg");
+        let width = text_width(text.text) * text.size;
+        // Helvetica has the cap height of 0.718 em.
+        let at = self.pt(text.x - width / 2., text.y + text.size * 0.359);
+        writeln!(
+            self.out,
+            "BT /F{} {} Tf {} Td ({}) Tj ET",
+            if text.bold { 2 } else { 1 },
+            num(text.size),
+            at,
+            escape(text.text)
+        )
+        .unwrap();
+    }
+}
+
+/// Returns the approximate width of `s` in Helvetica in em.
+fn text_width(s: &str) -> f64 {
+    s.chars()
+        .map(|c| match c {
+            ' ' | '.' | ',' | ':' => 0.278,
+            'i' | 'j' | 'l' | 'I' => 0.25,
+            'm' | 'w' | 'M' | 'W' => 0.85,
+            'A'..='Z' => 0.68,
+            _ => 0.556,
+        })
+        .sum()
+}
+
+/// Escapes a string for PDF, replacing non-ASCII characters with `?`.
+fn escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\\' | '(' | ')' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// A printable booklet of puzzles with the solutions in an appendix.
+///
+/// Each puzzle is labeled with its number, difficulty, and ID. The solutions
+/// are laid out in smaller grids after the puzzles.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::render::{Booklet, PageSize};
+/// use numpr::{solvers, Board, Pt, Puzzle, Solver};
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let answer = solvers::HeuristicSolver::new().solve(&Board::default(), true)?;
+/// let mut givens = answer;
+/// givens.set(Pt::new(4, 4)?, 0)?;
+/// let puzzles = vec![Puzzle::new(givens)?; 5];
+///
+/// let pdf = Booklet::new()
+///     .page_size(PageSize::Letter)
+///     .per_page(4)
+///     .write(Vec::new(), &puzzles)?;
+/// assert!(pdf.starts_with(b"%PDF-"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Booklet {
+    size: PageSize,
+    per_page: usize,
+    solutions_per_page: usize,
+    renderer: Renderer,
+}
+
+impl Default for Booklet {
+    fn default() -> Self {
+        Self {
+            size: PageSize::A4,
+            per_page: 2,
+            solutions_per_page: 12,
+            renderer: Renderer::new(),
+        }
+    }
+}
+
+/// The margin of pages in points.
+const MARGIN: f64 = 36.;
+/// The font size of labels in points.
+const LABEL: f64 = 10.;
+
+impl Booklet {
+    /// Returns a booklet of A4 pages having 2 puzzles and 12 solutions per
+    /// page.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the paper size.
+    pub fn page_size(mut self, size: PageSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets the number of puzzles per page, which is at least 1.
+    pub fn per_page(mut self, n: usize) -> Self {
+        self.per_page = n.max(1);
+        self
+    }
+
+    /// Sets the number of solutions per page in the appendix, which is at
+    /// least 1.
+    pub fn solutions_per_page(mut self, n: usize) -> Self {
+        self.solutions_per_page = n.max(1);
+        self
+    }
+
+    /// Sets the renderer drawing puzzles. The cell size is ignored since
+    /// puzzles are scaled to fit pages.
+    pub fn renderer(mut self, renderer: Renderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Returns the document of `puzzles`.
+    pub fn document(&self, puzzles: &[Puzzle]) -> PdfDocument {
+        let mut doc = PdfDocument::new(self.size);
+        let labels: Vec<String> = puzzles
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let level = p
+                    .difficulty()
+                    .map_or("Unrated".to_string(), |d| format!("{:?}", d));
+                format!("No. {}   {}   ID {}", i + 1, level, p.id())
+            })
+            .collect();
+        let scenes: Vec<Scene> = puzzles.iter().map(Scene::from).collect();
+        self.layout(&mut doc, &scenes, &labels, self.per_page, None);
+
+        let labels: Vec<String> = (1..=puzzles.len()).map(|i| format!("No. {}", i)).collect();
+        let scenes: Vec<Scene> = puzzles.iter().map(Scene::solution).collect();
+        self.layout(
+            &mut doc,
+            &scenes,
+            &labels,
+            self.solutions_per_page,
+            Some("Solutions"),
+        );
+        doc
+    }
+
+    /// Writes the document of `puzzles` to `w` and returns `w`.
+    pub fn write<W: Write>(&self, w: W, puzzles: &[Puzzle]) -> NumprResult<W> {
+        self.document(puzzles).write(w)
+    }
+
+    /// Lays out `scenes` in a grid of `per_page` slots per page with `title`
+    /// on the first page.
+    fn layout(
+        &self,
+        doc: &mut PdfDocument,
+        scenes: &[Scene],
+        labels: &[String],
+        per_page: usize,
+        title: Option<&str>,
+    ) {
+        let (width, height) = self.size.points();
+        let rows = (1..).find(|r| r * r >= per_page).unwrap();
+        let cols = per_page.div_ceil(rows);
+        let top = MARGIN + title.map_or(0., |_| LABEL * 3.);
+        let (slot_w, slot_h) = (
+            (width - MARGIN * 2.) / cols as f64,
+            (height - top - MARGIN) / rows as f64,
+        );
+
+        for (i, (scene, label)) in scenes.iter().zip(labels).enumerate() {
+            let k = i % per_page;
+            if k == 0 {
+                let page = doc.add_page();
+                if let (Some(title), 0) = (title, i) {
+                    page.text(&Text {
+                        x: width / 2.,
+                        y: MARGIN + LABEL,
+                        size: LABEL * 1.6,
+                        bold: true,
+                        color: Color::BLACK,
+                        text: title,
+                    });
+                }
+            }
+            let page = doc.pages.last_mut().unwrap();
+            let (x, y) = (
+                MARGIN + (k % cols) as f64 * slot_w,
+                top + (k / cols) as f64 * slot_h,
+            );
+            // Leave a label line and spacing between slots.
+            let side = (slot_w * 0.92).min((slot_h - LABEL * 2.) * 0.92);
+            let unit = self.renderer.clone().cell_size(1.).size(scene).0;
+            let renderer = self.renderer.clone().cell_size(side / unit);
+            let left = x + (slot_w - side) / 2.;
+            page.set_origin(0., 0.);
+            page.text(&Text {
+                x: left + side / 2.,
+                y: y + LABEL * 0.8,
+                size: LABEL,
+                bold: false,
+                color: Color::BLACK,
+                text: label,
+            });
+            page.set_origin(left, y + LABEL * 1.6);
+            renderer.draw(scene, page);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::pt::Pt;
+    use crate::solver::Solver;
+    use crate::solvers::HeuristicSolver;
+
+    fn puzzle() -> Puzzle {
+        let mut b = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        b.set(Pt::new(0, 0).unwrap(), 0).unwrap();
+        Puzzle::new(b).unwrap()
+    }
+
+    #[test]
+    fn document() {
+        let mut doc = PdfDocument::new(PageSize::Letter);
+        let page = doc.add_page();
+        page.set_origin(10., 20.);
+        page.rect(0., 0., 5., 5., Color::WHITE);
+        page.text(&Text {
+            x: 0.,
+            y: 0.,
+            size: 10.,
+            bold: true,
+            color: Color::BLACK,
+            text: "(a)",
+        });
+        let pdf = String::from_utf8(doc.write(Vec::new()).unwrap()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/MediaBox [0 0 612 792]"));
+        assert!(pdf.contains("1 1 1 rg\n10 767 5 5 re f\n"));
+        assert!(pdf.contains("/F2 10 Tf"));
+        assert!(pdf.contains("(\\(a\\)) Tj"));
+
+        // The cross-reference table points at the objects.
+        let xref: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(pdf[xref..].starts_with("xref\n0 7\n"));
+        let first = pdf[xref..].lines().nth(3).unwrap();
+        let offset: usize = first[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with("1 0 obj"));
+    }
+
+    #[test]
+    fn booklet() {
+        let puzzles = vec![puzzle(); 5];
+        let b = Booklet::new().per_page(4).solutions_per_page(4);
+        // 2 pages of puzzles and 2 pages of solutions.
+        assert_eq!(b.document(&puzzles).len(), 4);
+        assert_eq!(Booklet::new().document(&puzzles).len(), 4);
+        assert_eq!(Booklet::new().per_page(0).document(&puzzles[..1]).len(), 2);
+
+        let pdf = b.write(Vec::new(), &puzzles).unwrap();
+        assert_eq!(pdf, b.write(Vec::new(), &puzzles).unwrap());
+        let pdf = String::from_utf8(pdf).unwrap();
+        assert!(pdf.contains("/MediaBox [0 0 595.28 841.89]"));
+        assert!(pdf.contains(&format!("(No. 5   Easy   ID {}) Tj", puzzles[0].id())));
+        assert!(pdf.contains("(Solutions) Tj"));
+    }
+}
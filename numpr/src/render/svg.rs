@@ -0,0 +1,148 @@
+use super::{num, Canvas, Color, Text};
+use std::fmt::Write;
+
+/// A canvas writing an SVG document.
+///
+/// Numbers are written with at most 2 decimal places, so the same drawing
+/// always results in the same document.
+pub struct SvgCanvas {
+    out: String,
+}
+
+impl SvgCanvas {
+    /// Returns a canvas of the size using `font_family` for text.
+    pub fn new(width: f64, height: f64, font_family: &str) -> Self {
+        let (w, h) = (num(width), num(height));
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" font-family=\"{}\">",
+            escape(font_family),
+            w = w,
+            h = h
+        )
+        .unwrap();
+        Self { out }
+    }
+
+    /// Closes the document and returns it.
+    pub fn finish(mut self) -> String {
+        self.out.push_str("</svg>\n");
+        self.out
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: Color) {
+        writeln!(
+            self.out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            num(x),
+            num(y),
+            num(width),
+            num(height),
+            fill
+        )
+        .unwrap();
+    }
+
+    fn line(&mut self, points: &[(f64, f64)], width: f64, color: Color) {
+        let points: Vec<String> = points
+            .iter()
+            .map(|&(x, y)| format!("{},{}", num(x), num(y)))
+            .collect();
+        writeln!(
+            self.out,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" \
+             stroke-linecap=\"round\" stroke-linejoin=\"round\"/>",
+            points.join(" "),
+            color,
+            num(width)
+        )
+        .unwrap();
+    }
+
+    fn circle(
+        &mut self,
+        x: f64,
+        y: f64,
+        r: f64,
+        fill: Option<Color>,
+        stroke: Option<(Color, f64)>,
+    ) {
+        write!(
+            self.out,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"",
+            num(x),
+            num(y),
+            num(r)
+        )
+        .unwrap();
+        match fill {
+            Some(c) => write!(self.out, " fill=\"{}\"", c),
+            None => write!(self.out, " fill=\"none\""),
+        }
+        .unwrap();
+        if let Some((c, w)) = stroke {
+            write!(self.out, " stroke=\"{}\" stroke-width=\"{}\"", c, num(w)).unwrap();
+        }
+        self.out.push_str("/>\n");
+    }
+
+    fn text(&mut self, text: &Text) {
+        write!(
+            self.out,
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\"",
+            num(text.x),
+            num(text.y),
+            num(text.size)
+        )
+        .unwrap();
+        if text.bold {
+            self.out.push_str(" font-weight=\"bold\"");
+        }
+        writeln!(
+            self.out,
+            " fill=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+            text.color,
+            escape(text.text)
+        )
+        .unwrap();
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes() {
+        let mut c = SvgCanvas::new(10., 10., "a<b");
+        c.circle(1., 2., 3., None, Some((Color::BLACK, 0.5)));
+        c.text(&Text {
+            x: 0.,
+            y: 0.,
+            size: 1.,
+            bold: false,
+            color: Color(1, 2, 255),
+            text: "&",
+        });
+        assert_eq!(
+            c.finish(),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"10\" height=\"10\" \
+             viewBox=\"0 0 10 10\" font-family=\"a&lt;b\">\n\
+             <circle cx=\"1\" cy=\"2\" r=\"3\" fill=\"none\" stroke=\"#000000\" stroke-width=\"0.5\"/>\n\
+             <text x=\"0\" y=\"0\" font-size=\"1\" fill=\"#0102ff\" text-anchor=\"middle\" \
+             dominant-baseline=\"central\">&amp;</text>\n\
+             </svg>\n"
+        );
+    }
+}
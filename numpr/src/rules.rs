@@ -0,0 +1,78 @@
+use crate::board::Board;
+use crate::constraint::Constraint;
+use crate::consts::SIZE;
+use crate::error::NumprResult;
+use std::sync::Arc;
+
+/// A set of variant constraints applied on top of the classic rules.
+///
+/// `Rules` is cheap to clone, so it can be passed to solver factories.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::{constraints, Pt, Rules, Solver, solvers, Board};
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let cells = (0..9).map(|x| Pt::new(x, 0)).collect::<NumprResult<_>>()?;
+/// let rules = Rules::new().with(constraints::Thermometer::new(cells)?);
+///
+/// let b = solvers::VariantSolver::new(rules.clone()).solve(&Board::default(), true)?;
+/// rules.validate(&b)?;
+/// assert_eq!(b.get(Pt::new(8, 0)?), Some(9));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Rules {
+    constraints: Vec<Arc<dyn Constraint>>,
+}
+
+impl Rules {
+    /// Returns rules without any variant constraint.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the rules with `c` added.
+    pub fn with(mut self, c: impl Constraint + 'static) -> Self {
+        self.push(c);
+        self
+    }
+
+    /// Adds a constraint.
+    pub fn push(&mut self, c: impl Constraint + 'static) {
+        self.constraints.push(Arc::new(c));
+    }
+
+    /// Returns `true` when there's no variant constraint.
+    pub fn is_classic(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Iterates over the constraints.
+    pub fn constraints(&self) -> impl Iterator<Item = &dyn Constraint> {
+        self.constraints.iter().map(|c| c.as_ref())
+    }
+
+    /// Checks if a completed board satisfies the classic rules and all the
+    /// constraints.
+    pub fn validate(&self, board: &Board) -> NumprResult<()> {
+        board.validate()?;
+        for c in &self.constraints {
+            c.validate(board)?;
+        }
+        Ok(())
+    }
+
+    /// Runs [`Constraint::propagate`](trait.Constraint.html#tymethod.propagate)
+    /// of all the constraints once.
+    pub fn propagate(&self, masks: &mut [u16; SIZE]) -> NumprResult<bool> {
+        let mut changed = false;
+        for c in &self.constraints {
+            changed |= c.propagate(masks)?;
+        }
+        Ok(changed)
+    }
+}
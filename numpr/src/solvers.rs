@@ -1,5 +1,100 @@
+mod bitboard;
+mod bitparallel;
 mod heuristic;
+mod iterative;
 mod naive;
+mod variant;
 
-pub use heuristic::HeuristicSolver;
+pub use bitboard::BitBoardSolver;
+pub use bitparallel::BitParallelSolver;
+pub use heuristic::{Branching, HeuristicSolver, ValueOrder};
+pub use iterative::IterativeSolver;
 pub use naive::NaiveSolver;
+pub use variant::VariantSolver;
+
+use crate::consts::*;
+
+/// Indices of grids in each row, column, and block.
+pub(crate) static UNITS: [[u8; 9]; 27] = units();
+
+const fn units() -> [[u8; 9]; 27] {
+    let mut units = [[0; 9]; 27];
+    let mut i = 0;
+    while i < 9 {
+        let mut j = 0;
+        while j < 9 {
+            let x = i % 3 * BLOCK_WIDTH + j % BLOCK_WIDTH;
+            let y = i / 3 * BLOCK_HEIGHT + j / BLOCK_WIDTH;
+            units[i * 3][j] = (i * WIDTH + j) as u8;
+            units[i * 3 + 1][j] = (j * WIDTH + i) as u8;
+            units[i * 3 + 2][j] = (y * WIDTH + x) as u8;
+            j += 1;
+        }
+        i += 1;
+    }
+    units
+}
+
+/// Indices of grids in the same row, column, or block as each grid.
+pub(crate) static PEERS: [[u8; 20]; SIZE] = peers();
+
+const fn peers() -> [[u8; 20]; SIZE] {
+    let mut peers = [[0; 20]; SIZE];
+    let mut i = 0;
+    while i < SIZE {
+        let (x, y) = (i % WIDTH, i / WIDTH);
+        let mut k = 0;
+        let mut j = 0;
+        while j < SIZE {
+            let (u, v) = (j % WIDTH, j / WIDTH);
+            let block = u / BLOCK_WIDTH == x / BLOCK_WIDTH && v / BLOCK_HEIGHT == y / BLOCK_HEIGHT;
+            if j != i && (u == x || v == y || block) {
+                peers[i][k] = j as u8;
+                k += 1;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    peers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pt::{Pt, PtIter};
+
+    #[test]
+    fn units() {
+        for i in 0..9 {
+            let its: [Vec<Pt>; 3] = [
+                PtIter::row(Pt::new(0, i).unwrap()).collect(),
+                PtIter::col(Pt::new(i, 0).unwrap()).collect(),
+                PtIter::block(Pt::new(i % 3 * BLOCK_WIDTH, i / 3 * BLOCK_HEIGHT).unwrap())
+                    .collect(),
+            ];
+            for (k, it) in its.iter().enumerate() {
+                let unit: Vec<usize> = it.iter().map(|p| p.index()).collect();
+                let expected: Vec<usize> = UNITS[i * 3 + k].iter().map(|&j| j as usize).collect();
+                assert_eq!(unit, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn peers() {
+        for (i, peers) in PEERS.iter().enumerate() {
+            let pt = Pt::new(i % WIDTH, i / WIDTH).unwrap();
+            let mut expected: Vec<usize> = PtIter::row(pt)
+                .chain(PtIter::col(pt))
+                .chain(PtIter::block(pt))
+                .map(|p| p.index())
+                .filter(|&j| j != i)
+                .collect();
+            expected.sort_unstable();
+            expected.dedup();
+            let peers: Vec<usize> = peers.iter().map(|&j| j as usize).collect();
+            assert_eq!(peers, expected);
+        }
+    }
+}
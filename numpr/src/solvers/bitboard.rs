@@ -0,0 +1,190 @@
+use crate::bitboard::BitBoard;
+use crate::budget::{self, Budget};
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::solvers::UNITS;
+use crate::Board;
+use crate::Solver;
+use rand::prelude::*;
+
+/// BitBoardSolver solves puzzles on a [`BitBoard`](../struct.BitBoard.html).
+///
+/// It finds naked and hidden singles with bitwise operations on candidate
+/// masks and branches on the grid having the fewest candidates.
+pub struct BitBoardSolver {
+    budget: Option<Budget>,
+}
+
+const ALL: u16 = 0b11_1111_1110;
+
+impl Default for BitBoardSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitBoardSolver {
+    /// Returns a new `BitBoardSolver`.
+    pub fn new() -> Self {
+        Self { budget: None }
+    }
+
+    /// Returns the solver failing with `NumprError::BudgetExceeded` when
+    /// `budget` runs out.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Places naked and hidden singles until none is left. It returns the
+    /// index of the grid having the fewest candidates, or `None` when the
+    /// board is completed.
+    fn propagate(&self, b: &mut BitBoard) -> NumprResult<Option<usize>> {
+        loop {
+            let mut progress = false;
+            let mut best = None;
+            let mut fewest = 10;
+            for i in 0..SIZE {
+                if b.raw_get(i) != 0 {
+                    continue;
+                }
+                let m = b.raw_candidates(i);
+                if m == 0 {
+                    return NumprError::unsolvable();
+                }
+                let cnt = m.count_ones();
+                if cnt == 1 {
+                    b.place(i, m.trailing_zeros() as u8);
+                    progress = true;
+                } else if cnt < fewest {
+                    fewest = cnt;
+                    best = Some(i);
+                }
+            }
+            if progress {
+                continue;
+            }
+
+            for unit in &UNITS {
+                let mut once = 0u16;
+                let mut twice = 0u16;
+                let mut placed = 0u16;
+                for &i in unit {
+                    let i = i as usize;
+                    match b.raw_get(i) {
+                        0 => {
+                            let m = b.raw_candidates(i);
+                            twice |= once & m;
+                            once |= m;
+                        }
+                        n => placed |= 1 << n,
+                    }
+                }
+                if once | placed != ALL {
+                    return NumprError::unsolvable();
+                }
+
+                let mut singles = once & !twice;
+                while singles != 0 {
+                    let n = singles.trailing_zeros() as u8;
+                    singles &= singles - 1;
+                    let i = unit
+                        .iter()
+                        .map(|&i| i as usize)
+                        .find(|&i| b.raw_get(i) == 0 && b.raw_candidates(i) & (1 << n) != 0);
+                    match i {
+                        Some(i) => b.place(i, n),
+                        // Another single in the unit took the grid.
+                        None => return NumprError::unsolvable(),
+                    }
+                    progress = true;
+                }
+            }
+            if !progress {
+                return Ok(best);
+            }
+        }
+    }
+
+    fn recurse(&self, mut b: BitBoard, random: bool) -> NumprResult<BitBoard> {
+        budget::spend(&self.budget)?;
+        let i = match self.propagate(&mut b)? {
+            Some(i) => i,
+            None => return Ok(b),
+        };
+
+        let m = b.raw_candidates(i);
+        let mut candidates = [0u8; 9];
+        let mut n = 0;
+        for c in 1..=9 {
+            if m & (1 << c) != 0 {
+                candidates[n] = c;
+                n += 1;
+            }
+        }
+        if random {
+            candidates[..n].shuffle(&mut rand::thread_rng());
+        }
+        for &c in &candidates[..n] {
+            let mut next = b;
+            next.place(i, c);
+            let res = self.recurse(next, random);
+            if matches!(res, Ok(_) | Err(NumprError::BudgetExceeded)) {
+                return res;
+            }
+        }
+        NumprError::unsolvable()
+    }
+}
+
+impl Solver for BitBoardSolver {
+    fn solve(self, board: &Board, random: bool) -> NumprResult<Board> {
+        let b = match BitBoard::new(board) {
+            Ok(b) => b,
+            Err(_) => return NumprError::unsolvable(),
+        };
+        Ok(self.recurse(b, random)?.to_board())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::NaiveGenerator;
+    use crate::pt::Pt;
+    use crate::solvers::HeuristicSolver;
+    use crate::uniques::NaiveUniquenessChecker;
+    use crate::{Generator, UniquenessChecker};
+
+    #[test]
+    fn solve() {
+        let b = BitBoardSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        b.validate().unwrap();
+    }
+
+    #[test]
+    fn puzzle() {
+        let answer = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        let puzzle = NaiveGenerator::new(SIZE as u32)
+            .generate(&answer, BitBoardSolver::new, NaiveUniquenessChecker::new)
+            .unwrap();
+        NaiveUniquenessChecker::new()
+            .check(&puzzle, HeuristicSolver::new)
+            .unwrap();
+
+        let b = BitBoardSolver::new().solve(&puzzle, false).unwrap();
+        assert!(b.iter().eq(answer.iter()));
+    }
+
+    #[test]
+    fn conflict() {
+        let mut b = Board::default();
+        b.set(Pt::new(0, 0).unwrap(), 1).unwrap();
+        b.set(Pt::new(8, 0).unwrap(), 1).unwrap();
+        assert!(BitBoardSolver::new().solve(&b, false).is_err());
+    }
+}
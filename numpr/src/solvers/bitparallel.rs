@@ -0,0 +1,511 @@
+mod kernel;
+
+#[cfg(target_arch = "x86_64")]
+use self::kernel::{Avx2, Sse2};
+use self::kernel::{Kernel, Mask, Planes, Scalar};
+use crate::budget::{self, Budget};
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::Board;
+use crate::Solver;
+use rand::prelude::*;
+
+/// BitParallelSolver solves puzzles by bitwise operations over many grids at
+/// once, which makes it suitable for solving a large number of puzzles.
+///
+/// Like band-based solvers such as JCZSolve or Tdoku, it keeps a 27-bit mask
+/// of candidate grids for each number in each band of three rows. Locked
+/// candidates within a band are removed with lookup tables: a number must be
+/// placed once in each row and block of the band, so only the blocks of each
+/// row that fit such an arrangement are kept. A row left with a single
+/// candidate places the number and removes it from the column in the other
+/// bands. Naked singles are found by counting candidates of all the grids in
+/// parallel. Before branching, it places numbers having a single place in a
+/// column, and then branches on a grid having two candidates when there is
+/// one.
+///
+/// The counting uses AVX2 or SSE2 when the processor supports them, and falls
+/// back to portable code otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::{solvers::BitParallelSolver, Board, Solver};
+///
+/// let solver = BitParallelSolver::new();
+/// assert_eq!(solver.count(&Board::default(), 3).unwrap(), 3);
+///
+/// let b = solver.solve(&Board::default(), true).unwrap();
+/// b.validate().unwrap();
+/// ```
+pub struct BitParallelSolver {
+    isa: Isa,
+    budget: Option<Budget>,
+}
+
+/// Instruction sets the solver can use.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Isa {
+    Scalar,
+    #[cfg(target_arch = "x86_64")]
+    Sse2,
+    #[cfg(target_arch = "x86_64")]
+    Avx2(Avx2),
+}
+
+const BAND: u32 = (1 << 27) - 1;
+const ROW: u32 = 0x1ff;
+const COLUMN: u32 = 1 | 1 << 9 | 1 << 18;
+
+/// Blocks of a row having candidates: the k-th bit is set when the 9 bits of
+/// the row have any in the k-th block.
+static SHRINK: [u32; 512] = shrink();
+
+/// Candidates of a band kept by the blocks having candidates in each row,
+/// where the bit `r * 3 + k` is set when the r-th row has candidates in the
+/// k-th block. A number is placed in a different block in each row, so only
+/// the blocks that are part of such a permutation are kept.
+static COMPLEX: [u32; 512] = complex();
+
+/// The candidate of a row having exactly one, or `0`.
+static SINGLE: [u16; 512] = single();
+
+const fn shrink() -> [u32; 512] {
+    let mut t = [0; 512];
+    let mut s = 0;
+    while s < 512 {
+        let mut k = 0;
+        while k < 3 {
+            if s >> (k * 3) & 7 != 0 {
+                t[s] |= 1 << k;
+            }
+            k += 1;
+        }
+        s += 1;
+    }
+    t
+}
+
+const fn single() -> [u16; 512] {
+    let mut t = [0; 512];
+    let mut k = 0;
+    while k < 9 {
+        t[1 << k] = 1 << k;
+        k += 1;
+    }
+    t
+}
+
+const fn complex() -> [u32; 512] {
+    const PERMUTATIONS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    let mut t = [0; 512];
+    let mut s = 0;
+    while s < 512 {
+        let mut p = 0;
+        while p < PERMUTATIONS.len() {
+            let (mut fits, mut m) = (true, 0);
+            let mut r = 0;
+            while r < 3 {
+                let k = PERMUTATIONS[p][r];
+                if s & 1 << (r * 3 + k) == 0 {
+                    fits = false;
+                }
+                m |= 7 << (r * 9 + k * 3);
+                r += 1;
+            }
+            if fits {
+                t[s] |= m;
+            }
+            p += 1;
+        }
+        s += 1;
+    }
+    t
+}
+
+#[derive(Copy, Clone)]
+struct State {
+    planes: Planes,
+    unsolved: Mask,
+    // Planes changed since their locked candidates were removed, where the
+    // bit `d * 4 + b` is of the number `d + 1` in the band `b`.
+    dirty: u64,
+}
+
+/// Progress of counting answers.
+struct Search {
+    random: bool,
+    limit: usize,
+    found: usize,
+    answer: Option<State>,
+}
+
+impl Default for BitParallelSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitParallelSolver {
+    /// Returns a new `BitParallelSolver` using the fastest instruction set
+    /// available.
+    pub fn new() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if let Some(k) = Avx2::new() {
+                return Self::with_isa(Isa::Avx2(k));
+            }
+            Self::with_isa(Isa::Sse2)
+        }
+        #[cfg(not(target_arch = "x86_64"))]
+        Self::with_isa(Isa::Scalar)
+    }
+
+    /// Returns a new `BitParallelSolver` that doesn't use SIMD instructions.
+    pub fn scalar() -> Self {
+        Self::with_isa(Isa::Scalar)
+    }
+
+    fn with_isa(isa: Isa) -> Self {
+        Self { isa, budget: None }
+    }
+
+    /// Returns the solver failing with `NumprError::BudgetExceeded` when
+    /// `budget` runs out.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Returns the name of the instruction set used: `"avx2"`, `"sse2"`, or
+    /// `"scalar"`.
+    pub fn instruction_set(&self) -> &'static str {
+        match self.isa {
+            Isa::Scalar => "scalar",
+            #[cfg(target_arch = "x86_64")]
+            Isa::Sse2 => "sse2",
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx2(_) => "avx2",
+        }
+    }
+
+    /// Returns the number of answers, counting up to `limit`.
+    ///
+    /// Unlike `solve`, it doesn't consume the solver so that it can be reused
+    /// for many puzzles.
+    pub fn count(&self, board: &Board, limit: usize) -> NumprResult<usize> {
+        Ok(self.run(board, false, limit)?.found)
+    }
+
+    fn run(&self, board: &Board, random: bool, limit: usize) -> NumprResult<Search> {
+        let mut search = Search {
+            random,
+            limit,
+            found: 0,
+            answer: None,
+        };
+        match self.isa {
+            Isa::Scalar => self.start(Scalar, board, &mut search)?,
+            #[cfg(target_arch = "x86_64")]
+            Isa::Sse2 => self.start(Sse2, board, &mut search)?,
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx2(k) => self.start(k, board, &mut search)?,
+        }
+        Ok(search)
+    }
+
+    fn start<K: Kernel>(&self, k: K, board: &Board, search: &mut Search) -> NumprResult<()> {
+        let mut s = State {
+            planes: [[BAND, BAND, BAND, 0]; 9],
+            unsolved: [BAND, BAND, BAND, 0],
+            // All the planes are checked once.
+            dirty: 0x7_7777_7777,
+        };
+        // Grids of each number given, where the first one is of empty grids.
+        let mut given = [[0u32; 3]; 10];
+        for (b, band) in board.numbers().chunks_exact(27).enumerate() {
+            for (i, &n) in band.iter().enumerate() {
+                given[n as usize][b] |= 1 << i;
+            }
+        }
+        for (d, given) in given[1..].iter().enumerate() {
+            for (b, &g) in given.iter().enumerate() {
+                if singles(g) != g {
+                    // Conflicting numbers in a row.
+                    return Ok(());
+                }
+                let rows = (0..3)
+                    .filter(|&r| g >> (r * 9) & ROW != 0)
+                    .fold(0, |m, r| m | ROW << (r * 9));
+                s.planes[d][b] = s.planes[d][b] & !rows | g;
+            }
+        }
+        if search.limit > 0 {
+            self.search(k, s, search)?;
+        }
+        Ok(())
+    }
+
+    /// Removes locked candidates and places singles until none is left. It
+    /// returns the counts of candidates of each grid, or `None` when the
+    /// board has no answer.
+    fn propagate<K: Kernel>(&self, k: K, s: &mut State) -> Option<[Mask; 3]> {
+        loop {
+            while s.dirty != 0 {
+                let i = s.dirty.trailing_zeros() as usize;
+                s.dirty &= s.dirty - 1;
+                let (d, b) = (i >> 2, i & 3);
+                let m = s.planes[d][b];
+                let shrink = SHRINK[(m & ROW) as usize]
+                    | SHRINK[(m >> 9 & ROW) as usize] << 3
+                    | SHRINK[(m >> 18) as usize] << 6;
+                let m = m & COMPLEX[shrink as usize];
+                if m == 0 {
+                    return None;
+                }
+                s.planes[d][b] = m;
+
+                // Rows having a single candidate newly found.
+                let cells = singles(m) & s.unsolved[b];
+                if cells == 0 {
+                    continue;
+                }
+                s.unsolved[b] &= !cells;
+                let mut changed = 0;
+                for e in 0..9 {
+                    let p = s.planes[e][b];
+                    changed |= ((p & cells != 0) as u64) << (e * 4 + b);
+                    s.planes[e][b] = p & !cells;
+                }
+                let cols = ((cells | cells >> 9 | cells >> 18) & ROW) * COLUMN;
+                for c in 0..3 {
+                    let p = s.planes[d][c];
+                    changed |= ((p & cols != 0) as u64) << (d * 4 + c);
+                    s.planes[d][c] = p & !cols;
+                }
+                s.planes[d][b] = m;
+                s.dirty |= changed & !(1 << i);
+            }
+
+            let counts = k.count(&s.planes);
+            if (0..3).any(|b| s.unsolved[b] & !counts[0][b] != 0) {
+                return None;
+            }
+            let mut found = false;
+            for (b, &twice) in counts[1].iter().enumerate().take(3) {
+                let mut cells = s.unsolved[b] & !twice;
+                while cells != 0 {
+                    let bit = cells & cells.wrapping_neg();
+                    cells &= cells - 1;
+                    match (0..9).find(|&d| s.planes[d][b] & bit != 0) {
+                        Some(d) => place(s, d, b, bit),
+                        // Another single took the last candidate.
+                        None => return None,
+                    }
+                    found = true;
+                }
+            }
+            if !found && !hidden_singles(s) {
+                return Some(counts);
+            }
+        }
+    }
+
+    fn search<K: Kernel>(&self, k: K, mut s: State, search: &mut Search) -> NumprResult<()> {
+        budget::spend(&self.budget)?;
+        let counts = match self.propagate(k, &mut s) {
+            Some(counts) => counts,
+            None => return Ok(()),
+        };
+        if s.unsolved == [0; 4] {
+            search.found += 1;
+            search.answer.get_or_insert(s);
+            return Ok(());
+        }
+
+        // A grid having two candidates, or the first empty one.
+        let pairs = [0, 1, 2].map(|b| s.unsolved[b] & !counts[2][b]);
+        let (b, m) = match (0..3).find(|&b| pairs[b] != 0) {
+            Some(b) => (b, pairs[b]),
+            None => {
+                let b = (0..3).find(|&b| s.unsolved[b] != 0).unwrap();
+                (b, s.unsolved[b])
+            }
+        };
+        let bit = m & m.wrapping_neg();
+
+        let mut candidates = [0; 9];
+        let mut n = 0;
+        for d in 0..9 {
+            if s.planes[d][b] & bit != 0 {
+                candidates[n] = d;
+                n += 1;
+            }
+        }
+        if search.random {
+            candidates[..n].shuffle(&mut rand::thread_rng());
+        }
+        for &d in &candidates[..n] {
+            let mut next = s;
+            place(&mut next, d, b, bit);
+            self.search(k, next, search)?;
+            if search.found >= search.limit {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Places the number `d + 1` at `bit` of the band `b` by removing the other
+/// candidates in the row. The other numbers and columns are updated by
+/// `propagate`.
+#[inline]
+fn place(s: &mut State, d: usize, b: usize, bit: u32) {
+    let row = ROW << (bit.trailing_zeros() / 9 * 9);
+    s.planes[d][b] = s.planes[d][b] & !row | bit;
+    s.dirty |= 1 << (d * 4 + b);
+}
+
+/// Places numbers having a single place in a column, which the rows of a band
+/// don't reveal. It returns `true` when any is placed.
+fn hidden_singles(s: &mut State) -> bool {
+    let mut found = false;
+    for d in 0..9 {
+        let p = s.planes[d];
+        let cols = [0, 1, 2].map(|b| (p[b] | p[b] >> 9 | p[b] >> 18) & ROW);
+        for b in 0..3 {
+            // Columns having candidates only in the band, in a single row.
+            let (r0, r1, r2) = (p[b] & ROW, p[b] >> 9 & ROW, p[b] >> 18);
+            let once = (r0 ^ r1 ^ r2) & !(r0 & r1 | r1 & r2 | r2 & r0);
+            let only = cols[b] & !cols[(b + 1) % 3] & !cols[(b + 2) % 3];
+            let mut cells = p[b] & ((once & only) * COLUMN) & s.unsolved[b];
+            while cells != 0 {
+                let bit = cells & cells.wrapping_neg();
+                cells &= cells - 1;
+                place(s, d, b, bit);
+                found = true;
+            }
+        }
+    }
+    found
+}
+
+/// Returns the candidates of rows having exactly one.
+#[inline]
+fn singles(m: u32) -> u32 {
+    SINGLE[(m & ROW) as usize] as u32
+        | (SINGLE[(m >> 9 & ROW) as usize] as u32) << 9
+        | (SINGLE[(m >> 18) as usize] as u32) << 18
+}
+
+impl Solver for BitParallelSolver {
+    fn solve(self, board: &Board, random: bool) -> NumprResult<Board> {
+        let s = match self.run(board, random, 1)?.answer {
+            Some(s) => s,
+            None => return NumprError::unsolvable(),
+        };
+        let mut numbers = [0; SIZE];
+        for (i, n) in numbers.iter_mut().enumerate() {
+            *n = (1..=9)
+                .find(|&d| s.planes[d as usize - 1][i / 27] & 1 << (i % 27) != 0)
+                .unwrap();
+        }
+        Board::new(&numbers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::NaiveGenerator;
+    use crate::pt::{Pt, PtIter};
+    use crate::solvers::HeuristicSolver;
+    use crate::uniques::NaiveUniquenessChecker;
+    use crate::Generator;
+
+    fn solvers() -> Vec<BitParallelSolver> {
+        let mut v = vec![BitParallelSolver::scalar()];
+        #[cfg(target_arch = "x86_64")]
+        {
+            v.push(BitParallelSolver::with_isa(Isa::Sse2));
+            if let Some(k) = Avx2::new() {
+                v.push(BitParallelSolver::with_isa(Isa::Avx2(k)));
+            }
+        }
+        v
+    }
+
+    #[test]
+    fn solve() {
+        for s in solvers() {
+            let b = s.solve(&Board::default(), true).unwrap();
+            b.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn count() {
+        let answer = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        let puzzle = NaiveGenerator::new(SIZE as u32)
+            .generate(&answer, HeuristicSolver::new, NaiveUniquenessChecker::new)
+            .unwrap();
+
+        // Removing numbers from the answer until it has multiple answers.
+        let mut multi = puzzle;
+        for pt in PtIter::all() {
+            if multi.get(pt).is_some() {
+                multi.set(pt, 0).unwrap();
+                if BitParallelSolver::new().count(&multi, 2).unwrap() == 2 {
+                    break;
+                }
+            }
+        }
+
+        for s in solvers() {
+            assert_eq!(s.count(&puzzle, 2).unwrap(), 1);
+            assert_eq!(s.count(&multi, 2).unwrap(), 2);
+            assert_eq!(s.count(&multi, 0).unwrap(), 0);
+            assert_eq!(s.count(&Board::default(), 100).unwrap(), 100);
+            let b = s.solve(&puzzle, false).unwrap();
+            assert!(b.iter().eq(answer.iter()));
+        }
+    }
+
+    #[test]
+    fn tables() {
+        assert_eq!(SHRINK[0b100_000_001], 0b101);
+        assert_eq!(COMPLEX[0b111_111_111], BAND);
+        assert_eq!(COMPLEX[0b111_111_000], 0);
+        // The first row only has candidates in the first block, so the other
+        // rows can't be placed there.
+        let m = COMPLEX[0b111_111_001];
+        assert_eq!(m & ROW, 0o7);
+        assert_eq!(m >> 9 & ROW, 0o770);
+        assert_eq!(m >> 18, 0o770);
+        assert_eq!(
+            singles(0b100_000_000 << 18 | 0b11 << 9 | 0b1000),
+            1 << 26 | 0b1000
+        );
+    }
+
+    #[test]
+    fn conflict() {
+        let mut b = Board::default();
+        b.set(Pt::new(0, 0).unwrap(), 1).unwrap();
+        b.set(Pt::new(1, 1).unwrap(), 1).unwrap();
+        for s in solvers() {
+            assert_eq!(s.count(&b, 2).unwrap(), 0);
+            assert!(s.solve(&b, false).is_err());
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Bitwise operations over the candidate planes of all the numbers.
+//!
+//! The solver spends most of its time in these operations, so they have SIMD
+//! implementations chosen at runtime in addition to the portable one.
+
+/// Grids of the three bands in the first three lanes. The n-th bit of a
+/// lane is the n-th grid of the band from its top left corner. The last lane
+/// is always `0`, so that a mask fills a 128-bit register.
+pub(super) type Mask = [u32; 4];
+
+/// Candidates of each number. The i-th plane is a mask of grids where
+/// `i + 1` can be placed.
+pub(super) type Planes = [Mask; 9];
+
+/// Operations over the planes.
+///
+/// A value of a kernel is a proof that the processor supports the
+/// instructions it uses, so the operations are safe to call once it's
+/// obtained.
+pub(super) trait Kernel: Copy {
+    /// Returns masks of grids having at least one, two, and three candidates.
+    fn count(self, planes: &Planes) -> [Mask; 3];
+}
+
+/// The portable implementation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) struct Scalar;
+
+impl Kernel for Scalar {
+    #[inline]
+    fn count(self, planes: &Planes) -> [Mask; 3] {
+        let mut r = [[0; 4]; 3];
+        for p in planes {
+            for b in 0..4 {
+                r[2][b] |= r[1][b] & p[b];
+                r[1][b] |= r[0][b] & p[b];
+                r[0][b] |= p[b];
+            }
+        }
+        r
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(super) use self::x86::{Avx2, Sse2};
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::{Kernel, Mask, Planes};
+    use std::arch::x86_64::*;
+
+    /// The implementation processing a plane at once. SSE2 is available on
+    /// all x86_64 processors.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub(in super::super) struct Sse2;
+
+    #[inline]
+    unsafe fn load(p: &Mask) -> __m128i {
+        _mm_loadu_si128(p.as_ptr() as *const __m128i)
+    }
+
+    #[inline]
+    unsafe fn store(p: &mut Mask, v: __m128i) {
+        _mm_storeu_si128(p.as_mut_ptr() as *mut __m128i, v)
+    }
+
+    impl Kernel for Sse2 {
+        #[inline]
+        fn count(self, planes: &Planes) -> [Mask; 3] {
+            let mut r = [[0; 4]; 3];
+            unsafe {
+                let (mut once, mut twice, mut thrice) = (
+                    _mm_setzero_si128(),
+                    _mm_setzero_si128(),
+                    _mm_setzero_si128(),
+                );
+                for p in planes {
+                    let p = load(p);
+                    thrice = _mm_or_si128(thrice, _mm_and_si128(twice, p));
+                    twice = _mm_or_si128(twice, _mm_and_si128(once, p));
+                    once = _mm_or_si128(once, p);
+                }
+                store(&mut r[0], once);
+                store(&mut r[1], twice);
+                store(&mut r[2], thrice);
+            }
+            r
+        }
+    }
+
+    /// The implementation processing two planes at once. It can only be
+    /// obtained by `new` on processors supporting AVX2.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub(in super::super) struct Avx2(());
+
+    impl Avx2 {
+        /// Returns the kernel when the processor supports AVX2.
+        pub(in super::super) fn new() -> Option<Self> {
+            if is_x86_feature_detected!("avx2") {
+                Some(Avx2(()))
+            } else {
+                None
+            }
+        }
+    }
+
+    impl Kernel for Avx2 {
+        #[inline]
+        fn count(self, planes: &Planes) -> [Mask; 3] {
+            // Safe because `self` is only made by `new` after detecting AVX2.
+            unsafe { avx2_count(planes) }
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn avx2_count(planes: &Planes) -> [Mask; 3] {
+        let (mut once, mut twice, mut thrice) = (
+            _mm256_setzero_si256(),
+            _mm256_setzero_si256(),
+            _mm256_setzero_si256(),
+        );
+        for pair in planes.chunks_exact(2) {
+            let p = _mm256_loadu_si256(pair.as_ptr() as *const __m256i);
+            thrice = _mm256_or_si256(thrice, _mm256_and_si256(twice, p));
+            twice = _mm256_or_si256(twice, _mm256_and_si256(once, p));
+            once = _mm256_or_si256(once, p);
+        }
+
+        // Merge the counts of even and odd planes, then add the last one.
+        let (o0, o1) = (
+            _mm256_castsi256_si128(once),
+            _mm256_extracti128_si256(once, 1),
+        );
+        let (t0, t1) = (
+            _mm256_castsi256_si128(twice),
+            _mm256_extracti128_si256(twice, 1),
+        );
+        let (h0, h1) = (
+            _mm256_castsi256_si128(thrice),
+            _mm256_extracti128_si256(thrice, 1),
+        );
+        let mut once = _mm_or_si128(o0, o1);
+        let mut twice = _mm_or_si128(_mm_or_si128(t0, t1), _mm_and_si128(o0, o1));
+        let mut thrice = _mm_or_si128(
+            _mm_or_si128(h0, h1),
+            _mm_or_si128(_mm_and_si128(t0, o1), _mm_and_si128(o0, t1)),
+        );
+        let p = load(&planes[8]);
+        thrice = _mm_or_si128(thrice, _mm_and_si128(twice, p));
+        twice = _mm_or_si128(twice, _mm_and_si128(once, p));
+        once = _mm_or_si128(once, p);
+
+        let mut r = [[0; 4]; 3];
+        store(&mut r[0], once);
+        store(&mut r[1], twice);
+        store(&mut r[2], thrice);
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    fn random_mask() -> Mask {
+        let mut rng = rand::thread_rng();
+        let mut m = [0; 4];
+        for b in m.iter_mut().take(3) {
+            *b = rng.gen::<u32>() & rng.gen::<u32>() & ((1 << 27) - 1);
+        }
+        m
+    }
+
+    fn random_planes() -> Planes {
+        let mut planes = [[0; 4]; 9];
+        for p in planes.iter_mut() {
+            *p = random_mask();
+        }
+        planes
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn same_as_scalar(k: impl Kernel) {
+        for _ in 0..100 {
+            let planes = random_planes();
+            assert_eq!(k.count(&planes), Scalar.count(&planes));
+        }
+    }
+
+    #[test]
+    fn scalar() {
+        let mut planes = [[0; 4]; 9];
+        planes[0] = [0b111, 0, 1, 0];
+        planes[1] = [0b110, 0, 1, 0];
+        planes[2] = [0b100, 0, 0, 0];
+        assert_eq!(
+            Scalar.count(&planes),
+            [[0b111, 0, 1, 0], [0b110, 0, 1, 0], [0b100, 0, 0, 0]]
+        );
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn simd() {
+        same_as_scalar(Sse2);
+        if let Some(k) = Avx2::new() {
+            same_as_scalar(k);
+        }
+    }
+}
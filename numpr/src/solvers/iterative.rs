@@ -0,0 +1,104 @@
+use crate::budget::Budget;
+use crate::chooser::Chooser;
+use crate::choosers::Mrv;
+use crate::engine::Engine;
+use crate::error::{NumprError, NumprResult};
+use crate::Board;
+use crate::Solver;
+
+/// IterativeSolver solves puzzles with the search [`Engine`](../engine/struct.Engine.html),
+/// which backtracks with an undo trail instead of recursion and board copies.
+///
+/// `C` is the [`Chooser`](../trait.Chooser.html) deciding the grid to branch
+/// on.
+pub struct IterativeSolver<C = Mrv> {
+    chooser: C,
+    budget: Option<Budget>,
+}
+
+impl Default for IterativeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IterativeSolver {
+    /// Returns a new `IterativeSolver` branching on grids having the fewest
+    /// candidates.
+    pub fn new() -> Self {
+        Self::with_chooser(Mrv::new())
+    }
+}
+
+impl<C: Chooser> IterativeSolver<C> {
+    /// Returns a new `IterativeSolver` using `chooser`.
+    pub fn with_chooser(chooser: C) -> Self {
+        Self {
+            chooser,
+            budget: None,
+        }
+    }
+
+    /// Returns the solver failing with `NumprError::BudgetExceeded` when
+    /// `budget` runs out.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+}
+
+impl<C: Chooser> Solver for IterativeSolver<C> {
+    fn solve(self, board: &Board, random: bool) -> NumprResult<Board> {
+        let e = match Engine::new(board, self.chooser) {
+            Ok(e) => e.shuffle(random),
+            Err(_) => return NumprError::unsolvable(),
+        };
+        let mut e = match self.budget {
+            Some(b) => e.with_budget(b),
+            None => e,
+        };
+        match e.next_answer()? {
+            Some(b) => Ok(b),
+            None => NumprError::unsolvable(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::choosers::{InOrder, Random};
+    use crate::generators::NaiveGenerator;
+    use crate::pt::Pt;
+    use crate::solvers::HeuristicSolver;
+    use crate::uniques::NaiveUniquenessChecker;
+    use crate::Generator;
+
+    #[test]
+    fn solve() {
+        let answer = IterativeSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        answer.validate().unwrap();
+        let puzzle = NaiveGenerator::new(crate::consts::SIZE as u32)
+            .generate(&answer, HeuristicSolver::new, NaiveUniquenessChecker::new)
+            .unwrap();
+
+        let results = vec![
+            IterativeSolver::new().solve(&puzzle, false),
+            IterativeSolver::with_chooser(InOrder::new()).solve(&puzzle, false),
+            IterativeSolver::with_chooser(Random::new()).solve(&puzzle, true),
+        ];
+        for b in results {
+            assert!(b.unwrap().iter().eq(answer.iter()));
+        }
+    }
+
+    #[test]
+    fn conflict() {
+        let mut b = Board::default();
+        b.set(Pt::new(0, 0).unwrap(), 1).unwrap();
+        b.set(Pt::new(1, 1).unwrap(), 1).unwrap();
+        assert!(IterativeSolver::new().solve(&b, false).is_err());
+    }
+}
@@ -1,10 +1,21 @@
+use crate::budget::{self, Budget};
 use crate::error::{NumprError, NumprResult};
 use crate::pt::Pt;
+use crate::stats::Collector;
 use crate::Board;
 use crate::Solver;
 
 /// Solves a puzzle with a simple brute-force approach.
-pub struct NaiveSolver {}
+///
+/// It doesn't use the search [`Engine`](../engine/struct.Engine.html), which
+/// always places singles first.
+///
+/// `C` is a [`Collector`](../stats/trait.Collector.html) receiving statistics
+/// of the search. It's `()` unless `with_stats` is called.
+pub struct NaiveSolver<C = ()> {
+    stats: C,
+    budget: Option<Budget>,
+}
 
 fn empty_grid((_, n): &(Pt, Option<u8>)) -> bool {
     n.is_none()
@@ -13,36 +24,63 @@ fn empty_grid((_, n): &(Pt, Option<u8>)) -> bool {
 impl NaiveSolver {
     /// Returns a new `NaiveSolver`.
     pub fn new() -> Self {
-        Self {}
+        Self {
+            stats: (),
+            budget: None,
+        }
     }
+}
 
-    fn recurse(&self, b: &mut Board, pt: Pt, random: bool) -> NumprResult<Board> {
+impl<C: Collector> NaiveSolver<C> {
+    /// Returns the solver reporting statistics of the search to `stats`.
+    pub fn with_stats<D: Collector>(self, stats: D) -> NaiveSolver<D> {
+        NaiveSolver {
+            stats,
+            budget: self.budget,
+        }
+    }
+
+    /// Returns the solver failing with `NumprError::BudgetExceeded` when
+    /// `budget` runs out.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    fn recurse(&mut self, b: &mut Board, pt: Pt, random: bool, depth: usize) -> NumprResult<Board> {
+        self.stats.node(depth);
+        budget::spend(&self.budget)?;
         // Note: precomputing pt and passing it as &[Pt] and [1..] was slower than the current code.
         let next = b.iter_after(pt).find(empty_grid);
 
         for n in b.candidates(pt, random) {
             b.set(pt, n).unwrap();
+            self.stats.guess();
             if next.is_none() {
                 return Ok(*b);
             }
 
-            let res = self.recurse(b, next.unwrap().0, random);
-            if res.is_ok() {
+            let res = self.recurse(b, next.unwrap().0, random, depth + 1);
+            if matches!(res, Ok(_) | Err(NumprError::BudgetExceeded)) {
                 return res;
             }
+            self.stats.backtrack();
         }
         b.set(pt, 0)?;
         return NumprError::unsolvable();
     }
 }
 
-impl Solver for NaiveSolver {
-    fn solve(self, board: &Board, random: bool) -> NumprResult<Board> {
+impl<C: Collector> Solver for NaiveSolver<C> {
+    fn solve(mut self, board: &Board, random: bool) -> NumprResult<Board> {
         let mut b = *board;
-        match &mut board.iter().find(empty_grid) {
-            Some((pt, _)) => self.recurse(&mut b, *pt, random),
+        self.stats.start();
+        let res = match &mut board.iter().find(empty_grid) {
+            Some((pt, _)) => self.recurse(&mut b, *pt, random, 0),
             None => Ok(*board),
-        }
+        };
+        self.stats.finish();
+        res
     }
 }
 
@@ -0,0 +1,286 @@
+use crate::budget::{self, Budget};
+use crate::constraints::common::*;
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::{Pt, PtIter};
+use crate::Board;
+use crate::Rules;
+use crate::Solver;
+use rand::prelude::*;
+
+/// VariantSolver solves puzzles having variant constraints in addition to the
+/// classic rules.
+///
+/// It propagates candidates with the constraints until nothing changes and
+/// then branches on the grid having the fewest candidates.
+pub struct VariantSolver {
+    rules: Rules,
+    budget: Option<Budget>,
+}
+
+#[derive(Copy, Clone)]
+struct State {
+    masks: [u16; SIZE],
+    // Grids whose number has already been removed from their peers.
+    done: u128,
+}
+
+impl VariantSolver {
+    /// Returns a solver enforcing `rules`.
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            rules,
+            budget: None,
+        }
+    }
+
+    /// Returns the solver failing with `NumprError::BudgetExceeded` when
+    /// `budget` runs out.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    fn init(&self, b: &Board) -> State {
+        let mut masks = [ALL; SIZE];
+        for (pt, n) in b.iter() {
+            if let Some(n) = n {
+                masks[pt.index()] = 1 << n;
+            }
+        }
+        State { masks, done: 0 }
+    }
+
+    fn propagate(&self, s: &mut State) -> NumprResult<()> {
+        loop {
+            let mut changed = false;
+            for i in 0..SIZE {
+                let m = s.masks[i];
+                if s.done & (1 << i) != 0 || m.count_ones() != 1 {
+                    continue;
+                }
+                s.done |= 1 << i;
+                changed = true;
+
+                let pt = Pt::new(i % WIDTH, i / WIDTH)?;
+                for p in PtIter::row(pt)
+                    .chain(PtIter::col(pt))
+                    .chain(PtIter::block(pt))
+                {
+                    if p != pt {
+                        restrict(&mut s.masks, p.index(), !m)?;
+                    }
+                }
+            }
+            changed |= self.rules.propagate(&mut s.masks)?;
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    fn recurse(&self, mut s: State, random: bool) -> NumprResult<Board> {
+        budget::spend(&self.budget)?;
+        self.propagate(&mut s)?;
+
+        let next = (0..SIZE)
+            .filter(|&i| s.masks[i].count_ones() > 1)
+            .min_by_key(|&i| s.masks[i].count_ones());
+        let i = match next {
+            Some(i) => i,
+            None => {
+                let mut b = Board::default();
+                for pt in PtIter::all() {
+                    b.set(pt, min(s.masks[pt.index()]))?;
+                }
+                if self.rules.validate(&b).is_err() {
+                    return NumprError::unsolvable();
+                }
+                return Ok(b);
+            }
+        };
+
+        let mut candidates: Vec<u8> = digits(s.masks[i]).collect();
+        if random {
+            candidates.shuffle(&mut rand::thread_rng());
+        }
+        for n in candidates {
+            let mut next = s;
+            next.masks[i] = 1 << n;
+            let res = self.recurse(next, random);
+            if matches!(res, Ok(_) | Err(NumprError::BudgetExceeded)) {
+                return res;
+            }
+        }
+        NumprError::unsolvable()
+    }
+}
+
+impl Solver for VariantSolver {
+    fn solve(self, board: &Board, random: bool) -> NumprResult<Board> {
+        let s = self.init(board);
+        self.recurse(s, random)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::*;
+    use crate::generators::NaiveGenerator;
+    use crate::solvers::HeuristicSolver;
+    use crate::uniques::NaiveUniquenessChecker;
+    use crate::{Generator, UniquenessChecker};
+
+    fn row(y: usize, xs: std::ops::Range<usize>) -> Vec<Pt> {
+        xs.map(|x| Pt::new(x, y).unwrap()).collect()
+    }
+
+    #[test]
+    fn classic() {
+        let b = VariantSolver::new(Rules::new())
+            .solve(&Board::default(), true)
+            .unwrap();
+        b.validate().unwrap();
+    }
+
+    #[test]
+    fn lines() {
+        let p = |x, y| Pt::new(x, y).unwrap();
+        let rules = Rules::new()
+            .with(Thermometer::new(row(0, 0..5)).unwrap())
+            .with(Arrow::new(Pt::new(0, 1).unwrap(), row(1, 1..4)).unwrap())
+            .with(Palindrome::new(vec![p(5, 2), p(6, 2), p(7, 3), p(8, 3)]).unwrap())
+            .with(GermanWhispers::new(row(3, 0..4)).unwrap())
+            .with(Renban::new(row(4, 4..9)).unwrap());
+
+        for _ in 0..5 {
+            let b = VariantSolver::new(rules.clone())
+                .solve(&Board::default(), true)
+                .unwrap();
+            rules.validate(&b).unwrap();
+        }
+    }
+
+    #[test]
+    fn unsolvable() {
+        // Nothing can be placed on a bulb when the next grid has 1.
+        let rules = Rules::new().with(Thermometer::new(row(1, 0..2)).unwrap());
+        let mut b = Board::default();
+        b.set(Pt::new(1, 1).unwrap(), 1).unwrap();
+        assert_eq!(
+            Err(NumprError::Unsolvable),
+            VariantSolver::new(rules).solve(&b, false).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn unique() {
+        let rules = Rules::new().with(Thermometer::new(row(0, 0..9)).unwrap());
+        let solution = VariantSolver::new(rules.clone())
+            .solve(&Board::default(), true)
+            .unwrap();
+
+        // The first row is fixed by the thermometer, so it doesn't need hints.
+        let mut b = solution;
+        for pt in row(0, 0..9) {
+            b.set(pt, 0).unwrap();
+        }
+        NaiveUniquenessChecker::new()
+            .check(&b, || VariantSolver::new(rules.clone()))
+            .unwrap();
+    }
+
+    #[test]
+    fn dots() {
+        let answer = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+
+        // Put Kropki dots and XV markers everywhere they fit.
+        let mut kropki = Kropki::new().negative(true);
+        let mut xv = XV::new().negative(true);
+        for pt in PtIter::all() {
+            for q in Pt::new(pt.x() + 1, pt.y())
+                .into_iter()
+                .chain(Pt::new(pt.x(), pt.y() + 1))
+            {
+                let (a, b) = (answer.raw_get(pt), answer.raw_get(q));
+                if a + 1 == b || b + 1 == a {
+                    kropki = kropki.white(pt, q).unwrap();
+                } else if a * 2 == b || b * 2 == a {
+                    kropki = kropki.black(pt, q).unwrap();
+                }
+                if a + b == 10 {
+                    xv = xv.x(pt, q).unwrap();
+                } else if a + b == 5 {
+                    xv = xv.v(pt, q).unwrap();
+                }
+            }
+        }
+        let rules = Rules::new().with(kropki).with(xv);
+        rules.validate(&answer).unwrap();
+
+        let puzzle = NaiveGenerator::new(40)
+            .generate(
+                &answer,
+                || VariantSolver::new(rules.clone()),
+                NaiveUniquenessChecker::new,
+            )
+            .unwrap();
+        NaiveUniquenessChecker::new()
+            .check(&puzzle, || VariantSolver::new(rules.clone()))
+            .unwrap();
+        let b = VariantSolver::new(rules.clone())
+            .solve(&puzzle, false)
+            .unwrap();
+        assert!(answer.iter().eq(b.iter()));
+    }
+
+    #[test]
+    fn outside() {
+        let answer = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+
+        // Give clues computed from the answer.
+        let mut rules = Rules::new();
+        for i in 0..9 {
+            let row = Outside::new(Side::Left, i).unwrap();
+            let cells = row.cells();
+            let crusts: Vec<usize> = (0..9)
+                .filter(|&k| answer.raw_get(cells[k]) % 8 == 1)
+                .collect();
+            let sum = cells[crusts[0] + 1..crusts[1]]
+                .iter()
+                .map(|&p| answer.raw_get(p))
+                .sum();
+            rules.push(Sandwich::new(row, sum).unwrap());
+
+            let col = Outside::new(Side::Top, i).unwrap();
+            let mut highest = 0;
+            let mut count = 0;
+            for p in col.cells() {
+                if answer.raw_get(p) > highest {
+                    highest = answer.raw_get(p);
+                    count += 1;
+                }
+            }
+            rules.push(Skyscraper::new(col, count).unwrap());
+        }
+        let corner = Outside::new(Side::Top, 0).unwrap();
+        let sum = PtIter::all()
+            .filter(|p| p.x() == p.y())
+            .map(|p| answer.raw_get(p))
+            .sum();
+        rules.push(LittleKiller::new(corner, Diagonal::DownRight, sum).unwrap());
+        rules.validate(&answer).unwrap();
+
+        let mut b = answer;
+        for p in PtIter::all().filter(|p| p.y() < 6) {
+            b.set(p, 0).unwrap();
+        }
+        let b = VariantSolver::new(rules.clone()).solve(&b, true).unwrap();
+        rules.validate(&b).unwrap();
+    }
+}
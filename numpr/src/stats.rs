@@ -0,0 +1,183 @@
+//! Statistics of searches done by solvers.
+//!
+//! Solvers supporting statistics report events to a [`Collector`]. The
+//! default collector `()` ignores all of them, so solvers without statistics
+//! run as fast as before. [`Stats`] records them, which is also a cheap proxy
+//! for difficulty of puzzles.
+//!
+//! [`Collector`]: trait.Collector.html
+//! [`Stats`]: struct.Stats.html
+//!
+//! # Examples
+//!
+//! ```
+//! use numpr::stats::Stats;
+//! use numpr::{solvers, Board, Solver};
+//!
+//! let mut stats = Stats::default();
+//! solvers::HeuristicSolver::new()
+//!     .with_stats(&mut stats)
+//!     .solve(&Board::default(), false)
+//!     .unwrap();
+//! assert!(stats.nodes > 0);
+//! ```
+
+use std::time::{Duration, Instant};
+
+/// A trait to receive events of a search.
+///
+/// All the methods do nothing by default.
+pub trait Collector {
+    /// Called when the search starts.
+    fn start(&mut self) {}
+
+    /// Called when the search finishes, successfully or not.
+    fn finish(&mut self) {}
+
+    /// Called when the search visits a node at `depth`. The root is at depth 0.
+    fn node(&mut self, _depth: usize) {}
+
+    /// Called when propagation places a number without guessing.
+    fn forced(&mut self) {}
+
+    /// Called when the search tries one of candidates of a grid.
+    fn guess(&mut self) {}
+
+    /// Called when a guess turns out to be wrong.
+    fn backtrack(&mut self) {}
+}
+
+/// The collector ignoring all the events.
+impl Collector for () {}
+
+impl<C: Collector + ?Sized> Collector for &mut C {
+    fn start(&mut self) {
+        (**self).start()
+    }
+
+    fn finish(&mut self) {
+        (**self).finish()
+    }
+
+    fn node(&mut self, depth: usize) {
+        (**self).node(depth)
+    }
+
+    fn forced(&mut self) {
+        (**self).forced()
+    }
+
+    fn guess(&mut self) {
+        (**self).guess()
+    }
+
+    fn backtrack(&mut self) {
+        (**self).backtrack()
+    }
+}
+
+/// Statistics of searches.
+///
+/// Values are accumulated when the same `Stats` is used for multiple searches.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    /// The number of nodes visited.
+    pub nodes: u64,
+    /// The number of wrong guesses undone.
+    pub backtracks: u64,
+    /// The maximum depth of nodes visited.
+    pub max_depth: usize,
+    /// The number of numbers placed by propagation.
+    pub forced: u64,
+    /// The number of guesses made.
+    pub guesses: u64,
+    /// Time spent on searches.
+    pub elapsed: Duration,
+    started: Option<Instant>,
+}
+
+impl Stats {
+    /// Returns new empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Collector for Stats {
+    fn start(&mut self) {
+        self.started = Some(Instant::now());
+    }
+
+    fn finish(&mut self) {
+        if let Some(t) = self.started.take() {
+            self.elapsed += t.elapsed();
+        }
+    }
+
+    fn node(&mut self, depth: usize) {
+        self.nodes += 1;
+        self.max_depth = self.max_depth.max(depth);
+    }
+
+    fn forced(&mut self) {
+        self.forced += 1;
+    }
+
+    fn guess(&mut self) {
+        self.guesses += 1;
+    }
+
+    fn backtrack(&mut self) {
+        self.backtracks += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::fixtures;
+    use crate::solvers::{HeuristicSolver, NaiveSolver};
+    use crate::Solver;
+
+    fn puzzle() -> Board {
+        fixtures::board(fixtures::MEDIUM)
+    }
+
+    #[test]
+    fn heuristic() {
+        let mut stats = Stats::new();
+        HeuristicSolver::new()
+            .with_stats(&mut stats)
+            .solve(&puzzle(), false)
+            .unwrap();
+        assert!(stats.nodes > 0);
+        assert!(stats.forced > 0);
+        assert!(stats.guesses >= stats.backtracks);
+        assert!(stats.max_depth < stats.nodes as usize);
+        assert!(stats.started.is_none());
+
+        // Statistics are accumulated.
+        let nodes = stats.nodes;
+        HeuristicSolver::new()
+            .with_stats(&mut stats)
+            .solve(&puzzle(), false)
+            .unwrap();
+        assert_eq!(stats.nodes, nodes * 2);
+    }
+
+    #[test]
+    fn naive() {
+        let b = puzzle();
+        let mut stats = Stats::new();
+        NaiveSolver::new()
+            .with_stats(&mut stats)
+            .solve(&b, false)
+            .unwrap();
+        let empty = b.iter().filter(|(_, n)| n.is_none()).count();
+        assert_eq!(stats.forced, 0);
+        assert_eq!(stats.max_depth, empty - 1);
+        assert_eq!(stats.guesses - stats.backtracks, empty as u64);
+        assert!(stats.nodes >= empty as u64);
+    }
+}
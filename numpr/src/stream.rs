@@ -0,0 +1,230 @@
+//! An endless stream of puzzles generated in background threads.
+//!
+//! # Examples
+//!
+//! ```
+//! use numpr::stream::PuzzleStream;
+//!
+//! let puzzles: Vec<_> = PuzzleStream::builder().workers(2).spawn().take(3).collect();
+//! for p in puzzles {
+//!     assert!(p.solution.validate().is_ok());
+//!     assert!(p.clues < 81);
+//! }
+//! ```
+
+use crate::board::Board;
+use crate::consts::SIZE;
+use crate::generator::Generator;
+use crate::generators::NaiveGenerator;
+use crate::placer::Placer;
+use crate::placers::PermutationPlacer;
+use crate::rating::{self, Difficulty};
+use crate::solvers::{BitBoardSolver, HeuristicSolver};
+use crate::uniques::NaiveUniquenessChecker;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// A puzzle generated by [`PuzzleStream`](struct.PuzzleStream.html).
+#[derive(Copy, Clone)]
+pub struct GeneratedPuzzle {
+    /// The puzzle having a unique answer.
+    pub puzzle: Board,
+    /// The answer of the puzzle.
+    pub solution: Board,
+    /// The number of grids filled in the puzzle.
+    pub clues: usize,
+    /// The difficulty of the puzzle.
+    pub difficulty: Difficulty,
+}
+
+/// A builder of [`PuzzleStream`](struct.PuzzleStream.html).
+pub struct Builder {
+    workers: usize,
+    buffer: usize,
+    max_empty_grid: u32,
+    difficulty: Option<Difficulty>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            workers: thread::available_parallelism().map_or(1, |n| n.get()),
+            buffer: 16,
+            max_empty_grid: SIZE as u32,
+            difficulty: None,
+        }
+    }
+}
+
+impl Builder {
+    /// Sets the number of worker threads. It defaults to the number of
+    /// available processors.
+    pub fn workers(mut self, n: usize) -> Self {
+        self.workers = n.max(1);
+        self
+    }
+
+    /// Sets the number of puzzles generated ahead of consumption. Workers wait
+    /// when this many puzzles are not consumed yet. It defaults to 16.
+    pub fn buffer(mut self, n: usize) -> Self {
+        self.buffer = n;
+        self
+    }
+
+    /// Sets the maximum number of empty grids in a puzzle. All the grids that
+    /// can be removed are removed by default.
+    pub fn max_empty_grid(mut self, n: u32) -> Self {
+        self.max_empty_grid = n;
+        self
+    }
+
+    /// Only yields puzzles having `difficulty`.
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    /// Starts worker threads and returns the stream of their puzzles.
+    pub fn spawn(self) -> PuzzleStream {
+        let (tx, rx) = mpsc::sync_channel(self.buffer);
+        let stop = Arc::new(AtomicBool::new(false));
+        let workers = (0..self.workers)
+            .map(|_| {
+                let tx = tx.clone();
+                let stop = stop.clone();
+                let (max, difficulty) = (self.max_empty_grid, self.difficulty);
+                thread::spawn(move || work(tx, &stop, max, difficulty))
+            })
+            .collect();
+        PuzzleStream {
+            rx: Some(rx),
+            stop,
+            workers,
+        }
+    }
+}
+
+fn generate(max_empty_grid: u32) -> Option<GeneratedPuzzle> {
+    let solution = PermutationPlacer::new()
+        .place(HeuristicSolver::new())
+        .ok()?;
+    let puzzle = NaiveGenerator::new(max_empty_grid)
+        .generate(&solution, BitBoardSolver::new, NaiveUniquenessChecker::new)
+        .ok()?;
+    Some(GeneratedPuzzle {
+        puzzle,
+        solution,
+        clues: puzzle.iter().filter(|(_, n)| n.is_some()).count(),
+        difficulty: rating::rate(&puzzle).ok()?,
+    })
+}
+
+fn work(
+    tx: SyncSender<GeneratedPuzzle>,
+    stop: &AtomicBool,
+    max_empty_grid: u32,
+    difficulty: Option<Difficulty>,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        let p = match generate(max_empty_grid) {
+            Some(p) => p,
+            None => continue,
+        };
+        let wanted = difficulty.is_none() || difficulty == Some(p.difficulty);
+        if wanted && tx.send(p).is_err() {
+            // The stream was dropped.
+            return;
+        }
+    }
+}
+
+/// An endless iterator of puzzles generated by worker threads.
+///
+/// Each puzzle has a unique answer and no more grid can be removed from it
+/// unless `max_empty_grid` is set. Dropping the stream stops the workers.
+pub struct PuzzleStream {
+    rx: Option<Receiver<GeneratedPuzzle>>,
+    stop: Arc<AtomicBool>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl PuzzleStream {
+    /// Returns a builder to configure the stream.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns a stream using the default configuration.
+    pub fn new() -> Self {
+        Self::builder().spawn()
+    }
+}
+
+impl Default for PuzzleStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for PuzzleStream {
+    type Item = GeneratedPuzzle;
+
+    fn next(&mut self) -> Option<GeneratedPuzzle> {
+        self.rx.as_ref()?.recv().ok()
+    }
+}
+
+impl Drop for PuzzleStream {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Unblocks workers waiting to send puzzles.
+        self.rx.take();
+        for w in self.workers.drain(..) {
+            let _ = w.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use crate::unique::UniquenessChecker;
+
+    #[test]
+    fn stream() {
+        let puzzles: Vec<_> = PuzzleStream::builder()
+            .workers(3)
+            .buffer(0)
+            .spawn()
+            .take(5)
+            .collect();
+        assert_eq!(puzzles.len(), 5);
+        for p in puzzles {
+            NaiveUniquenessChecker::new()
+                .check(&p.puzzle, HeuristicSolver::new)
+                .unwrap();
+            let answer = HeuristicSolver::new().solve(&p.puzzle, false).unwrap();
+            assert!(answer.iter().eq(p.solution.iter()));
+            assert_eq!(
+                p.clues,
+                SIZE - p.puzzle.iter().filter(|(_, n)| n.is_none()).count()
+            );
+            assert_eq!(rating::rate(&p.puzzle).unwrap(), p.difficulty);
+        }
+    }
+
+    #[test]
+    fn options() {
+        let mut s = PuzzleStream::builder()
+            .workers(1)
+            .max_empty_grid(20)
+            .difficulty(Difficulty::Easy)
+            .spawn();
+        let p = s.next().unwrap();
+        assert_eq!(p.clues, SIZE - 20);
+        assert_eq!(p.difficulty, Difficulty::Easy);
+    }
+}
@@ -0,0 +1,440 @@
+use crate::board::Board;
+use crate::consts::*;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use rand::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// A transformation of a board preserving validity of the answer.
+///
+/// A transformation optionally transposes the board, then permutes rows,
+/// columns, and numbers. Rows are only permuted within bands and bands are
+/// permuted as a whole, and so are columns and stacks.
+///
+/// Transformations are composed with [`then`](#method.then) and reverted with
+/// [`inverse`](#method.inverse). They are serialized by `Display` and
+/// deserialized by `FromStr`.
+///
+/// # Examples
+///
+/// ```
+/// use numpr::{solvers, Board, Pt, Solver, Transform};
+///
+/// # use numpr::NumprResult;
+/// # fn main() -> NumprResult<()> {
+/// let b = solvers::HeuristicSolver::new().solve(&Board::default(), true)?;
+/// let t = Transform::rotate(1)
+///     .then(&Transform::swap_bands(0, 2)?)
+///     .then(&Transform::permute_digits([9, 8, 7, 6, 5, 4, 3, 2, 1])?);
+/// let disguised = t.apply(&b);
+/// assert!(disguised.validate().is_ok());
+///
+/// let s = t.to_string();
+/// let u: Transform = s.parse()?;
+/// assert!(u.inverse().apply(&disguised).iter().eq(b.iter()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Transform {
+    transpose: bool,
+    /// The `i`-th row of the result comes from `rows[i]`-th row.
+    rows: [u8; 9],
+    /// The `i`-th column of the result comes from `cols[i]`-th column.
+    cols: [u8; 9],
+    /// Number `n` is replaced with `digits[n]`. `digits[0]` is always 0.
+    digits: [u8; 10],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    /// Returns the transformation that doesn't change anything.
+    pub fn identity() -> Self {
+        Self {
+            transpose: false,
+            rows: IDENTITY,
+            cols: IDENTITY,
+            digits: DIGITS,
+        }
+    }
+
+    /// Rotates the board clockwise by `quarter_turns` times 90 degrees.
+    pub fn rotate(quarter_turns: u32) -> Self {
+        let quarter = Self::from_parts(true, IDENTITY, REVERSE, DIGITS);
+        (0..quarter_turns % 4).fold(Self::identity(), |t, _| t.then(&quarter))
+    }
+
+    /// Flips the board left and right.
+    pub fn flip_horizontal() -> Self {
+        Self::from_parts(false, IDENTITY, REVERSE, DIGITS)
+    }
+
+    /// Flips the board top and bottom.
+    pub fn flip_vertical() -> Self {
+        Self::from_parts(false, REVERSE, IDENTITY, DIGITS)
+    }
+
+    /// Flips the board along the diagonal from the top left corner.
+    pub fn transpose() -> Self {
+        Self::from_parts(true, IDENTITY, IDENTITY, DIGITS)
+    }
+
+    /// Replaces number `n` with `digits[n - 1]`.
+    ///
+    /// It fails with `NumprError::InvalidTransform` when `digits` isn't a
+    /// permutation of 1 to 9.
+    pub fn permute_digits(digits: [u8; 9]) -> NumprResult<Self> {
+        let mut d = DIGITS;
+        d[1..].copy_from_slice(&digits);
+        check_digits(&d)?;
+        Ok(Self::from_parts(false, IDENTITY, IDENTITY, d))
+    }
+
+    /// Swaps the `a`-th and `b`-th rows in the same band.
+    ///
+    /// It fails with `NumprError::InvalidTransform` when they are in
+    /// different bands.
+    pub fn swap_rows(a: usize, b: usize) -> NumprResult<Self> {
+        Ok(Self::from_parts(false, swap(a, b, 1)?, IDENTITY, DIGITS))
+    }
+
+    /// Swaps the `a`-th and `b`-th columns in the same stack.
+    ///
+    /// It fails with `NumprError::InvalidTransform` when they are in
+    /// different stacks.
+    pub fn swap_cols(a: usize, b: usize) -> NumprResult<Self> {
+        Ok(Self::from_parts(false, IDENTITY, swap(a, b, 1)?, DIGITS))
+    }
+
+    /// Swaps the `a`-th and `b`-th bands, which are 0 to 2 from the top.
+    pub fn swap_bands(a: usize, b: usize) -> NumprResult<Self> {
+        Ok(Self::from_parts(false, swap(a, b, 3)?, IDENTITY, DIGITS))
+    }
+
+    /// Swaps the `a`-th and `b`-th stacks, which are 0 to 2 from the left.
+    pub fn swap_stacks(a: usize, b: usize) -> NumprResult<Self> {
+        Ok(Self::from_parts(false, IDENTITY, swap(a, b, 3)?, DIGITS))
+    }
+
+    /// Returns a random transformation.
+    pub fn random() -> Self {
+        let mut rng = rand::thread_rng();
+        let mut perm = || {
+            let mut p = [0; 9];
+            let mut bands = [0u8, 1, 2];
+            bands.shuffle(&mut rng);
+            for (b, &band) in bands.iter().enumerate() {
+                let mut r = [0u8, 1, 2];
+                r.shuffle(&mut rng);
+                for j in 0..3 {
+                    p[b * 3 + j] = band * 3 + r[j];
+                }
+            }
+            p
+        };
+        let (rows, cols) = (perm(), perm());
+        let mut digits = DIGITS;
+        digits[1..].shuffle(&mut rng);
+        Self::from_parts(rand::random(), rows, cols, digits)
+    }
+
+    pub(crate) fn from_parts(
+        transpose: bool,
+        rows: [u8; 9],
+        cols: [u8; 9],
+        digits: [u8; 10],
+    ) -> Self {
+        Self {
+            transpose,
+            rows,
+            cols,
+            digits,
+        }
+    }
+
+    /// Returns the transformed board.
+    pub fn apply(&self, board: &Board) -> Board {
+        let mut b = Board::default();
+        for r in 0..HEIGHT {
+            for c in 0..WIDTH {
+                let (y, x) = self.source(r, c);
+                let n = board.raw_get(Pt::new(x, y).unwrap());
+                b.set(Pt::new(c, r).unwrap(), self.digits[n as usize])
+                    .unwrap();
+            }
+        }
+        b
+    }
+
+    /// Returns the transformation applying `self` and then `next`.
+    pub fn then(&self, next: &Transform) -> Transform {
+        let compose = |a: &[u8; 9], b: &[u8; 9]| {
+            let mut p = [0; 9];
+            for i in 0..9 {
+                p[i] = a[b[i] as usize];
+            }
+            p
+        };
+        let (rows, cols) = if next.transpose {
+            (
+                compose(&self.cols, &next.rows),
+                compose(&self.rows, &next.cols),
+            )
+        } else {
+            (
+                compose(&self.rows, &next.rows),
+                compose(&self.cols, &next.cols),
+            )
+        };
+        let mut digits = [0; 10];
+        for (n, d) in digits.iter_mut().enumerate() {
+            *d = next.digits[self.digits[n] as usize];
+        }
+        Transform {
+            transpose: self.transpose ^ next.transpose,
+            rows,
+            cols,
+            digits,
+        }
+    }
+
+    /// Returns the transformation reverting `self`.
+    pub fn inverse(&self) -> Transform {
+        let invert = |p: &[u8; 9]| {
+            let mut q = [0; 9];
+            for (i, &v) in p.iter().enumerate() {
+                q[v as usize] = i as u8;
+            }
+            q
+        };
+        let (rows, cols) = if self.transpose {
+            (invert(&self.cols), invert(&self.rows))
+        } else {
+            (invert(&self.rows), invert(&self.cols))
+        };
+        let mut digits = [0; 10];
+        for (n, &d) in self.digits.iter().enumerate() {
+            digits[d as usize] = n as u8;
+        }
+        Transform {
+            transpose: self.transpose,
+            rows,
+            cols,
+            digits,
+        }
+    }
+
+    /// Returns `(row, column)` of the original board moved to `(r, c)`.
+    fn source(&self, r: usize, c: usize) -> (usize, usize) {
+        let (r, c) = (self.rows[r] as usize, self.cols[c] as usize);
+        if self.transpose {
+            (c, r)
+        } else {
+            (r, c)
+        }
+    }
+}
+
+impl fmt::Display for Transform {
+    /// Formats as `{transpose}-{rows}-{cols}-{digits}`, for example
+    /// `0-012345678-012345678-123456789` for the identity.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |p: &[u8]| -> String { p.iter().map(|&d| (b'0' + d) as char).collect() };
+        write!(
+            f,
+            "{}-{}-{}-{}",
+            self.transpose as u8,
+            digits(&self.rows),
+            digits(&self.cols),
+            digits(&self.digits[1..])
+        )
+    }
+}
+
+impl FromStr for Transform {
+    type Err = NumprError;
+
+    fn from_str(s: &str) -> NumprResult<Self> {
+        let invalid = || NumprError::invalid_transform(s);
+        let parts: Vec<Vec<u8>> = s
+            .trim()
+            .split('-')
+            .map(|p| p.bytes().map(|b| b.wrapping_sub(b'0')).collect())
+            .collect();
+        let (t, rows, cols, digits) = match parts.as_slice() {
+            [t, r, c, d] if t.len() == 1 && r.len() == 9 && c.len() == 9 && d.len() == 9 => {
+                (t[0], r, c, d)
+            }
+            _ => return invalid(),
+        };
+        let mut p = [[0; 9]; 2];
+        p[0].copy_from_slice(rows);
+        p[1].copy_from_slice(cols);
+        let mut d = DIGITS;
+        d[1..].copy_from_slice(digits);
+        if t > 1 || !p.iter().all(keeps_bands) || check_digits(&d).is_err() {
+            return invalid();
+        }
+        Ok(Self::from_parts(t == 1, p[0], p[1], d))
+    }
+}
+
+const IDENTITY: [u8; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+const REVERSE: [u8; 9] = [8, 7, 6, 5, 4, 3, 2, 1, 0];
+const DIGITS: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+/// Returns the permutation swapping `a` and `b`, which are indices of groups
+/// of `width` rows or columns.
+fn swap(a: usize, b: usize, width: usize) -> NumprResult<[u8; 9]> {
+    let n = 9 / width;
+    if a >= n || b >= n {
+        return NumprError::invalid_transform(format!("{} or {} is out of 0..{}", a, b, n));
+    }
+    if width == 1 && a / BLOCK_HEIGHT != b / BLOCK_HEIGHT {
+        return NumprError::invalid_transform(format!("{} and {} are in different blocks", a, b));
+    }
+    let mut p = IDENTITY;
+    for j in 0..width {
+        p.swap(a * width + j, b * width + j);
+    }
+    Ok(p)
+}
+
+/// Returns `true` when `p` is a permutation keeping bands or stacks.
+fn keeps_bands(p: &[u8; 9]) -> bool {
+    let mut seen = 0u16;
+    for (i, &v) in p.iter().enumerate() {
+        if v > 8 || seen & 1 << v != 0 || v / 3 != p[i / 3 * 3] / 3 {
+            return false;
+        }
+        seen |= 1 << v;
+    }
+    true
+}
+
+fn check_digits(d: &[u8; 10]) -> NumprResult<()> {
+    let mut seen = 0u16;
+    for (n, &v) in d.iter().enumerate().skip(1) {
+        if v == 0 || v > 9 || seen & 1 << v != 0 {
+            return NumprError::invalid_transform(format!("{} can't be replaced with {}", n, v));
+        }
+        seen |= 1 << v;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use crate::solvers::HeuristicSolver;
+
+    fn answer() -> Board {
+        HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap()
+    }
+
+    fn transform() -> Transform {
+        Transform::from_parts(
+            true,
+            [5, 3, 4, 8, 7, 6, 0, 2, 1],
+            [0, 1, 2, 6, 7, 8, 4, 5, 3],
+            [0, 2, 3, 1, 4, 9, 8, 7, 6, 5],
+        )
+    }
+
+    #[test]
+    fn apply() {
+        let b = answer();
+        let t = transform();
+        let a = t.apply(&b);
+        assert!(a.validate().is_ok());
+        // (2, 0) in the result comes from row cols[2] = 2, column rows[0] = 5.
+        let n = b.get(Pt::new(5, 2).unwrap()).unwrap();
+        assert_eq!(a.get(Pt::new(2, 0).unwrap()), Some(t.digits[n as usize]));
+    }
+
+    #[test]
+    fn compose() {
+        let b = answer();
+        let t = transform();
+        let u = Transform::from_parts(false, [2, 1, 0, 3, 4, 5, 6, 7, 8], IDENTITY, t.digits);
+        assert!(t.then(&u).apply(&b).iter().eq(u.apply(&t.apply(&b)).iter()));
+        assert!(u.then(&t).apply(&b).iter().eq(t.apply(&u.apply(&b)).iter()));
+        assert_eq!(t.then(&t.inverse()), Transform::identity());
+        assert_eq!(u.inverse().then(&u), Transform::identity());
+        assert!(t.inverse().apply(&t.apply(&b)).iter().eq(b.iter()));
+    }
+
+    #[test]
+    fn constructors() {
+        let b = answer();
+        let at = |b: &Board, x, y| b.get(Pt::new(x, y).unwrap());
+        assert!(at(&Transform::rotate(1).apply(&b), 8, 0) == at(&b, 0, 0));
+        assert!(at(&Transform::rotate(2).apply(&b), 8, 8) == at(&b, 0, 0));
+        assert!(at(&Transform::rotate(3).apply(&b), 0, 8) == at(&b, 0, 0));
+        assert_eq!(Transform::rotate(4), Transform::identity());
+        assert_eq!(
+            Transform::rotate(2),
+            Transform::flip_horizontal().then(&Transform::flip_vertical())
+        );
+        assert!(at(&Transform::transpose().apply(&b), 1, 0) == at(&b, 0, 1));
+
+        assert!(at(&Transform::swap_rows(3, 5).unwrap().apply(&b), 0, 3) == at(&b, 0, 5));
+        assert!(at(&Transform::swap_cols(7, 6).unwrap().apply(&b), 6, 0) == at(&b, 7, 0));
+        assert!(at(&Transform::swap_bands(0, 2).unwrap().apply(&b), 0, 7) == at(&b, 0, 1));
+        assert!(at(&Transform::swap_stacks(1, 0).unwrap().apply(&b), 4, 0) == at(&b, 1, 0));
+        assert!(Transform::swap_rows(2, 3).is_err());
+        assert!(Transform::swap_cols(0, 9).is_err());
+        assert!(Transform::swap_bands(0, 3).is_err());
+
+        let t = Transform::permute_digits([2, 3, 4, 5, 6, 7, 8, 9, 1]).unwrap();
+        assert_eq!(
+            t.apply(&b).get(Pt::new(0, 0).unwrap()),
+            at(&b, 0, 0).map(|n| n % 9 + 1)
+        );
+        assert!(Transform::permute_digits([1, 1, 2, 3, 4, 5, 6, 7, 8]).is_err());
+        assert!(Transform::permute_digits([0, 1, 2, 3, 4, 5, 6, 7, 8]).is_err());
+
+        for _ in 0..10 {
+            let t = Transform::random();
+            assert!(t.apply(&b).validate().is_ok());
+            assert_eq!(t.then(&t.inverse()), Transform::identity());
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        assert_eq!(
+            Transform::identity().to_string(),
+            "0-012345678-012345678-123456789"
+        );
+        let t = transform();
+        assert_eq!(t.to_string().parse::<Transform>(), Ok(t));
+        let u = Transform::random();
+        assert_eq!(u.to_string().parse::<Transform>(), Ok(u));
+
+        for s in &[
+            "",
+            "2-012345678-012345678-123456789",
+            "0-312045678-012345678-123456789",
+            "0-012345678-012345678-123456788",
+            "0-012345678-01234567-123456789",
+            "0-012345678-012345678-023456789",
+            "0-012345678-012345678-123456789-0",
+        ] {
+            assert!(
+                matches!(s.parse::<Transform>(), Err(NumprError::InvalidTransform(_))),
+                "{}",
+                s
+            );
+        }
+    }
+}
@@ -11,6 +11,8 @@ pub trait UniquenessChecker {
     ///
     /// It returns [`NumprError`](enum.NumprError) when the puzzle has multiple
     /// solutions or doesn't have any solution.
+    /// `NumprError::BudgetExceeded` returned by a solver must be returned as is
+    /// because the result is unknown then.
     ///
     /// # Examples
     ///
@@ -1,3 +1,7 @@
 mod naive;
+#[cfg(feature = "parallel")]
+mod parallel;
 
 pub use naive::NaiveUniquenessChecker;
+#[cfg(feature = "parallel")]
+pub use parallel::ParallelUniquenessChecker;
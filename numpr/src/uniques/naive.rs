@@ -1,15 +1,27 @@
 use crate::board::Board;
+use crate::budget::Budget;
 use crate::error::{NumprError, NumprResult};
 use crate::solver::Solver;
 use crate::unique::UniquenessChecker;
 
 /// Checks if the given puzzle has a unique solution by putting all possible
 /// candidates.
-pub struct NaiveUniquenessChecker {}
+pub struct NaiveUniquenessChecker {
+    budget: Option<Budget>,
+}
 
 impl NaiveUniquenessChecker {
     pub fn new() -> Self {
-        Self {}
+        Self { budget: None }
+    }
+
+    /// Returns the checker failing with `NumprError::BudgetExceeded` when
+    /// `budget` runs out. It's checked before each solver starts, so the
+    /// solvers should also use a clone of `budget` to be stopped in the
+    /// middle of a search.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
     }
 }
 
@@ -27,12 +39,19 @@ impl UniquenessChecker for NaiveUniquenessChecker {
             let mut found = 0;
             for c in board.candidates(pt, false) {
                 b.set(pt, c)?;
+                if let Some(budget) = &self.budget {
+                    budget.check()?;
+                }
                 let s = factory();
-                if s.solve(&b, false).is_ok() {
-                    if found != 0 {
-                        return NumprError::multiple_solutions();
+                match s.solve(&b, false) {
+                    Ok(_) => {
+                        if found != 0 {
+                            return NumprError::multiple_solutions();
+                        }
+                        found = c;
                     }
-                    found = c;
+                    Err(NumprError::BudgetExceeded) => return NumprError::budget_exceeded(),
+                    Err(_) => {}
                 }
             }
             if found == 0 {
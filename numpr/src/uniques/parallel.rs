@@ -0,0 +1,136 @@
+use crate::board::Board;
+use crate::budget::Budget;
+use crate::error::{NumprError, NumprResult};
+use crate::pt::Pt;
+use crate::solver::Solver;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Checks if the given puzzle has a unique solution in the same way as
+/// [`NaiveUniquenessChecker`](struct.NaiveUniquenessChecker.html), but tries
+/// candidates of all the empty grids in parallel.
+///
+/// All the threads stop as soon as a grid turns out to have no candidate or
+/// more than one candidate leading to an answer.
+///
+/// It doesn't implement [`UniquenessChecker`](../trait.UniquenessChecker.html)
+/// because solvers are created from several threads, which needs the factory
+/// to be `Sync`.
+///
+/// This checker is available with the `parallel` feature.
+#[derive(Default)]
+pub struct ParallelUniquenessChecker {
+    budget: Option<Budget>,
+}
+
+impl ParallelUniquenessChecker {
+    /// Returns a new `ParallelUniquenessChecker` running on the global rayon
+    /// thread pool.
+    pub fn new() -> Self {
+        Self { budget: None }
+    }
+
+    /// Returns the checker failing with `NumprError::BudgetExceeded` when
+    /// `budget` runs out. It's checked before each solver starts, so the
+    /// solvers should also use a clone of `budget` to be stopped in the
+    /// middle of a search.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Checks if `board` has exactly one answer in the same way as
+    /// [`UniquenessChecker::check`](../trait.UniquenessChecker.html#tymethod.check).
+    pub fn check<S>(self, board: &Board, factory: impl Fn() -> S + Sync) -> NumprResult<()>
+    where
+        S: Solver,
+    {
+        let stop = AtomicBool::new(false);
+        let exceeded = AtomicBool::new(false);
+        let empty: Vec<Pt> = board
+            .iter()
+            .filter(|(_, n)| n.is_none())
+            .map(|(pt, _)| pt)
+            .collect();
+
+        empty.par_iter().try_for_each(|&pt| {
+            if stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            let candidates: Vec<u8> = board.candidates(pt, false).into_iter().collect();
+            let found = AtomicUsize::new(0);
+            candidates.par_iter().for_each(|&c| {
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                let mut b = *board;
+                b.set(pt, c).unwrap();
+                let res = match &self.budget {
+                    Some(budget) => budget.check().and_then(|_| factory().solve(&b, false)),
+                    None => factory().solve(&b, false),
+                };
+                match res {
+                    Ok(_) => {
+                        if found.fetch_add(1, Ordering::Relaxed) > 0 {
+                            stop.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    Err(NumprError::BudgetExceeded) => {
+                        exceeded.store(true, Ordering::Relaxed);
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                    Err(_) => {}
+                }
+            });
+
+            let res = match found.load(Ordering::Relaxed) {
+                n if n > 1 => NumprError::multiple_solutions(),
+                _ if exceeded.load(Ordering::Relaxed) => NumprError::budget_exceeded(),
+                // Another grid stopped the search before this grid was done.
+                _ if stop.load(Ordering::Relaxed) => Ok(()),
+                0 => NumprError::unsolvable(),
+                _ => Ok(()),
+            };
+            if res.is_err() {
+                stop.store(true, Ordering::Relaxed);
+            }
+            res
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::NaiveGenerator;
+    use crate::solvers::HeuristicSolver;
+    use crate::uniques::NaiveUniquenessChecker;
+    use crate::{Generator, UniquenessChecker};
+
+    #[test]
+    fn default() {
+        let u = ParallelUniquenessChecker::new();
+        assert!(u.check(&Board::default(), HeuristicSolver::new).is_err());
+    }
+
+    #[test]
+    fn same_as_naive() {
+        let answer = HeuristicSolver::new()
+            .solve(&Board::default(), true)
+            .unwrap();
+        let puzzle = NaiveGenerator::new(crate::consts::SIZE as u32)
+            .generate(&answer, HeuristicSolver::new, NaiveUniquenessChecker::new)
+            .unwrap();
+        ParallelUniquenessChecker::new()
+            .check(&puzzle, HeuristicSolver::new)
+            .unwrap();
+
+        for (pt, _) in puzzle.iter().filter(|(_, n)| n.is_some()).take(10) {
+            let mut b = puzzle;
+            b.set(pt, 0).unwrap();
+            let naive = NaiveUniquenessChecker::new().check(&b, HeuristicSolver::new);
+            let parallel = ParallelUniquenessChecker::new().check(&b, HeuristicSolver::new);
+            assert_eq!(naive.is_ok(), parallel.is_ok());
+        }
+    }
+}